@@ -25,6 +25,12 @@ fn main() {
         }
     }
 
+    // tcmalloc is provided by the system (e.g. from gperftools packages) rather
+    // than a -sys crate, so we need to link it ourselves.
+    if std::env::var("CARGO_FEATURE_ALLOCATOR_TCMALLOC").is_ok() {
+        println!("cargo:rustc-link-lib=tcmalloc");
+    }
+
     let interpreter_config = pyo3_build_config::get();
 
     // Re-export the path to the configured Python interpreter. Tests can
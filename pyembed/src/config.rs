@@ -9,8 +9,8 @@ use {
     oxidized_importer::{PackedResourcesSource, PythonResourcesState},
     pyo3::ffi as pyffi,
     python_packaging::interpreter::{
-        MemoryAllocatorBackend, MultiprocessingStartMethod, PythonInterpreterConfig,
-        PythonInterpreterProfile, TerminfoResolution,
+        AllocatorChain, MemoryAllocatorBackend, MultiprocessingStartMethod,
+        PythonInterpreterConfig, PythonInterpreterProfile, TerminfoResolution,
     },
     std::{
         ffi::{CString, OsString},
@@ -458,11 +458,26 @@ impl<'a> Default for OxidizedPythonInterpreterConfig<'a> {
     }
 }
 
+/// Whether a Cargo feature enabling a memory allocator is enabled in this build.
+///
+/// This checks the same conditions as the allocator bindings in `pyalloc`.
+fn allocator_feature_enabled(feature: &str) -> bool {
+    match feature {
+        "allocator-jemalloc" => cfg!(feature = "jemalloc-sys"),
+        "allocator-mimalloc" => cfg!(feature = "libmimalloc-sys"),
+        "allocator-snmalloc" => cfg!(feature = "snmalloc-sys"),
+        "allocator-tcmalloc" => cfg!(feature = "allocator-tcmalloc"),
+        _ => false,
+    }
+}
+
 impl<'a> OxidizedPythonInterpreterConfig<'a> {
     /// Create a new type with all values resolved.
     pub fn resolve(
         self,
     ) -> Result<ResolvedOxidizedPythonInterpreterConfig<'a>, NewInterpreterError> {
+        let effective_allocator_backend = self.effective_allocator_backend();
+
         let argv = if let Some(args) = self.argv {
             Some(args)
         } else if self.interpreter_config.argv.is_some() {
@@ -471,6 +486,34 @@ impl<'a> OxidizedPythonInterpreterConfig<'a> {
             Some(std::env::args_os().collect::<Vec<_>>())
         };
 
+        // Reject contradictory settings before they get to CPython. The argv
+        // we pass to the interpreter may not come from the interpreter config.
+        // So validate against the effective argv.
+        PythonInterpreterConfig {
+            argv: self
+                .interpreter_config
+                .argv
+                .clone()
+                .or_else(|| argv.clone()),
+            ..self.interpreter_config.clone()
+        }
+        .validate_with_allocator_backend(&effective_allocator_backend)
+        .map_err(|errors| {
+            NewInterpreterError::Dynamic(format!(
+                "invalid Python interpreter configuration: {}",
+                errors.join("; ")
+            ))
+        })?;
+
+        if let Some(feature) = self.allocator_backend.requires_feature() {
+            if !allocator_feature_enabled(feature) {
+                return Err(NewInterpreterError::Dynamic(format!(
+                    "{} allocator backend requested but it isn't compiled into this build configuration; try `cargo build --features {}`",
+                    self.allocator_backend, feature
+                )));
+            }
+        }
+
         let exe = if let Some(exe) = self.exe {
             exe
         } else {
@@ -541,6 +584,39 @@ impl<'a> OxidizedPythonInterpreterConfig<'a> {
             },
         })
     }
+
+    /// The memory allocator backend that replaces CPython's allocator.
+    ///
+    /// The backend replaces CPython's allocator (and any debug hooks it
+    /// installed) only for the memory domains it is installed for. Installing
+    /// it for the `pymalloc` arena doesn't replace anything CPython set up.
+    /// And `allocator_debug` reinstalls debug hooks on top of it. If nothing
+    /// is replaced, [MemoryAllocatorBackend::Default] is returned.
+    fn effective_allocator_backend(&self) -> MemoryAllocatorBackend {
+        if !self.allocator_debug && (self.allocator_raw || self.allocator_mem || self.allocator_obj)
+        {
+            self.allocator_backend
+        } else {
+            MemoryAllocatorBackend::Default
+        }
+    }
+
+    /// Describe the memory allocators this configuration uses.
+    ///
+    /// An unset [PythonInterpreterConfig::allocator] resolves to
+    /// [crate::Allocator::NotSet]. The Rust layer is the backend that
+    /// replaces CPython's allocator, as also used by [Self::resolve()] to
+    /// validate the configuration. So [AllocatorChain::is_coherent()] agrees
+    /// with whether [Self::resolve()] accepts the allocator settings.
+    ///
+    /// Whether the backend is compiled into the current binary isn't
+    /// checked.
+    pub fn active_allocator_chain(&self) -> AllocatorChain {
+        AllocatorChain {
+            cpython_layer: self.interpreter_config.allocator.unwrap_or_default(),
+            rust_layer: self.effective_allocator_backend(),
+        }
+    }
 }
 
 /// An `OxidizedPythonInterpreterConfig` that has fields resolved.
@@ -9,7 +9,6 @@ use {
         config::{OxidizedPythonInterpreterConfig, ResolvedOxidizedPythonInterpreterConfig},
         conversion::osstring_to_bytes,
         error::NewInterpreterError,
-        osutils::resolve_terminfo_dirs,
         pyalloc::PythonMemoryAllocator,
     },
     once_cell::sync::Lazy,
@@ -22,7 +21,7 @@ use {
         exceptions::PyRuntimeError, ffi as pyffi, prelude::*, types::PyDict, AsPyPointer,
         PyTypeInfo,
     },
-    python_packaging::interpreter::{MultiprocessingStartMethod, TerminfoResolution},
+    python_packaging::interpreter::OsInfo,
     std::{
         collections::BTreeSet,
         env, fs,
@@ -103,16 +102,8 @@ impl<'interpreter, 'resources> MainPythonInterpreter<'interpreter, 'resources> {
     ) -> Result<MainPythonInterpreter<'interpreter, 'resources>, NewInterpreterError> {
         let config: ResolvedOxidizedPythonInterpreterConfig<'resources> = config.try_into()?;
 
-        match config.terminfo_resolution {
-            TerminfoResolution::Dynamic => {
-                if let Some(v) = resolve_terminfo_dirs() {
-                    env::set_var("TERMINFO_DIRS", v);
-                }
-            }
-            TerminfoResolution::Static(ref v) => {
-                env::set_var("TERMINFO_DIRS", v);
-            }
-            TerminfoResolution::None => {}
+        if let Some(v) = config.terminfo_resolution.resolve_dirs() {
+            env::set_var("TERMINFO_DIRS", v);
         }
 
         let mut res = MainPythonInterpreter {
@@ -272,29 +263,14 @@ impl<'interpreter, 'resources> MainPythonInterpreter<'interpreter, 'resources> {
             NewInterpreterError::new_from_pyerr(py, err, "import of oxidized importer module")
         })?;
 
-        let cb = |importer_state: &mut ImporterState| match self.config.multiprocessing_start_method
-        {
-            MultiprocessingStartMethod::None => {}
-            MultiprocessingStartMethod::Fork
-            | MultiprocessingStartMethod::ForkServer
-            | MultiprocessingStartMethod::Spawn => {
-                importer_state.set_multiprocessing_set_start_method(Some(
-                    self.config.multiprocessing_start_method.to_string(),
-                ));
-            }
-            MultiprocessingStartMethod::Auto => {
-                // Windows uses "spawn" because "fork" isn't available.
-                // Everywhere else uses "fork." The default on macOS is "spawn." This
-                // is due to https://bugs.python.org/issue33725, which only affects
-                // Python framework builds. Our assumption is we aren't using a Python
-                // framework, so "spawn" is safe.
-                let method = if cfg!(target_family = "windows") {
-                    "spawn"
-                } else {
-                    "fork"
-                };
+        let cb = |importer_state: &mut ImporterState| {
+            let method = self
+                .config
+                .multiprocessing_start_method
+                .resolve(&OsInfo::current());
 
-                importer_state.set_multiprocessing_set_start_method(Some(method.to_string()));
+            if let Some(value) = method.to_python_str() {
+                importer_state.set_multiprocessing_set_start_method(Some(value.to_string()));
             }
         };
 
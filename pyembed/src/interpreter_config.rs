@@ -249,6 +249,44 @@ pub fn set_argv(
     }
 }
 
+/// Apply the `PyPreConfig` fields of a [PythonInterpreterConfig] to a `PyPreConfig`.
+///
+/// `pre_config` should already be initialized, e.g. via
+/// `PyPreConfig_InitIsolatedConfig()`. Only fields that are set in `value`
+/// are written. [PythonInterpreterConfig::profile] isn't consulted.
+pub fn to_raw_pypreconfig(value: &PythonInterpreterConfig, pre_config: &mut pyffi::PyPreConfig) {
+    if let Some(parse_argv) = value.parse_argv {
+        pre_config.parse_argv = if parse_argv { 1 } else { 0 };
+    }
+    if let Some(isolated) = value.isolated {
+        pre_config.isolated = if isolated { 1 } else { 0 };
+    }
+    if let Some(use_environment) = value.use_environment {
+        pre_config.use_environment = if use_environment { 1 } else { 0 };
+    }
+    if let Some(configure_locale) = value.configure_locale {
+        pre_config.configure_locale = if configure_locale { 1 } else { 0 };
+    }
+    if let Some(coerce_c_locale) = value.coerce_c_locale {
+        pre_config.coerce_c_locale = coerce_c_locale.as_int();
+    }
+    if let Some(coerce_c_locale_warn) = value.coerce_c_locale_warn {
+        pre_config.coerce_c_locale_warn = if coerce_c_locale_warn { 1 } else { 0 };
+    }
+    if let Some(legacy_windows_fs_encoding) = value.legacy_windows_fs_encoding {
+        set_windows_fs_encoding(pre_config, legacy_windows_fs_encoding);
+    }
+    if let Some(utf8_mode) = value.utf8_mode {
+        pre_config.utf8_mode = if utf8_mode { 1 } else { 0 };
+    }
+    if let Some(dev_mode) = value.development_mode {
+        pre_config.dev_mode = if dev_mode { 1 } else { 0 };
+    }
+    if let Some(allocator) = value.allocator {
+        pre_config.allocator = allocator as c_int;
+    }
+}
+
 impl<'a> TryFrom<&ResolvedOxidizedPythonInterpreterConfig<'a>> for pyffi::PyPreConfig {
     type Error = NewInterpreterError;
 
@@ -264,55 +302,30 @@ impl<'a> TryFrom<&ResolvedOxidizedPythonInterpreterConfig<'a>> for pyffi::PyPreC
                 PythonInterpreterProfile::Isolated => {
                     pyffi::PyPreConfig_InitIsolatedConfig(&mut pre_config)
                 }
+                profile => {
+                    unreachable!("unhandled PythonInterpreterProfile variant: {:?}", profile)
+                }
             }
         }
 
-        if let Some(parse_argv) = value.parse_argv {
-            pre_config.parse_argv = if parse_argv { 1 } else { 0 };
-        }
-        if let Some(isolated) = value.isolated {
-            pre_config.isolated = if isolated { 1 } else { 0 };
-        }
-        if let Some(use_environment) = value.use_environment {
-            pre_config.use_environment = if use_environment { 1 } else { 0 };
-        }
-        if let Some(configure_locale) = value.configure_locale {
-            pre_config.configure_locale = if configure_locale { 1 } else { 0 };
-        }
-        if let Some(coerce_c_locale) = value.coerce_c_locale {
-            pre_config.coerce_c_locale = coerce_c_locale as c_int;
-        }
-        if let Some(coerce_c_locale_warn) = value.coerce_c_locale_warn {
-            pre_config.coerce_c_locale_warn = if coerce_c_locale_warn { 1 } else { 0 };
-        }
-        if let Some(legacy_windows_fs_encoding) = value.legacy_windows_fs_encoding {
-            set_windows_fs_encoding(&mut pre_config, legacy_windows_fs_encoding);
-        }
-        if let Some(utf8_mode) = value.utf8_mode {
-            pre_config.utf8_mode = if utf8_mode { 1 } else { 0 };
-        }
-        if let Some(dev_mode) = value.development_mode {
-            pre_config.dev_mode = if dev_mode { 1 } else { 0 };
-        }
-        if let Some(allocator) = value.allocator {
-            pre_config.allocator = allocator as c_int;
-        }
+        to_raw_pypreconfig(value, &mut pre_config);
 
         Ok(pre_config)
     }
 }
 
-pub fn python_interpreter_config_to_py_config(
+/// Apply the `PyConfig` fields of a [PythonInterpreterConfig] to a `PyConfig`.
+///
+/// `config` should already be initialized, e.g. via
+/// `PyConfig_InitIsolatedConfig()`. Only fields that are set in `value` are
+/// written. [PythonInterpreterConfig::profile] isn't consulted.
+///
+/// String fields are converted to the platform's `wchar_t` representation:
+/// UTF-16 on Windows and the locale encoding elsewhere.
+pub fn to_raw_pyconfig(
     value: &PythonInterpreterConfig,
-) -> Result<pyffi::PyConfig, NewInterpreterError> {
-    let mut config: pyffi::PyConfig = unsafe { std::mem::zeroed() };
-    unsafe {
-        match value.profile {
-            PythonInterpreterProfile::Isolated => pyffi::PyConfig_InitIsolatedConfig(&mut config),
-            PythonInterpreterProfile::Python => pyffi::PyConfig_InitPythonConfig(&mut config),
-        }
-    }
-
+    config: &mut pyffi::PyConfig,
+) -> Result<(), NewInterpreterError> {
     if let Some(isolated) = value.isolated {
         config.isolated = if isolated { 1 } else { 0 };
     }
@@ -349,7 +362,7 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(filesystem_encoding) = &value.filesystem_encoding {
         set_config_string_from_str(
-            &config,
+            config,
             &config.filesystem_encoding,
             filesystem_encoding,
             "setting filesystem_encoding",
@@ -357,7 +370,7 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(filesystem_errors) = &value.filesystem_errors {
         set_config_string_from_str(
-            &config,
+            config,
             &config.filesystem_errors,
             filesystem_errors,
             "setting filesystem_errors",
@@ -365,7 +378,7 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(pycache_prefix) = &value.pycache_prefix {
         set_config_string_from_path(
-            &config,
+            config,
             &config.pycache_prefix,
             pycache_prefix,
             "setting pycache_prefix",
@@ -375,11 +388,11 @@ pub fn python_interpreter_config_to_py_config(
         config.parse_argv = if parse_argv { 1 } else { 0 };
     }
     if let Some(argv) = &value.argv {
-        set_argv(&mut config, argv)?;
+        set_argv(config, argv)?;
     }
     if let Some(program_name) = &value.program_name {
         set_config_string_from_path(
-            &config,
+            config,
             &config.program_name,
             program_name,
             "setting program_name",
@@ -441,7 +454,7 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(stdio_encoding) = &value.stdio_encoding {
         set_config_string_from_str(
-            &config,
+            config,
             &config.stdio_encoding,
             stdio_encoding,
             "setting stdio_encoding",
@@ -449,24 +462,25 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(stdio_errors) = &value.stdio_errors {
         set_config_string_from_str(
-            &config,
+            config,
             &config.stdio_errors,
             stdio_errors,
             "setting stdio_errors",
         )?;
     }
     if let Some(legacy_windows_stdio) = value.legacy_windows_stdio {
-        set_legacy_windows_stdio(&mut config, legacy_windows_stdio);
+        set_legacy_windows_stdio(config, legacy_windows_stdio);
     }
 
     if let Some(check_hash_pycs_mode) = value.check_hash_pycs_mode {
         set_config_string_from_str(
-            &config,
+            config,
             &config.check_hash_pycs_mode,
             match check_hash_pycs_mode {
                 CheckHashPycsMode::Always => "always",
                 CheckHashPycsMode::Never => "never",
                 CheckHashPycsMode::Default => "default",
+                mode => unreachable!("unhandled CheckHashPycsMode variant: {:?}", mode),
             },
             "setting check_hash_pycs_mode",
         )?;
@@ -476,7 +490,7 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(python_path_env) = &value.python_path_env {
         set_config_string_from_str(
-            &config,
+            config,
             &config.pythonpath_env,
             python_path_env,
             "setting pythonpath_env",
@@ -484,7 +498,7 @@ pub fn python_interpreter_config_to_py_config(
     }
 
     if let Some(home) = &value.home {
-        set_config_string_from_path(&config, &config.home, home, "setting home")?;
+        set_config_string_from_path(config, &config.home, home, "setting home")?;
     }
     if let Some(module_search_paths) = &value.module_search_paths {
         config.module_search_paths_set = 1;
@@ -498,27 +512,22 @@ pub fn python_interpreter_config_to_py_config(
         }
     }
     if let Some(executable) = &value.executable {
-        set_config_string_from_path(
-            &config,
-            &config.executable,
-            executable,
-            "setting executable",
-        )?;
+        set_config_string_from_path(config, &config.executable, executable, "setting executable")?;
     }
     if let Some(base_executable) = &value.base_executable {
         set_config_string_from_path(
-            &config,
+            config,
             &config.base_executable,
             base_executable,
             "setting base_executable",
         )?;
     }
     if let Some(prefix) = &value.prefix {
-        set_config_string_from_path(&config, &config.prefix, prefix, "setting prefix")?;
+        set_config_string_from_path(config, &config.prefix, prefix, "setting prefix")?;
     }
     if let Some(base_prefix) = &value.base_prefix {
         set_config_string_from_path(
-            &config,
+            config,
             &config.base_prefix,
             base_prefix,
             "setting base_prefix",
@@ -526,7 +535,7 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(exec_prefix) = &value.exec_prefix {
         set_config_string_from_path(
-            &config,
+            config,
             &config.exec_prefix,
             exec_prefix,
             "setting exec_prefix",
@@ -534,7 +543,7 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(base_exec_prefix) = &value.base_exec_prefix {
         set_config_string_from_path(
-            &config,
+            config,
             &config.base_exec_prefix,
             base_exec_prefix,
             "setting base_exec_prefix",
@@ -545,29 +554,41 @@ pub fn python_interpreter_config_to_py_config(
     }
     if let Some(run_command) = &value.run_command {
         set_config_string_from_str(
-            &config,
+            config,
             &config.run_command,
             run_command,
             "setting run_command",
         )?;
     }
     if let Some(run_module) = &value.run_module {
-        set_config_string_from_str(
-            &config,
-            &config.run_module,
-            run_module,
-            "setting run_module",
-        )?;
+        set_config_string_from_str(config, &config.run_module, run_module, "setting run_module")?;
     }
     if let Some(run_filename) = &value.run_filename {
         set_config_string_from_path(
-            &config,
+            config,
             &config.run_filename,
             run_filename,
             "setting run_filename",
         )?;
     }
 
+    Ok(())
+}
+
+pub fn python_interpreter_config_to_py_config(
+    value: &PythonInterpreterConfig,
+) -> Result<pyffi::PyConfig, NewInterpreterError> {
+    let mut config: pyffi::PyConfig = unsafe { std::mem::zeroed() };
+    unsafe {
+        match value.profile {
+            PythonInterpreterProfile::Isolated => pyffi::PyConfig_InitIsolatedConfig(&mut config),
+            PythonInterpreterProfile::Python => pyffi::PyConfig_InitPythonConfig(&mut config),
+            profile => unreachable!("unhandled PythonInterpreterProfile variant: {:?}", profile),
+        }
+    }
+
+    to_raw_pyconfig(value, &mut config)?;
+
     Ok(config)
 }
 
@@ -48,6 +48,12 @@ The optional `allocator-snmalloc` feature controls support for using
 [snmalloc](https://github.com/microsoft/snmalloc) as Python's memory allocator.
 The feature behaves similarly to `jemalloc`, which is documented above.
 
+The optional `allocator-tcmalloc` feature controls support for using
+[tcmalloc](https://github.com/gperftools/gperftools) as Python's memory
+allocator. Unlike the other allocators, tcmalloc isn't built from source:
+the feature links against a `libtcmalloc` library that must be present on
+the build machine.
+
 The optional `serialization` feature controls whether configuration types
 (such as [OxidizedPythonInterpreterConfig]) implement `Serialize` and
 `Deserialize`.
@@ -59,7 +65,6 @@ mod conversion;
 mod error;
 mod interpreter;
 mod interpreter_config;
-mod osutils;
 mod pyalloc;
 pub mod technotes;
 #[cfg(test)]
@@ -74,14 +79,15 @@ pub use {
         },
         error::NewInterpreterError,
         interpreter::MainPythonInterpreter,
+        interpreter_config::{to_raw_pyconfig, to_raw_pypreconfig},
         pyalloc::PythonMemoryAllocator,
     },
     oxidized_importer::{PackedResourcesSource, PythonResourcesState},
     python_packaging::{
         interpreter::{
-            Allocator, BytesWarning, CheckHashPycsMode, CoerceCLocale, MemoryAllocatorBackend,
-            MultiprocessingStartMethod, PythonInterpreterConfig, PythonInterpreterProfile,
-            TerminfoResolution,
+            Allocator, AllocatorChain, BytesWarning, CheckHashPycsMode, CoerceCLocale,
+            MemoryAllocatorBackend, MultiprocessingStartMethod, PythonInterpreterConfig,
+            PythonInterpreterProfile, TerminfoResolution,
         },
         resource::BytecodeOptimizationLevel,
     },
@@ -72,13 +72,13 @@ to.)
 
 # Support for Custom Allocators
 
-We support `jemalloc`, `mimalloc`, `snmalloc`, and Rust's global allocator as
-custom Python allocators.
+We support `jemalloc`, `mimalloc`, `snmalloc`, `tcmalloc`, and Rust's global
+allocator as custom Python allocators.
 
 Rust's global allocator can independently also be set to one of the aforementioned
 custom allocators via external Rust code.
 
-Our `jemalloc`, `mimalloc`, and `snmalloc` Python allocator bindings speak
+Our `jemalloc`, `mimalloc`, `snmalloc`, and `tcmalloc` Python allocator bindings speak
 directly to the underlying C APIs provided by these allocators. By contrast,
 going through the Rust global allocator introduces an abstraction layer. This
 abstraction layer adds overhead (as we need to track allocation sizes to appease
@@ -102,6 +102,17 @@ use {
 
 const MIN_ALIGN: usize = 16;
 
+// gperftools exports `tc_` prefixed variants of the standard allocation
+// functions. We bind to those so we don't depend on tcmalloc overriding
+// the libc symbols.
+#[cfg(feature = "allocator-tcmalloc")]
+extern "C" {
+    fn tc_malloc(size: usize) -> *mut c_void;
+    fn tc_calloc(nelem: usize, elsize: usize) -> *mut c_void;
+    fn tc_realloc(ptr: *mut c_void, size: usize) -> *mut c_void;
+    fn tc_free(ptr: *mut c_void);
+}
+
 /// Tracks allocations from an allocator.
 ///
 /// Some allocators need to pass the original allocation size and alignment
@@ -251,6 +262,16 @@ extern "C" fn snmalloc_malloc(_ctx: *mut c_void, size: usize) -> *mut c_void {
     unsafe { snmalloc_sys::sn_malloc(size) as *mut _ }
 }
 
+#[cfg(feature = "allocator-tcmalloc")]
+extern "C" fn tcmalloc_malloc(_ctx: *mut c_void, size: usize) -> *mut c_void {
+    let size = match size {
+        0 => 1,
+        val => val,
+    };
+
+    unsafe { tc_malloc(size) }
+}
+
 extern "C" fn rust_calloc(ctx: *mut c_void, nelem: usize, elsize: usize) -> *mut c_void {
     let size = match nelem * elsize {
         0 => 1,
@@ -297,6 +318,16 @@ extern "C" fn snmalloc_calloc(_ctx: *mut c_void, nelem: usize, elsize: usize) ->
     unsafe { snmalloc_sys::sn_calloc(nelem, size) as *mut _ }
 }
 
+#[cfg(feature = "allocator-tcmalloc")]
+extern "C" fn tcmalloc_calloc(_ctx: *mut c_void, nelem: usize, elsize: usize) -> *mut c_void {
+    let (nelem, elsize) = match nelem * elsize {
+        0 => (1, 1),
+        _ => (nelem, elsize),
+    };
+
+    unsafe { tc_calloc(nelem, elsize) }
+}
+
 extern "C" fn rust_realloc(ctx: *mut c_void, ptr: *mut c_void, new_size: usize) -> *mut c_void {
     if ptr.is_null() {
         return rust_malloc(ctx, new_size);
@@ -361,6 +392,20 @@ extern "C" fn snmalloc_realloc(ctx: *mut c_void, ptr: *mut c_void, new_size: usi
     unsafe { snmalloc_sys::sn_realloc(ptr as *mut _, new_size) as *mut _ }
 }
 
+#[cfg(feature = "allocator-tcmalloc")]
+extern "C" fn tcmalloc_realloc(ctx: *mut c_void, ptr: *mut c_void, new_size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return tcmalloc_malloc(ctx, new_size);
+    }
+
+    let new_size = match new_size {
+        0 => 1,
+        val => val,
+    };
+
+    unsafe { tc_realloc(ptr, new_size) }
+}
+
 extern "C" fn rust_free(ctx: *mut c_void, ptr: *mut c_void) {
     if ptr.is_null() {
         return;
@@ -406,6 +451,15 @@ extern "C" fn snmalloc_free(_ctx: *mut c_void, ptr: *mut c_void) {
     unsafe { snmalloc_sys::sn_free(ptr as *mut _) }
 }
 
+#[cfg(feature = "allocator-tcmalloc")]
+extern "C" fn tcmalloc_free(_ctx: *mut c_void, ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+
+    unsafe { tc_free(ptr) }
+}
+
 extern "C" fn rust_arena_free(ctx: *mut c_void, ptr: *mut c_void, _size: usize) {
     if ptr.is_null() {
         return;
@@ -451,6 +505,15 @@ extern "C" fn snmalloc_arena_free(_ctx: *mut c_void, ptr: *mut c_void, _size: us
     unsafe { snmalloc_sys::sn_free(ptr as *mut _) }
 }
 
+#[cfg(feature = "allocator-tcmalloc")]
+extern "C" fn tcmalloc_arena_free(_ctx: *mut c_void, ptr: *mut c_void, _size: usize) {
+    if ptr.is_null() {
+        return;
+    }
+
+    unsafe { tc_free(ptr) }
+}
+
 /// Represents a `PyMemAllocatorEx` that can be installed as a memory allocator.
 enum AllocatorInstance {
     /// Backed by a `PyMemAllocatorEx` struct.
@@ -480,7 +543,9 @@ impl PythonMemoryAllocator {
             MemoryAllocatorBackend::Jemalloc => Some(Self::jemalloc()),
             MemoryAllocatorBackend::Mimalloc => Some(Self::mimalloc()),
             MemoryAllocatorBackend::Snmalloc => Some(Self::snmalloc()),
+            MemoryAllocatorBackend::Tcmalloc => Some(Self::tcmalloc()),
             MemoryAllocatorBackend::Rust => Some(Self::rust()),
+            backend => unreachable!("unhandled MemoryAllocatorBackend variant: {:?}", backend),
         }
     }
 
@@ -593,6 +658,33 @@ impl PythonMemoryAllocator {
         panic!("snmalloc allocator requested but it isn't compiled into this build configuration; try `cargo build --features allocator-snmalloc`");
     }
 
+    /// Construct a new instance using tcmalloc.
+    #[cfg(feature = "allocator-tcmalloc")]
+    pub fn tcmalloc() -> Self {
+        Self {
+            backend: MemoryAllocatorBackend::Tcmalloc,
+            instance: AllocatorInstance::Simple(
+                pyffi::PyMemAllocatorEx {
+                    ctx: std::ptr::null_mut(),
+                    malloc: Some(tcmalloc_malloc),
+                    calloc: Some(tcmalloc_calloc),
+                    realloc: Some(tcmalloc_realloc),
+                    free: Some(tcmalloc_free),
+                },
+                pyffi::PyObjectArenaAllocator {
+                    ctx: std::ptr::null_mut(),
+                    alloc: Some(tcmalloc_malloc),
+                    free: Some(tcmalloc_arena_free),
+                },
+            ),
+        }
+    }
+
+    #[cfg(not(feature = "allocator-tcmalloc"))]
+    pub fn tcmalloc() -> Self {
+        panic!("tcmalloc allocator requested but it isn't compiled into this build configuration; try `cargo build --features allocator-tcmalloc`");
+    }
+
     /// Obtain the backend used for this instance.
     #[allow(unused)]
     pub fn backend(&self) -> MemoryAllocatorBackend {
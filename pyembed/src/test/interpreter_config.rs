@@ -4,14 +4,20 @@
 
 use {
     super::{default_interpreter_config, set_sys_paths, PYTHON_INTERPRETER_PATH},
-    crate::{MainPythonInterpreter, OxidizedPythonInterpreterConfig},
+    crate::{
+        to_raw_pyconfig, to_raw_pypreconfig, MainPythonInterpreter,
+        OxidizedPythonInterpreterConfig, PythonMemoryAllocator,
+    },
     pyo3::{
         ffi as pyffi,
         prelude::*,
         types::{PyBytes, PyList, PyString, PyStringData},
     },
     python_packaging::{
-        interpreter::{BytesWarning, MemoryAllocatorBackend, PythonInterpreterProfile},
+        interpreter::{
+            Allocator, AllocatorChain, BytesWarning, CheckHashPycsMode, MemoryAllocatorBackend,
+            PythonInterpreterProfile,
+        },
         resource::BytecodeOptimizationLevel,
     },
     rusty_fork::rusty_fork_test,
@@ -179,6 +185,45 @@ rusty_fork_test! {
         assert!(interp.allocator.is_none());
     }
 
+    #[test]
+    fn test_active_allocator_chain() {
+        let mut config = OxidizedPythonInterpreterConfig::default();
+        assert_eq!(
+            config.active_allocator_chain(),
+            AllocatorChain {
+                cpython_layer: Allocator::NotSet,
+                rust_layer: MemoryAllocatorBackend::Default,
+            }
+        );
+
+        config.interpreter_config.allocator = Some(Allocator::PyMalloc);
+        config.allocator_backend = MemoryAllocatorBackend::Jemalloc;
+        let chain = config.active_allocator_chain();
+        assert_eq!(chain.rust_layer, MemoryAllocatorBackend::Jemalloc);
+        assert!(chain.is_coherent());
+
+        config.interpreter_config.allocator = Some(Allocator::PyMallocDebug);
+        assert!(!config.active_allocator_chain().is_coherent());
+
+        // Debug hooks are reinstalled on top of the backend.
+        config.allocator_debug = true;
+        assert_eq!(
+            config.active_allocator_chain().rust_layer,
+            MemoryAllocatorBackend::Default
+        );
+        assert!(config.active_allocator_chain().is_coherent());
+        config.allocator_debug = false;
+
+        // The backend doesn't replace anything if only used for arenas.
+        config.allocator_raw = false;
+        config.allocator_pymalloc_arena = true;
+        assert_eq!(
+            config.active_allocator_chain().rust_layer,
+            MemoryAllocatorBackend::Default
+        );
+        assert!(config.active_allocator_chain().is_coherent());
+    }
+
     #[test]
     fn test_allocator_rust() {
         let mut config = default_interpreter_config();
@@ -301,6 +346,37 @@ rusty_fork_test! {
         assert_eq!(interp.allocator.as_ref().unwrap().backend(), MemoryAllocatorBackend::Snmalloc);
     }
 
+    #[cfg(feature = "allocator-tcmalloc")]
+    #[test]
+    fn test_allocator_tcmalloc() {
+        let mut config = default_interpreter_config();
+
+        config.allocator_backend = MemoryAllocatorBackend::Tcmalloc;
+        config.allocator_raw = true;
+        config.allocator_mem = true;
+        config.allocator_obj = true;
+
+        let interp = MainPythonInterpreter::new(config).unwrap();
+
+        assert!(interp.allocator.is_some());
+        assert_eq!(interp.allocator.as_ref().unwrap().backend(), MemoryAllocatorBackend::Tcmalloc);
+    }
+
+    #[cfg(feature = "allocator-tcmalloc")]
+    #[test]
+    fn test_allocator_tcmalloc_pymalloc_arena() {
+        let mut config = default_interpreter_config();
+
+        config.allocator_backend = MemoryAllocatorBackend::Tcmalloc;
+        config.allocator_raw = true;
+        config.allocator_pymalloc_arena = true;
+
+        let interp = MainPythonInterpreter::new(config).unwrap();
+
+        assert!(interp.allocator.is_some());
+        assert_eq!(interp.allocator.as_ref().unwrap().backend(), MemoryAllocatorBackend::Tcmalloc);
+    }
+
     #[test]
     fn test_allocator_debug() {
         let mut config = default_interpreter_config();
@@ -321,6 +397,130 @@ rusty_fork_test! {
         MainPythonInterpreter::new(config).unwrap();
     }
 
+    #[test]
+    fn test_allocator_debug_variant_custom_backend() {
+        let mut config = default_interpreter_config();
+
+        config.interpreter_config.allocator = Some(Allocator::PyMallocDebug);
+        config.allocator_backend = MemoryAllocatorBackend::Rust;
+        config.allocator_raw = true;
+
+        assert!(config.clone().resolve().is_err());
+
+        config.allocator_debug = true;
+        assert!(config.clone().resolve().is_ok());
+
+        config.allocator_debug = false;
+        config.allocator_raw = false;
+        assert!(config.resolve().is_ok());
+    }
+
+    #[test]
+    fn test_to_raw_config_init_cycle() {
+        let mut config = default_interpreter_config();
+        set_sys_paths(&mut config);
+
+        let mut value = config.interpreter_config;
+        value.argv = Some(vec![OsString::from(PYTHON_INTERPRETER_PATH)]);
+        value.utf8_mode = Some(true);
+        value.optimization_level = Some(BytecodeOptimizationLevel::Two);
+        value.bytes_warning = Some(BytesWarning::Warn);
+        value.x_options = Some(vec!["pyembed_test=1".to_string()]);
+
+        let mut pre_config: pyffi::PyPreConfig = unsafe { std::mem::zeroed() };
+        unsafe { pyffi::PyPreConfig_InitIsolatedConfig(&mut pre_config) };
+        to_raw_pypreconfig(&value, &mut pre_config);
+        assert_eq!(pre_config.utf8_mode, 1);
+        assert_eq!(pre_config.parse_argv, 0);
+
+        let status = unsafe { pyffi::Py_PreInitialize(&pre_config) };
+        assert_eq!(unsafe { pyffi::PyStatus_Exception(status) }, 0);
+
+        let mut py_config: pyffi::PyConfig = unsafe { std::mem::zeroed() };
+        unsafe { pyffi::PyConfig_InitIsolatedConfig(&mut py_config) };
+        to_raw_pyconfig(&value, &mut py_config).unwrap();
+        assert_eq!(py_config.optimization_level, 2);
+        assert_eq!(py_config.bytes_warning, 1);
+        assert_eq!(py_config.site_import, 0);
+
+        let status = unsafe { pyffi::Py_InitializeFromConfig(&py_config) };
+        unsafe { pyffi::PyConfig_Clear(&mut py_config) };
+        assert_eq!(unsafe { pyffi::PyStatus_Exception(status) }, 0);
+
+        Python::with_gil(|py| {
+            let sys = py.import("sys").unwrap();
+            let flags = sys.getattr("flags").unwrap();
+            assert_eq!(flags.getattr("optimize").unwrap().extract::<i64>().unwrap(), 2);
+            assert_eq!(flags.getattr("bytes_warning").unwrap().extract::<i64>().unwrap(), 1);
+            assert_eq!(flags.getattr("utf8_mode").unwrap().extract::<i64>().unwrap(), 1);
+
+            let xoptions = sys.getattr("_xoptions").unwrap();
+            assert_eq!(
+                xoptions.get_item("pyembed_test").unwrap().extract::<String>().unwrap(),
+                "1"
+            );
+        });
+    }
+
+    #[test]
+    fn test_allocator_backend_available_when_enabled() {
+        // A backend compiled into this build must be available on its target.
+        for (backend, enabled) in [
+            (MemoryAllocatorBackend::Default, true),
+            (MemoryAllocatorBackend::Rust, true),
+            (MemoryAllocatorBackend::Jemalloc, cfg!(feature = "jemalloc-sys")),
+            (MemoryAllocatorBackend::Mimalloc, cfg!(feature = "libmimalloc-sys")),
+            (MemoryAllocatorBackend::Snmalloc, cfg!(feature = "snmalloc-sys")),
+            (MemoryAllocatorBackend::Tcmalloc, cfg!(feature = "allocator-tcmalloc")),
+        ] {
+            if enabled {
+                assert!(backend.is_available_on_current_target(), "{}", backend);
+                assert!(backend.compile_time_check().is_ok(), "{}", backend);
+            }
+        }
+    }
+
+    #[test]
+    fn test_allocator_from_backend_all_known() {
+        for backend in MemoryAllocatorBackend::all_known() {
+            match std::panic::catch_unwind(|| PythonMemoryAllocator::from_backend(*backend)) {
+                Ok(Some(allocator)) => assert_eq!(allocator.backend(), *backend),
+                Ok(None) => assert_eq!(*backend, MemoryAllocatorBackend::Default),
+                // Backends not compiled into this build panic. But they must
+                // not hit the fallback for unhandled variants.
+                Err(e) => {
+                    let message = e
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| e.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    assert!(
+                        message.contains("isn't compiled into this build configuration"),
+                        "{}: {}",
+                        backend,
+                        message
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_raw_config_all_known() {
+        for profile in PythonInterpreterProfile::all_known() {
+            for mode in CheckHashPycsMode::all_known() {
+                let mut config = default_interpreter_config();
+                config.interpreter_config.profile = *profile;
+                config.interpreter_config.check_hash_pycs_mode = Some(*mode);
+                let resolved = config.resolve().unwrap();
+
+                pyffi::PyPreConfig::try_from(&resolved).unwrap();
+                let mut py_config: pyffi::PyConfig = (&resolved).try_into().unwrap();
+                unsafe { pyffi::PyConfig_Clear(&mut py_config) };
+            }
+        }
+    }
+
     #[test]
     fn test_sys_paths_origin() {
         let mut config = OxidizedPythonInterpreterConfig::default();
@@ -779,4 +979,29 @@ rusty_fork_test! {
             assert_eq!(flags.getattr("dont_write_bytecode").unwrap().extract::<i64>().unwrap(), 1);
         });
     }
+
+    #[test]
+    fn test_sys_flags_repr() {
+        let mut config = default_interpreter_config();
+        config.interpreter_config.development_mode = Some(true);
+        config.interpreter_config.optimization_level = Some(BytecodeOptimizationLevel::One);
+        config.interpreter_config.bytes_warning = Some(BytesWarning::Warn);
+        config.interpreter_config.utf8_mode = Some(true);
+        config.interpreter_config.write_bytecode = Some(false);
+
+        let expected = config.interpreter_config.clone();
+
+        let interp = MainPythonInterpreter::new(config).unwrap();
+
+        interp.with_gil(|py| {
+            let version = py.version_info();
+            let sys = py.import("sys").unwrap();
+
+            let flags = sys.getattr("flags").unwrap();
+            assert_eq!(
+                flags.repr().unwrap().to_string(),
+                expected.to_sys_flags_repr(version.major as u32, version.minor as u32)
+            );
+        });
+    }
 }
@@ -241,6 +241,9 @@ pub fn cargo_features(exe: &dyn PythonBinaryBuilder) -> Vec<&str> {
         res.push("global-allocator-snmalloc");
         res.push("allocator-snmalloc");
     }
+    if exe.requires_tcmalloc() {
+        res.push("allocator-tcmalloc");
+    }
 
     res
 }
@@ -393,6 +393,9 @@ pub trait PythonBinaryBuilder {
     /// Whether the binary requires the Snmalloc library.
     fn requires_snmalloc(&self) -> bool;
 
+    /// Whether the binary requires the tcmalloc library.
+    fn requires_tcmalloc(&self) -> bool;
+
     /// Obtain software licensing information.
     fn licensed_components(&self) -> Result<LicensedComponents>;
 
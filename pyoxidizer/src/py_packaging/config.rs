@@ -9,13 +9,9 @@ Configuring a Python interpreter.
 use {
     anyhow::Result,
     itertools::Itertools,
-    python_packaging::{
-        interpreter::{
-            Allocator, BytesWarning, CheckHashPycsMode, CoerceCLocale, MemoryAllocatorBackend,
-            MultiprocessingStartMethod, PythonInterpreterConfig, PythonInterpreterProfile,
-            TerminfoResolution,
-        },
-        resource::BytecodeOptimizationLevel,
+    python_packaging::interpreter::{
+        MemoryAllocatorBackend, MultiprocessingStartMethod, PythonInterpreterConfig,
+        PythonInterpreterProfile, TerminfoResolution,
     },
     std::{
         io::Write,
@@ -36,13 +32,6 @@ pub fn default_memory_allocator(target_triple: &str) -> MemoryAllocatorBackend {
     }
 }
 
-fn optional_bool_to_string(value: &Option<bool>) -> String {
-    match value {
-        Some(value) => format!("Some({})", value),
-        None => "None".to_string(),
-    }
-}
-
 fn optional_string_to_string(value: &Option<String>) -> String {
     match value {
         Some(value) => format!("Some(\"{}\".to_string())", value.escape_default()),
@@ -64,20 +53,6 @@ fn optional_pathbuf_to_string(value: &Option<PathBuf>) -> String {
     }
 }
 
-fn optional_vec_string_to_string(value: &Option<Vec<String>>) -> String {
-    match value {
-        Some(value) => format!(
-            "Some(vec![{}])",
-            value
-                .iter()
-                .map(|x| format!("\"{}\".to_string()", x.escape_default()))
-                .collect::<Vec<_>>()
-                .join(", ")
-        ),
-        None => "None".to_string(),
-    }
-}
-
 /// Represents sources for loading packed resources data.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PyembedPackedResourcesSource {
@@ -183,64 +158,7 @@ impl PyembedPythonInterpreterConfig {
             "pyembed::OxidizedPythonInterpreterConfig {{\n    \
             exe: None,\n    \
             origin: None,\n    \
-            interpreter_config: pyembed::PythonInterpreterConfig {{\n        \
-            profile: {},\n        \
-            allocator: {},\n        \
-            configure_locale: {},\n        \
-            coerce_c_locale: {},\n        \
-            coerce_c_locale_warn: {},\n        \
-            development_mode: {},\n        \
-            isolated: {},\n        \
-            legacy_windows_fs_encoding: {},\n        \
-            parse_argv: {},\n        \
-            use_environment: {},\n        \
-            utf8_mode: {},\n        \
-            argv: None,\n        \
-            base_exec_prefix: {},\n        \
-            base_executable: {},\n        \
-            base_prefix: {},\n        \
-            buffered_stdio: {},\n        \
-            bytes_warning: {},\n        \
-            check_hash_pycs_mode: {},\n        \
-            configure_c_stdio: {},\n        \
-            dump_refs: {},\n        \
-            exec_prefix: {},\n        \
-            executable: {},\n        \
-            fault_handler: {},\n        \
-            filesystem_encoding: {},\n        \
-            filesystem_errors: {},\n        \
-            hash_seed: {},\n        \
-            home: {},\n        \
-            import_time: {},\n        \
-            inspect: {},\n        \
-            install_signal_handlers: {},\n        \
-            interactive: {},\n        \
-            legacy_windows_stdio: {},\n        \
-            malloc_stats: {},\n        \
-            module_search_paths: {},\n        \
-            optimization_level: {},\n        \
-            parser_debug: {},\n        \
-            pathconfig_warnings: {},\n        \
-            prefix: {},\n        \
-            program_name: {},\n        \
-            pycache_prefix: {},\n        \
-            python_path_env: {},\n        \
-            quiet: {},\n        \
-            run_command: {},\n        \
-            run_filename: {},\n        \
-            run_module: {},\n        \
-            show_ref_count: {},\n        \
-            site_import: {},\n        \
-            skip_first_source_line: {},\n        \
-            stdio_encoding: {},\n        \
-            stdio_errors: {},\n        \
-            tracemalloc: {},\n        \
-            user_site_directory: {},\n        \
-            verbose: {},\n        \
-            warn_options: {},\n        \
-            write_bytecode: {},\n        \
-            x_options: {},\n        \
-            }},\n    \
+            interpreter_config: {},\n    \
             allocator_backend: {},\n    \
             allocator_raw: {},\n    \
             allocator_mem: {},\n    \
@@ -263,116 +181,18 @@ impl PyembedPythonInterpreterConfig {
             write_modules_directory_env: {},\n    \
             }}\n\
             ",
-            match self.config.profile {
-                PythonInterpreterProfile::Isolated => "pyembed::PythonInterpreterProfile::Isolated",
-                PythonInterpreterProfile::Python => "pyembed::PythonInterpreterProfile::Python",
-            },
-            match self.config.allocator {
-                Some(Allocator::Debug) => "Some(pyembed::Allocator::Debug)",
-                Some(Allocator::Default) => "Some(pyembed::Allocator::Default)",
-                Some(Allocator::Malloc) => "Some(pyembed::Allocator::Malloc)",
-                Some(Allocator::MallocDebug) => "Some(pyembed::Allocator::MallocDebug)",
-                Some(Allocator::NotSet) => "Some(pyembed::Allocator::NotSet)",
-                Some(Allocator::PyMalloc) => "Some(pyembed::Allocator::PyMalloc)",
-                Some(Allocator::PyMallocDebug) => "Some(pyembed::Allocator::PyMallocDebug)",
-                None => "None",
-            },
-            optional_bool_to_string(&self.config.configure_locale),
-            match &self.config.coerce_c_locale {
-                Some(CoerceCLocale::C) => "Some(pyembed::CoerceCLocale::C)",
-                Some(CoerceCLocale::LCCtype) => "Some(pyembed::CoerceCLocale::LCCtype)",
-                None => "None",
-            },
-            optional_bool_to_string(&self.config.coerce_c_locale_warn),
-            optional_bool_to_string(&self.config.development_mode),
-            optional_bool_to_string(&self.config.isolated),
-            optional_bool_to_string(&self.config.legacy_windows_fs_encoding),
-            optional_bool_to_string(&self.config.parse_argv),
-            optional_bool_to_string(&self.config.use_environment),
-            optional_bool_to_string(&self.config.utf8_mode),
-            optional_pathbuf_to_string(&self.config.base_exec_prefix),
-            optional_pathbuf_to_string(&self.config.base_executable),
-            optional_pathbuf_to_string(&self.config.base_prefix),
-            optional_bool_to_string(&self.config.buffered_stdio),
-            match self.config.bytes_warning {
-                Some(BytesWarning::None) => "Some(pyembed::BytesWarning::None)",
-                Some(BytesWarning::Warn) => "Some(pyembed::BytesWarning::Warn)",
-                Some(BytesWarning::Raise) => "Some(pyembed::BytesWarning::Raise)",
-                None => "None",
-            },
-            match self.config.check_hash_pycs_mode {
-                Some(CheckHashPycsMode::Always) => "Some(pyembed::CheckHashPycsMode::Always)",
-                Some(CheckHashPycsMode::Default) => "Some(pyembed::CheckHashPycsMode::Default)",
-                Some(CheckHashPycsMode::Never) => "Some(pyembed::CheckHashPycsMode::Never)",
-                None => "None",
-            },
-            optional_bool_to_string(&self.config.configure_c_stdio),
-            optional_bool_to_string(&self.config.dump_refs),
-            optional_pathbuf_to_string(&self.config.exec_prefix),
-            optional_pathbuf_to_string(&self.config.executable),
-            optional_bool_to_string(&self.config.fault_handler),
-            optional_string_to_string(&self.config.filesystem_encoding),
-            optional_string_to_string(&self.config.filesystem_errors),
-            match &self.config.hash_seed {
-                Some(value) => format!("Some({})", value),
-                None => "None".to_string(),
-            },
-            optional_pathbuf_to_string(&self.config.home),
-            optional_bool_to_string(&self.config.import_time),
-            optional_bool_to_string(&self.config.inspect),
-            optional_bool_to_string(&self.config.install_signal_handlers),
-            optional_bool_to_string(&self.config.interactive),
-            optional_bool_to_string(&self.config.legacy_windows_stdio),
-            optional_bool_to_string(&self.config.malloc_stats),
-            match &self.config.module_search_paths {
-                Some(paths) => {
-                    format!(
-                        "Some(vec![{}])",
-                        paths
-                            .iter()
-                            .map(|p| path_to_string(p.as_path()))
-                            .collect::<Vec<String>>()
-                            .join(", ")
-                    )
-                }
-                None => "None".to_string(),
-            },
-            match self.config.optimization_level {
-                Some(BytecodeOptimizationLevel::Zero) =>
-                    "Some(pyembed::BytecodeOptimizationLevel::Zero)",
-                Some(BytecodeOptimizationLevel::One) =>
-                    "Some(pyembed::BytecodeOptimizationLevel::One)",
-                Some(BytecodeOptimizationLevel::Two) =>
-                    "Some(pyembed::BytecodeOptimizationLevel::Two)",
-                None => "None",
-            },
-            optional_bool_to_string(&self.config.parser_debug),
-            optional_bool_to_string(&self.config.pathconfig_warnings),
-            optional_pathbuf_to_string(&self.config.prefix),
-            optional_pathbuf_to_string(&self.config.program_name),
-            optional_pathbuf_to_string(&self.config.pycache_prefix),
-            optional_string_to_string(&self.config.python_path_env),
-            optional_bool_to_string(&self.config.quiet),
-            optional_string_to_string(&self.config.run_command),
-            optional_pathbuf_to_string(&self.config.run_filename),
-            optional_string_to_string(&self.config.run_module),
-            optional_bool_to_string(&self.config.show_ref_count),
-            optional_bool_to_string(&self.config.site_import),
-            optional_bool_to_string(&self.config.skip_first_source_line),
-            optional_string_to_string(&self.config.stdio_encoding),
-            optional_string_to_string(&self.config.stdio_errors),
-            optional_bool_to_string(&self.config.tracemalloc),
-            optional_bool_to_string(&self.config.user_site_directory),
-            optional_bool_to_string(&self.config.verbose),
-            optional_vec_string_to_string(&self.config.warn_options),
-            optional_bool_to_string(&self.config.write_bytecode),
-            optional_vec_string_to_string(&self.config.x_options),
+            self.config
+                .to_rust_struct_literal("pyembed", "pyembed")
+                .trim_end()
+                .replace('\n', "\n    "),
             match self.allocator_backend {
                 MemoryAllocatorBackend::Jemalloc => "pyembed::MemoryAllocatorBackend::Jemalloc",
                 MemoryAllocatorBackend::Mimalloc => "pyembed::MemoryAllocatorBackend::Mimalloc",
                 MemoryAllocatorBackend::Snmalloc => "pyembed::MemoryAllocatorBackend::Snmalloc",
+                MemoryAllocatorBackend::Tcmalloc => "pyembed::MemoryAllocatorBackend::Tcmalloc",
                 MemoryAllocatorBackend::Rust => "pyembed::MemoryAllocatorBackend::Rust",
                 MemoryAllocatorBackend::Default => "pyembed::MemoryAllocatorBackend::Default",
+                backend => unreachable!("unhandled MemoryAllocatorBackend variant: {:?}", backend),
             },
             self.allocator_raw,
             self.allocator_mem,
@@ -402,6 +222,8 @@ impl PyembedPythonInterpreterConfig {
                     "pyembed::MultiprocessingStartMethod::Spawn".to_string(),
                 MultiprocessingStartMethod::Auto =>
                     "pyembed::MultiprocessingStartMethod::Auto".to_string(),
+                ref method =>
+                    unreachable!("unhandled MultiprocessingStartMethod variant: {:?}", method),
             },
             self.sys_frozen,
             self.sys_meipass,
@@ -411,6 +233,7 @@ impl PyembedPythonInterpreterConfig {
                 TerminfoResolution::Static(ref v) => {
                     format!("pyembed::TerminfoResolution::Static(r###\"{}\"###", v)
                 }
+                ref value => unreachable!("unhandled TerminfoResolution variant: {:?}", value),
             },
             optional_pathbuf_to_string(&self.tcl_library),
             optional_string_to_string(&self.write_modules_directory_env),
@@ -449,7 +272,14 @@ mod tests {
         environment::default_target_triple,
         py_packaging::distribution::{BinaryLibpythonLinkMode, PythonDistribution},
     };
-    use {super::*, crate::testutil::*};
+    use {
+        super::*,
+        crate::testutil::*,
+        python_packaging::{
+            interpreter::{Allocator, BytesWarning, CheckHashPycsMode, CoerceCLocale},
+            resource::BytecodeOptimizationLevel,
+        },
+    };
 
     fn assert_contains(haystack: &str, needle: &str) -> Result<()> {
         assert!(
@@ -515,6 +345,88 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_serialize_interpreter_config() -> Result<()> {
+        let mut config = PyembedPythonInterpreterConfig::default();
+        config.config.allocator = Some(Allocator::Debug);
+        config.config.optimization_level = Some(BytecodeOptimizationLevel::Two);
+
+        let code = config.to_oxidized_python_interpreter_config_rs()?;
+
+        assert_contains(
+            &code,
+            "    interpreter_config: pyembed::PythonInterpreterConfig {\n        profile: pyembed::PythonInterpreterProfile::Isolated,\n",
+        )?;
+        assert_contains(
+            &code,
+            "        allocator: Some(pyembed::Allocator::Debug),\n",
+        )?;
+        assert_contains(
+            &code,
+            "        optimization_level: Some(pyembed::BytecodeOptimizationLevel::Two),\n",
+        )?;
+        assert_contains(&code, "\n    },\n    allocator_backend: ")
+    }
+
+    #[test]
+    fn test_serialize_all_known_variants() -> Result<()> {
+        // The enums are #[non_exhaustive], so matches on them need a fallback
+        // arm. Make sure every variant we know about renders without hitting it.
+        for profile in PythonInterpreterProfile::all_known() {
+            let mut config = PyembedPythonInterpreterConfig::default();
+            config.config.profile = *profile;
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+        for allocator in Allocator::all_known() {
+            let mut config = PyembedPythonInterpreterConfig::default();
+            config.config.allocator = Some(*allocator);
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+        for value in CoerceCLocale::all_known() {
+            let mut config = PyembedPythonInterpreterConfig::default();
+            config.config.coerce_c_locale = Some(*value);
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+        for value in BytesWarning::all_known() {
+            let mut config = PyembedPythonInterpreterConfig::default();
+            config.config.bytes_warning = Some(*value);
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+        for mode in CheckHashPycsMode::all_known() {
+            let mut config = PyembedPythonInterpreterConfig::default();
+            config.config.check_hash_pycs_mode = Some(*mode);
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+        for backend in MemoryAllocatorBackend::all_known() {
+            let config = PyembedPythonInterpreterConfig {
+                allocator_backend: *backend,
+                ..Default::default()
+            };
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+        for method in MultiprocessingStartMethod::all_known() {
+            let config = PyembedPythonInterpreterConfig {
+                multiprocessing_start_method: method.clone(),
+                ..Default::default()
+            };
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+        for resolution in [
+            TerminfoResolution::Dynamic,
+            TerminfoResolution::None,
+            TerminfoResolution::Static("/usr/share/terminfo".to_string()),
+        ] {
+            assert!(resolution.is_known());
+            let config = PyembedPythonInterpreterConfig {
+                terminfo_resolution: resolution,
+                ..Default::default()
+            };
+            config.to_oxidized_python_interpreter_config_rs()?;
+        }
+
+        Ok(())
+    }
+
     // TODO enable once CI has a linkable Python.
     #[test]
     #[ignore]
@@ -28,7 +28,9 @@ use {
     pyo3_build_config::{BuildFlag, BuildFlags, PythonImplementation, PythonVersion},
     python_packaging::{
         bytecode::BytecodeCompiler,
-        interpreter::MemoryAllocatorBackend,
+        interpreter::{
+            MemoryAllocatorBackend, MultiprocessingStartMethod, OsInfo, PythonInterpreterConfig,
+        },
         libpython::LibPythonBuildContext,
         licensing::{
             derive_package_license_infos, ComponentFlavor, LicensedComponent, LicensedComponents,
@@ -872,6 +874,10 @@ impl PythonBinaryBuilder for StandalonePythonExecutableBuilder {
         self.config.allocator_backend == MemoryAllocatorBackend::Snmalloc
     }
 
+    fn requires_tcmalloc(&self) -> bool {
+        self.config.allocator_backend == MemoryAllocatorBackend::Tcmalloc
+    }
+
     fn licensed_components(&self) -> Result<LicensedComponents> {
         Ok(self.resources_collector.normalized_licensed_components())
     }
@@ -897,6 +903,43 @@ impl PythonBinaryBuilder for StandalonePythonExecutableBuilder {
             warn!("See https://github.com/indygreg/PyOxidizer/issues/69 for more");
         }
 
+        // The built binary rejects contradictory interpreter settings when it
+        // starts. Catch them now instead. argv defaults to the process
+        // arguments at run time, so only validate it if it is set explicitly.
+        PythonInterpreterConfig {
+            argv: self.config.config.argv.clone().or_else(|| Some(vec![])),
+            ..self.config.config.clone()
+        }
+        .validate_with_allocator_backend(&self.config.allocator_backend)
+        .map_err(|errors| {
+            anyhow!(
+                "invalid Python interpreter configuration: {}",
+                errors.join("; ")
+            )
+        })?;
+
+        let start_method = &self.config.multiprocessing_start_method;
+        let macos = OsInfo {
+            os: "macos".to_string(),
+            has_fork: true,
+        };
+        if self.target_triple.contains("-apple-darwin")
+            && start_method.resolve(&macos).is_fork_based()
+        {
+            if *start_method == MultiprocessingStartMethod::Auto {
+                warn!(
+                    "multiprocessing_start_method = 'auto' resolves to 'fork' on macOS, which is not fork safe"
+                );
+            } else {
+                warn!(
+                    "multiprocessing_start_method = '{}' is not fork safe on macOS",
+                    start_method
+                );
+            }
+            warn!("consider using 'spawn' instead");
+            warn!("See https://docs.python.org/3/library/multiprocessing.html#contexts-and-start-methods for more");
+        }
+
         let compiled_resources = {
             let temp_dir = env.temporary_directory("pyoxidizer-bytecode-compile")?;
             let mut compiler = BytecodeCompiler::new(self.host_python_exe_path(), temp_dir.path())?;
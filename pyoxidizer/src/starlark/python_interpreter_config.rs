@@ -8,7 +8,7 @@ use {
     python_packaging::{
         interpreter::{
             Allocator, BytesWarning, CheckHashPycsMode, CoerceCLocale, MemoryAllocatorBackend,
-            MultiprocessingStartMethod, PythonInterpreterProfile, TerminfoResolution,
+            MultiprocessingStartMethod, ParseError, PythonInterpreterProfile, TerminfoResolution,
         },
         resource::BytecodeOptimizationLevel,
     },
@@ -325,7 +325,7 @@ impl TypedValue for PythonInterpreterConfigValue {
                 .map_err(|e| {
                     ValueError::from(RuntimeError {
                         code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                        message: e,
+                        message: e.to_string(),
                         label: format!("{}.{}", Self::TYPE, attribute),
                     })
                 })?;
@@ -338,7 +338,7 @@ impl TypedValue for PythonInterpreterConfigValue {
                         Allocator::try_from(value.to_string().as_str()).map_err(|e| {
                             ValueError::from(RuntimeError {
                                 code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                                message: e,
+                                message: e.to_string(),
                                 label: format!("{}.{}", Self::TYPE, attribute),
                             })
                         })?,
@@ -351,12 +351,31 @@ impl TypedValue for PythonInterpreterConfigValue {
             "coerce_c_locale" => {
                 inner.config.coerce_c_locale = if value.get_type() == "NoneType" {
                     None
+                } else if value.get_type() == "int" {
+                    let value = value.to_int()?;
+
+                    Some(
+                        i32::try_from(value)
+                            .map_err(|_| ParseError {
+                                type_name: "C locale coercion",
+                                bad_value: value.to_string(),
+                                valid_values: &["1", "2"],
+                            })
+                            .and_then(CoerceCLocale::try_from)
+                            .map_err(|e| {
+                                ValueError::from(RuntimeError {
+                                    code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
+                                    message: e.to_string(),
+                                    label: format!("{}.{}", Self::TYPE, attribute),
+                                })
+                            })?,
+                    )
                 } else {
                     Some(
                         CoerceCLocale::try_from(value.to_string().as_str()).map_err(|e| {
                             ValueError::from(RuntimeError {
                                 code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                                message: e,
+                                message: e.to_string(),
                                 label: format!("{}.{}", Self::TYPE, attribute),
                             })
                         })?,
@@ -399,12 +418,24 @@ impl TypedValue for PythonInterpreterConfigValue {
             "bytes_warning" => {
                 inner.config.bytes_warning = if value.get_type() == "NoneType" {
                     None
+                } else if value.get_type() == "int" {
+                    match value.to_int()? {
+                        v @ 0..=2 => Some(BytesWarning::from(v as i32)),
+                        _ => {
+                            return Err(ValueError::from(RuntimeError {
+                                code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
+                                message: "invalid bytes_warning integer value; use 0, 1, or 2"
+                                    .to_string(),
+                                label: format!("{}.{}", Self::TYPE, attribute),
+                            }))
+                        }
+                    }
                 } else {
                     Some(
                         BytesWarning::try_from(value.to_string().as_str()).map_err(|e| {
                             ValueError::from(RuntimeError {
                                 code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                                message: e,
+                                message: e.to_string(),
                                 label: format!("{}.{}", Self::TYPE, attribute),
                             })
                         })?,
@@ -419,7 +450,7 @@ impl TypedValue for PythonInterpreterConfigValue {
                         CheckHashPycsMode::try_from(value.to_string().as_str()).map_err(|e| {
                             ValueError::from(RuntimeError {
                                 code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                                message: e,
+                                message: e.to_string(),
                                 label: format!("{}.{}", Self::TYPE, attribute),
                             })
                         })?,
@@ -538,7 +569,7 @@ impl TypedValue for PythonInterpreterConfigValue {
                 inner.config.user_site_directory = value.to_optional();
             }
             "verbose" => {
-                inner.config.configure_locale = value.to_optional();
+                inner.config.verbose = value.to_optional();
             }
             "warn_options" => {
                 inner.config.warn_options = value.try_to_optional()?;
@@ -554,7 +585,7 @@ impl TypedValue for PythonInterpreterConfigValue {
                     MemoryAllocatorBackend::try_from(value.to_string().as_str()).map_err(|e| {
                         ValueError::from(RuntimeError {
                             code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                            message: e,
+                            message: e.to_string(),
                             label: format!("{}.{}", Self::TYPE, attribute),
                         })
                     })?;
@@ -593,7 +624,7 @@ impl TypedValue for PythonInterpreterConfigValue {
                 .map_err(|e| {
                     ValueError::from(RuntimeError {
                         code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                        message: e,
+                        message: e.to_string(),
                         label: format!("{}.{}", Self::TYPE, attribute),
                     })
                 })?;
@@ -609,7 +640,7 @@ impl TypedValue for PythonInterpreterConfigValue {
                     TerminfoResolution::try_from(value.to_string().as_str()).map_err(|e| {
                         ValueError::from(RuntimeError {
                             code: INCORRECT_PARAMETER_TYPE_ERROR_CODE,
-                            message: e,
+                            message: e.to_string(),
                             label: format!("{}.{}", Self::TYPE, attribute),
                         })
                     })?;
@@ -633,7 +664,10 @@ impl TypedValue for PythonInterpreterConfigValue {
 #[cfg(test)]
 mod tests {
     use crate::starlark::eval::EvaluationContext;
-    use {super::super::testutil::*, anyhow::Result};
+    use {
+        super::super::testutil::*, super::PythonInterpreterConfigValue, anyhow::Result,
+        python_packaging::interpreter::PythonInterpreterConfig,
+    };
 
     // TODO instantiating a new distribution every call is expensive. Can we cache this?
     fn get_env() -> Result<EvaluationContext> {
@@ -707,6 +741,22 @@ mod tests {
 
         eval_assert(&mut env, "config.coerce_c_locale == None")?;
 
+        env.eval("config.coerce_c_locale = 'LC_CTYPE'")?;
+        eval_assert(&mut env, "config.coerce_c_locale == 'LC_CTYPE'")?;
+
+        env.eval("config.coerce_c_locale = 2")?;
+        eval_assert(&mut env, "config.coerce_c_locale == 'C'")?;
+
+        env.eval("config.coerce_c_locale = 1")?;
+        eval_assert(&mut env, "config.coerce_c_locale == 'LC_CTYPE'")?;
+
+        assert!(env.eval("config.coerce_c_locale = 3").is_err());
+        assert!(env.eval("config.coerce_c_locale = 4294967297").is_err());
+        assert!(env.eval("config.coerce_c_locale = 'bogus'").is_err());
+
+        env.eval("config.coerce_c_locale = None")?;
+        eval_assert(&mut env, "config.coerce_c_locale == None")?;
+
         Ok(())
     }
 
@@ -812,6 +862,14 @@ mod tests {
         env.eval("config.bytes_warning = 'raise'")?;
         eval_assert(&mut env, "config.bytes_warning == 'raise'")?;
 
+        env.eval("config.bytes_warning = 1")?;
+        eval_assert(&mut env, "config.bytes_warning == 'warn'")?;
+
+        env.eval("config.bytes_warning = 0")?;
+        eval_assert(&mut env, "config.bytes_warning == 'none'")?;
+
+        assert!(env.eval("config.bytes_warning = 3").is_err());
+
         env.eval("config.bytes_warning = None")?;
         eval_assert(&mut env, "config.bytes_warning == None")?;
 
@@ -1163,6 +1221,17 @@ mod tests {
         let mut env = get_env()?;
 
         eval_assert(&mut env, "config.verbose == None")?;
+        let configure_locale = env.eval("config.configure_locale")?;
+
+        env.eval("config.verbose = True")?;
+        eval_assert(&mut env, "config.verbose == True")?;
+        assert_eq!(
+            env.eval("config.configure_locale")?.to_bool(),
+            configure_locale.to_bool()
+        );
+
+        env.eval("config.verbose = False")?;
+        eval_assert(&mut env, "config.verbose == False")?;
 
         Ok(())
     }
@@ -1385,4 +1454,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_pyoxidizer_starlark_snippet() -> Result<()> {
+        let mut env = get_env()?;
+
+        // argv isn't exposed to Starlark.
+        let config = PythonInterpreterConfig {
+            argv: None,
+            run_module: Some("main".to_string()),
+            stdio_errors: Some("quoted \"\\ value\n".to_string()),
+            ..PythonInterpreterConfig::for_testing()
+        };
+
+        env.eval(&config.to_pyoxidizer_starlark_snippet())?;
+
+        let value = env.eval("python_config")?;
+        let value = value
+            .downcast_ref::<PythonInterpreterConfigValue>()
+            .unwrap();
+        assert_eq!(value.inner("test").unwrap().config, config);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyoxidizer_starlark_snippet_default() -> Result<()> {
+        let mut env = get_env()?;
+
+        // The distribution's defaults (e.g. configure_locale) are reset.
+        let config = PythonInterpreterConfig::default();
+
+        env.eval(&config.to_pyoxidizer_starlark_snippet())?;
+
+        let value = env.eval("python_config")?;
+        let value = value
+            .downcast_ref::<PythonInterpreterConfigValue>()
+            .unwrap();
+        assert_eq!(value.inner("test").unwrap().config, config);
+
+        Ok(())
+    }
 }
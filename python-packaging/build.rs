@@ -0,0 +1,19 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "prost")]
+    {
+        println!("cargo:rerun-if-changed=proto/interpreter_config.proto");
+
+        prost_build::compile_protos(&["proto/interpreter_config.proto"], &["proto/"])
+            .expect("failed to compile proto/interpreter_config.proto");
+    }
+}
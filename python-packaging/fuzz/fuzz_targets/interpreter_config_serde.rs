@@ -0,0 +1,24 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Verify that serializing a `PythonInterpreterConfig` round-trips.
+
+#![no_main]
+
+use {libfuzzer_sys::fuzz_target, python_packaging::interpreter::PythonInterpreterConfig};
+
+fuzz_target!(|config: PythonInterpreterConfig| {
+    let json = serde_json::to_string(&config).expect("serialization should succeed");
+    let parsed = serde_json::from_str::<PythonInterpreterConfig>(&json)
+        .expect("deserializing serialized config should succeed");
+
+    assert_eq!(parsed, config);
+
+    // Validation must not panic, regardless of the outcome.
+    let _ = config.validate();
+});
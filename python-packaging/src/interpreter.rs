@@ -10,11 +10,168 @@
 
 use {
     crate::resource::BytecodeOptimizationLevel,
-    std::{ffi::OsString, os::raw::c_ulong, path::PathBuf, str::FromStr},
+    log::warn,
+    std::{
+        collections::HashMap,
+        ffi::{OsStr, OsString},
+        fmt::{Display, Formatter},
+        hash::Hash,
+        os::raw::c_ulong,
+        path::{Component, Path, PathBuf},
+        process::Command,
+        str::FromStr,
+        time::SystemTime,
+    },
 };
 
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+
+#[cfg(feature = "serialization")]
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "schemars")]
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, StringValidation},
+    JsonSchema,
+};
+
+/// Implement comparison against `&str` via a type's `as_str()` representation.
+///
+/// Comparison is ASCII case-insensitive, like parsing via `TryFrom<&str>`.
+/// Pass `case_sensitive` for types whose parsing is case-sensitive.
+macro_rules! impl_str_partial_eq {
+    ($t:ty) => {
+        impl_str_partial_eq!($t, |a: &str, b: &str| a.eq_ignore_ascii_case(b));
+    };
+    ($t:ty, case_sensitive) => {
+        impl_str_partial_eq!($t, |a: &str, b: &str| a == b);
+    };
+    ($t:ty, $cmp:expr) => {
+        impl PartialEq<&str> for $t {
+            fn eq(&self, other: &&str) -> bool {
+                ($cmp)(self.as_str(), *other)
+            }
+        }
+
+        impl PartialEq<$t> for &str {
+            fn eq(&self, other: &$t) -> bool {
+                other == self
+            }
+        }
+    };
+}
+
+/// JSON Schema for a string that must be one of the given values.
+#[cfg(feature = "schemars")]
+fn string_enum_schema(values: &[&str]) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(values.iter().map(|v| serde_json::Value::from(*v)).collect()),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// JSON Schema for a string that must match a regular expression.
+#[cfg(feature = "schemars")]
+fn string_pattern_schema(pattern: &str) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(pattern.to_string()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// A serde visitor for enums with a string representation.
+///
+/// Errors from parsing are reported with [ParseError]'s message, which lists
+/// the valid values.
+#[cfg(feature = "serialization")]
+struct EnumValueVisitor<T> {
+    valid_values: &'static [&'static str],
+    phantom: std::marker::PhantomData<T>,
+}
+
+#[cfg(feature = "serialization")]
+impl<'de, T> Visitor<'de> for EnumValueVisitor<T>
+where
+    T: for<'a> TryFrom<&'a str, Error = ParseError>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "one of: {}", self.valid_values.join(", "))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        T::try_from(v).map_err(E::custom)
+    }
+}
+
 #[cfg(feature = "serialization")]
-use serde::{Deserialize, Serialize};
+fn deserialize_enum_value<'de, D, T>(
+    deserializer: D,
+    valid_values: &'static [&'static str],
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: for<'a> TryFrom<&'a str, Error = ParseError>,
+{
+    deserializer.deserialize_str(EnumValueVisitor {
+        valid_values,
+        phantom: std::marker::PhantomData,
+    })
+}
+
+/// An error parsing an interpreter configuration value from a string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Human readable name of the type being parsed, e.g. `allocator`.
+    pub type_name: &'static str,
+
+    /// The value that failed to parse.
+    pub bad_value: String,
+
+    /// Values the type can be parsed from.
+    pub valid_values: &'static [&'static str],
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is not a valid {} value",
+            self.bad_value, self.type_name
+        )?;
+
+        let quoted = self
+            .valid_values
+            .iter()
+            .map(|v| format!("'{}'", v))
+            .collect::<Vec<_>>();
+
+        match quoted.as_slice() {
+            [] => Ok(()),
+            [value] => write!(f, "; use {}", value),
+            [a, b] => write!(f, "; use {} or {}", a, b),
+            [head @ .., last] => write!(f, "; use {}, or {}", head.join(", "), last),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for String {
+    fn from(e: ParseError) -> Self {
+        e.to_string()
+    }
+}
 
 /// Defines the profile to use to configure a Python interpreter.
 ///
@@ -22,9 +179,11 @@ use serde::{Deserialize, Serialize};
 /// `PyPreConfig` and `PyConfig` C structs.
 ///
 /// Serialization type: `string`.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
 pub enum PythonInterpreterProfile {
     /// Python is isolated from the system.
     ///
@@ -41,19 +200,61 @@ pub enum PythonInterpreterProfile {
     Python,
 }
 
-impl Default for PythonInterpreterProfile {
-    fn default() -> Self {
-        PythonInterpreterProfile::Isolated
+impl PythonInterpreterProfile {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] = &["isolated", "python"];
+
+    /// All variants known to this version of the crate.
+    pub fn all_known() -> &'static [Self] {
+        &[Self::Isolated, Self::Python]
     }
-}
 
-impl ToString for PythonInterpreterProfile {
-    fn to_string(&self) -> String {
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate.
+    pub fn is_known(&self) -> bool {
+        Self::all_known().contains(self)
+    }
+
+    /// Whether this is [Self::Isolated].
+    pub fn is_isolated(&self) -> bool {
+        *self == Self::Isolated
+    }
+
+    /// Whether this is [Self::Python].
+    pub fn is_python(&self) -> bool {
+        *self == Self::Python
+    }
+
+    /// The name of the CPython function initializing a `PyConfig` for this profile.
+    pub fn to_pyconfig_init_func(&self) -> &'static str {
+        match self {
+            Self::Isolated => "PyConfig_InitIsolatedConfig",
+            Self::Python => "PyConfig_InitPythonConfig",
+        }
+    }
+
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::Isolated => "isolated",
             Self::Python => "python",
         }
-        .to_string()
+    }
+}
+
+impl Default for PythonInterpreterProfile {
+    fn default() -> Self {
+        PythonInterpreterProfile::Isolated
+    }
+}
+
+impl Display for PythonInterpreterProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -63,29 +264,58 @@ impl From<PythonInterpreterProfile> for String {
     }
 }
 
+impl_str_partial_eq!(PythonInterpreterProfile);
+
 impl TryFrom<&str> for PythonInterpreterProfile {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
+        match value.to_ascii_lowercase().as_str() {
             "isolated" => Ok(Self::Isolated),
             "python" => Ok(Self::Python),
-            _ => Err(format!(
-                "{} is not a valid profile; use 'isolated' or 'python'",
-                value
-            )),
+            _ => Err(ParseError {
+                type_name: "profile",
+                bad_value: value.to_string(),
+                valid_values: Self::VALID_VALUES,
+            }),
         }
     }
 }
 
 impl TryFrom<String> for PythonInterpreterProfile {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for PythonInterpreterProfile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for PythonInterpreterProfile {
+    fn schema_name() -> String {
+        "PythonInterpreterProfile".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_enum_schema(Self::VALID_VALUES)
+    }
+}
+
+impl FromStr for PythonInterpreterProfile {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 /// Defines `terminfo` database resolution semantics.
 ///
 /// Python links against libraries like `readline`, `libedit`, and `ncurses`
@@ -104,9 +334,11 @@ impl TryFrom<String> for PythonInterpreterProfile {
 /// to control that behavior.
 ///
 /// Serialization type: `string`.
-#[derive(Clone, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
 pub enum TerminfoResolution {
     /// Resolve `terminfo` database using appropriate behavior for current OS.
     ///
@@ -135,12 +367,64 @@ pub enum TerminfoResolution {
     Static(String),
 }
 
-impl ToString for TerminfoResolution {
-    fn to_string(&self) -> String {
+impl TerminfoResolution {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] = &["dynamic", "none", "static:<path>"];
+
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate. Unlike other enums in this
+    /// module, there is no `all_known()` because variants carry data.
+    pub fn is_known(&self) -> bool {
+        matches!(self, Self::Dynamic | Self::None | Self::Static(_))
+    }
+
+    /// Obtain an instance from the `TERMINFO_DIRS` environment variable.
+    ///
+    /// Returns [Self::Static] with the variable's value if it is set and
+    /// non-empty. This allows operators to redirect terminfo lookups without
+    /// rebuilding the application.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("TERMINFO_DIRS")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(Self::Static)
+    }
+
+    /// Resolve the value that the `TERMINFO_DIRS` environment variable should be set to.
+    ///
+    /// Returns `None` if the environment variable should not be set.
+    ///
+    /// For [Self::Dynamic], an existing `TERMINFO_DIRS` environment variable is
+    /// always respected. Otherwise, the directories are derived from the
+    /// current operating system. On Linux, Debian and RedHat based
+    /// distributions (detected via `/etc/os-release`) use the directories
+    /// their ncurses is built with. On other distributions, whichever of
+    /// well-known directories such as `/etc/terminfo` and
+    /// `/usr/share/terminfo` exist are used. Windows doesn't use the terminfo
+    /// database.
+    pub fn resolve_dirs(&self) -> Option<String> {
+        match self {
+            Self::Dynamic => {
+                if std::env::var_os("TERMINFO_DIRS").is_some() {
+                    None
+                } else {
+                    resolve_terminfo_dirs()
+                }
+            }
+            Self::Static(value) => Some(value.clone()),
+            Self::None => None,
+        }
+    }
+}
+
+impl Display for TerminfoResolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Dynamic => "dynamic".to_string(),
-            Self::None => "none".to_string(),
-            Self::Static(value) => format!("static:{}", value),
+            Self::Dynamic => f.write_str("dynamic"),
+            Self::None => f.write_str("none"),
+            Self::Static(value) => write!(f, "static:{}", value),
         }
     }
 }
@@ -151,33 +435,186 @@ impl From<TerminfoResolution> for String {
     }
 }
 
+/// Keywords compare ASCII case-insensitively, like parsing via `TryFrom<&str>`.
+/// Static paths are case-sensitive.
+impl PartialEq<&str> for TerminfoResolution {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            Self::Dynamic => other.eq_ignore_ascii_case("dynamic"),
+            Self::None => other.eq_ignore_ascii_case("none"),
+            Self::Static(value) => {
+                let prefix_len = "static:".len();
+
+                matches!(other.get(..prefix_len), Some(prefix) if prefix.eq_ignore_ascii_case("static:"))
+                    && other.get(prefix_len..) == Some(value.as_str())
+            }
+        }
+    }
+}
+
+impl PartialEq<TerminfoResolution> for &str {
+    fn eq(&self, other: &TerminfoResolution) -> bool {
+        other == self
+    }
+}
+
 impl TryFrom<&str> for TerminfoResolution {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value == "dynamic" {
+        // Only the keyword is case-insensitive. Paths retain their case.
+        let lower = value.to_ascii_lowercase();
+
+        if lower == "dynamic" {
             Ok(Self::Dynamic)
-        } else if value == "none" {
+        } else if lower == "none" {
             Ok(Self::None)
-        } else if let Some(suffix) = value.strip_prefix("static:") {
-            Ok(Self::Static(suffix.to_string()))
+        } else if lower.starts_with("static:") {
+            Ok(Self::Static(value["static:".len()..].to_string()))
         } else {
-            Err(format!(
-                "{} is not a valid terminfo resolution value",
-                value
-            ))
+            Err(ParseError {
+                type_name: "terminfo resolution",
+                bad_value: value.to_string(),
+                valid_values: Self::VALID_VALUES,
+            })
         }
     }
 }
 
 impl TryFrom<String> for TerminfoResolution {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for TerminfoResolution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for TerminfoResolution {
+    fn schema_name() -> String {
+        "TerminfoResolution".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_pattern_schema("^(dynamic|none|static:.*)$")
+    }
+}
+
+impl FromStr for TerminfoResolution {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+/// terminfo directories for Debian based distributions.
+///
+/// Search for `--with-terminfo-dirs` at
+/// https://salsa.debian.org/debian/ncurses/blob/master/debian/rules to find
+/// the source of truth for this.
+const TERMINFO_DIRS_DEBIAN: &str = "/etc/terminfo:/lib/terminfo:/usr/share/terminfo";
+
+/// terminfo directories for RedHat based distributions.
+///
+/// CentOS compiled with
+/// `--with-terminfo-dirs=%{_sysconfdir}/terminfo:%{_datadir}/terminfo`.
+const TERMINFO_DIRS_REDHAT: &str = "/etc/terminfo:/usr/share/terminfo";
+
+/// terminfo directories for macOS.
+const TERMINFO_DIRS_MACOS: &str = "/usr/share/terminfo";
+
+/// terminfo directories to probe on unknown Linux distributions.
+const TERMINFO_DIRS_COMMON: &[&str] = &[
+    "/usr/local/etc/terminfo",
+    "/usr/local/lib/terminfo",
+    "/usr/local/share/terminfo",
+    "/etc/terminfo",
+    "/usr/lib/terminfo",
+    "/lib/terminfo",
+    "/usr/share/terminfo",
+];
+
+enum LinuxDistroVariant {
+    Debian,
+    RedHat,
+    Unknown,
+}
+
+fn resolve_linux_distro(root: &Path) -> LinuxDistroVariant {
+    // Attempt to resolve the Linux distro by parsing /etc files.
+    let os_release = root.join("etc/os-release");
+
+    if let Ok(data) = std::fs::read_to_string(os_release) {
+        for line in data.split('\n') {
+            if line.starts_with("ID_LIKE=") {
+                if line.contains("debian") {
+                    return LinuxDistroVariant::Debian;
+                } else if line.contains("rhel") || line.contains("fedora") {
+                    return LinuxDistroVariant::RedHat;
+                }
+            } else if line.starts_with("ID=") && line.contains("fedora") {
+                return LinuxDistroVariant::RedHat;
+            }
+        }
+    }
+
+    LinuxDistroVariant::Unknown
+}
+
+/// Join directories relative to `root` into a `TERMINFO_DIRS` value.
+fn join_terminfo_dirs<'a>(root: &Path, dirs: impl Iterator<Item = &'a str>) -> String {
+    dirs.map(|dir| root.join(dir.trim_start_matches('/')).display().to_string())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Resolve `TERMINFO_DIRS` for a Linux filesystem mounted at `root`.
+///
+/// Paths in the result are relative to `root`. When `root` is `/`, they are
+/// the absolute paths of the running system.
+fn resolve_linux_terminfo_dirs(root: &Path) -> String {
+    match resolve_linux_distro(root) {
+        // TODO we could stat() the well-known paths ourselves and omit
+        // paths that don't exist. This /might/ save some syscalls, since
+        // ncurses doesn't appear to be the most frugal w.r.t. filesystem
+        // requests.
+        LinuxDistroVariant::Debian => join_terminfo_dirs(root, TERMINFO_DIRS_DEBIAN.split(':')),
+        LinuxDistroVariant::RedHat => join_terminfo_dirs(root, TERMINFO_DIRS_REDHAT.split(':')),
+        LinuxDistroVariant::Unknown => {
+            // We don't know this Linux variant. Look for common terminfo
+            // database directories and use paths that are found.
+            join_terminfo_dirs(
+                root,
+                TERMINFO_DIRS_COMMON
+                    .iter()
+                    .copied()
+                    .filter(|dir| root.join(dir.trim_start_matches('/')).exists()),
+            )
+        }
+    }
+}
+
+/// Resolve `TERMINFO_DIRS` using heuristics for the current operating system.
+fn resolve_terminfo_dirs() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        Some(resolve_linux_terminfo_dirs(Path::new("/")))
+    } else if cfg!(target_os = "macos") {
+        Some(TERMINFO_DIRS_MACOS.to_string())
+    } else {
+        // Windows doesn't use the terminfo database. We don't know about
+        // other operating systems.
+        None
+    }
+}
+
 /// Defines a backend for a memory allocator.
 ///
 /// This says which memory allocator API / library to configure the Python
@@ -186,9 +623,11 @@ impl TryFrom<String> for TerminfoResolution {
 /// Not all allocators are available in all program builds.
 ///
 /// Serialization type: `string`
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
 pub enum MemoryAllocatorBackend {
     /// The default allocator as configured by Python.
     ///
@@ -222,6 +661,15 @@ pub enum MemoryAllocatorBackend {
     /// Serialized value: `snmalloc`
     Snmalloc,
 
+    /// Use the tcmalloc allocator from gperftools
+    /// (<https://github.com/gperftools/gperftools>).
+    ///
+    /// Requires the binary to be built with tcmalloc support, which links
+    /// against a system-provided `libtcmalloc`.
+    ///
+    /// Serialized value: `tcmalloc`
+    Tcmalloc,
+
     /// Use Rust's global allocator.
     ///
     /// The Rust allocator is less efficient than other allocators because of
@@ -233,26 +681,144 @@ pub enum MemoryAllocatorBackend {
     Rust,
 }
 
-impl Default for MemoryAllocatorBackend {
-    fn default() -> Self {
-        if cfg!(windows) {
-            Self::Default
+impl MemoryAllocatorBackend {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] = &[
+        "default", "jemalloc", "mimalloc", "snmalloc", "tcmalloc", "rust",
+    ];
+
+    /// All variants known to this version of the crate.
+    pub fn all_known() -> &'static [Self] {
+        &[
+            Self::Default,
+            Self::Jemalloc,
+            Self::Mimalloc,
+            Self::Snmalloc,
+            Self::Tcmalloc,
+            Self::Rust,
+        ]
+    }
+
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate.
+    pub fn is_known(&self) -> bool {
+        Self::all_known().contains(self)
+    }
+
+    /// The `pyembed` Cargo feature required to use this backend.
+    ///
+    /// Returns `None` if the backend is always available.
+    pub fn requires_feature(&self) -> Option<&'static str> {
+        match self {
+            Self::Default | Self::Rust => None,
+            Self::Jemalloc => Some("allocator-jemalloc"),
+            Self::Mimalloc => Some("allocator-mimalloc"),
+            Self::Snmalloc => Some("allocator-snmalloc"),
+            Self::Tcmalloc => Some("allocator-tcmalloc"),
+        }
+    }
+
+    /// Whether this backend can be used on the target this crate is compiled for.
+    ///
+    /// This doesn't check whether the required Cargo feature (see
+    /// [Self::requires_feature()]) is enabled. It only reflects whether the
+    /// backend could work on the target at all. See
+    /// [Self::is_available_for_target_triple()] for the rules applied.
+    pub fn is_available_on_current_target(&self) -> bool {
+        let wasm = cfg!(target_family = "wasm");
+
+        match self {
+            Self::Default | Self::Rust => true,
+            Self::Jemalloc => !cfg!(windows) && !wasm,
+            Self::Mimalloc => !wasm,
+            Self::Snmalloc => cfg!(target_pointer_width = "64") && !wasm,
+            Self::Tcmalloc => !cfg!(windows) && !wasm,
+        }
+    }
+
+    /// Whether this backend can be used on a given Rust target triple.
+    ///
+    /// * `default` and `rust` are available everywhere.
+    /// * `jemalloc` isn't available on Windows or WebAssembly.
+    /// * `mimalloc` isn't available on WebAssembly.
+    /// * `snmalloc` requires a 64-bit target and isn't available on WebAssembly.
+    /// * `tcmalloc` links against a system library from gperftools, which
+    ///   isn't available on Windows or WebAssembly.
+    pub fn is_available_for_target_triple(&self, triple: &str) -> bool {
+        let arch = triple.split('-').next().unwrap_or_default();
+        let windows = triple.contains("-windows");
+        let wasm = arch.starts_with("wasm");
+        let bits_64 = [
+            "x86_64",
+            "aarch64",
+            "powerpc64",
+            "riscv64",
+            "s390x",
+            "mips64",
+            "loongarch64",
+            "sparc64",
+            "sparcv9",
+        ]
+        .iter()
+        .any(|prefix| arch.starts_with(prefix));
+
+        match self {
+            Self::Default | Self::Rust => true,
+            Self::Jemalloc => !windows && !wasm,
+            Self::Mimalloc => !wasm,
+            Self::Snmalloc => bits_64 && !wasm,
+            Self::Tcmalloc => !windows && !wasm,
+        }
+    }
+
+    /// Verify this backend is available on the target this crate is compiled for.
+    ///
+    /// This is [Self::is_available_on_current_target()] with a descriptive
+    /// error, for callers that want to reject a configuration up front.
+    /// Build scripts are compiled for the host, not the target, so they
+    /// should use [Self::is_available_for_target_triple()] with Cargo's
+    /// `TARGET` instead.
+    pub fn compile_time_check(&self) -> Result<(), String> {
+        if self.is_available_on_current_target() {
+            Ok(())
         } else {
-            Self::Jemalloc
+            Err(format!(
+                "the {} memory allocator backend is not available on this target",
+                self
+            ))
         }
     }
-}
 
-impl ToString for MemoryAllocatorBackend {
-    fn to_string(&self) -> String {
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::Default => "default",
             Self::Jemalloc => "jemalloc",
             Self::Mimalloc => "mimalloc",
             Self::Snmalloc => "snmalloc",
+            Self::Tcmalloc => "tcmalloc",
             Self::Rust => "rust",
         }
-        .to_string()
+    }
+}
+
+impl Default for MemoryAllocatorBackend {
+    fn default() -> Self {
+        if cfg!(windows) {
+            Self::Default
+        } else {
+            Self::Jemalloc
+        }
+    }
+}
+
+impl Display for MemoryAllocatorBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -262,37 +828,72 @@ impl From<MemoryAllocatorBackend> for String {
     }
 }
 
+impl_str_partial_eq!(MemoryAllocatorBackend);
+
 impl TryFrom<&str> for MemoryAllocatorBackend {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
+        match value.to_ascii_lowercase().as_str() {
             "default" => Ok(Self::Default),
             "jemalloc" => Ok(Self::Jemalloc),
             "mimalloc" => Ok(Self::Mimalloc),
             "snmalloc" => Ok(Self::Snmalloc),
+            "tcmalloc" => Ok(Self::Tcmalloc),
             "rust" => Ok(Self::Rust),
-            _ => Err(format!("{} is not a valid memory allocator backend", value)),
+            _ => Err(ParseError {
+                type_name: "memory allocator backend",
+                bad_value: value.to_string(),
+                valid_values: Self::VALID_VALUES,
+            }),
         }
     }
 }
 
 impl TryFrom<String> for MemoryAllocatorBackend {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for MemoryAllocatorBackend {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for MemoryAllocatorBackend {
+    fn schema_name() -> String {
+        "MemoryAllocatorBackend".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_enum_schema(Self::VALID_VALUES)
+    }
+}
+
+impl FromStr for MemoryAllocatorBackend {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 /// Holds values for `coerce_c_locale`.
 ///
 /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.coerce_c_locale>.
 ///
 /// Serialization type: `string`
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
 pub enum CoerceCLocale {
     /// Read the LC_CTYPE locale to decide if it should be coerced.
     ///
@@ -306,13 +907,45 @@ pub enum CoerceCLocale {
     C = 2,
 }
 
-impl ToString for CoerceCLocale {
-    fn to_string(&self) -> String {
+impl CoerceCLocale {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] = &["LC_CTYPE", "C"];
+
+    /// All variants known to this version of the crate.
+    pub fn all_known() -> &'static [Self] {
+        &[Self::LCCtype, Self::C]
+    }
+
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate.
+    pub fn is_known(&self) -> bool {
+        Self::all_known().contains(self)
+    }
+
+    /// The value of `PyPreConfig.coerce_c_locale` corresponding to this variant.
+    pub fn as_int(&self) -> i32 {
+        match self {
+            Self::LCCtype => 1,
+            Self::C => 2,
+        }
+    }
+
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::LCCtype => "LC_CTYPE",
             Self::C => "C",
         }
-        .to_string()
+    }
+}
+
+impl Display for CoerceCLocale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -322,43 +955,99 @@ impl From<CoerceCLocale> for String {
     }
 }
 
+impl_str_partial_eq!(CoerceCLocale);
+
 impl TryFrom<&str> for CoerceCLocale {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "LC_CTYPE" => Ok(Self::LCCtype),
-            "C" => Ok(Self::C),
-            _ => Err(format!("{} is not a valid C locale coercion value", value)),
+        match value.to_ascii_lowercase().as_str() {
+            "lc_ctype" => Ok(Self::LCCtype),
+            "c" => Ok(Self::C),
+            _ => Err(ParseError {
+                type_name: "C locale coercion",
+                bad_value: value.to_string(),
+                valid_values: Self::VALID_VALUES,
+            }),
         }
     }
 }
 
 impl TryFrom<String> for CoerceCLocale {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
-/// Defines what to do when comparing `bytes` or `bytesarray` with `str` or comparing `bytes` with `int`.
-///
-/// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.bytes_warning>.
-///
-/// Serialization type: `string`
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
-pub enum BytesWarning {
-    /// Do nothing.
-    ///
-    /// Serialization value: `none`
-    None = 0,
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for CoerceCLocale {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
 
-    /// Issue a warning.
-    ///
-    /// Serialization value: `warn`
+#[cfg(feature = "schemars")]
+impl JsonSchema for CoerceCLocale {
+    fn schema_name() -> String {
+        "CoerceCLocale".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_enum_schema(Self::VALID_VALUES)
+    }
+}
+
+impl FromStr for CoerceCLocale {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl From<CoerceCLocale> for i32 {
+    fn from(value: CoerceCLocale) -> i32 {
+        value.as_int()
+    }
+}
+
+impl TryFrom<i32> for CoerceCLocale {
+    type Error = ParseError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::LCCtype),
+            2 => Ok(Self::C),
+            _ => Err(ParseError {
+                type_name: "C locale coercion",
+                bad_value: value.to_string(),
+                valid_values: &["1", "2"],
+            }),
+        }
+    }
+}
+
+/// Defines what to do when comparing `bytes` or `bytesarray` with `str` or comparing `bytes` with `int`.
+///
+/// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.bytes_warning>.
+///
+/// Serialization type: `string`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
+pub enum BytesWarning {
+    /// Do nothing.
+    ///
+    /// Serialization value: `none`
+    None = 0,
+
+    /// Issue a warning.
+    ///
+    /// Serialization value: `warn`
     Warn = 1,
 
     /// Raise a `BytesWarning`.
@@ -367,14 +1056,38 @@ pub enum BytesWarning {
     Raise = 2,
 }
 
-impl ToString for BytesWarning {
-    fn to_string(&self) -> String {
+impl BytesWarning {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] = &["none", "warn", "raise"];
+
+    /// All variants known to this version of the crate.
+    pub fn all_known() -> &'static [Self] {
+        &[Self::None, Self::Warn, Self::Raise]
+    }
+
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate.
+    pub fn is_known(&self) -> bool {
+        Self::all_known().contains(self)
+    }
+
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::None => "none",
             Self::Warn => "warn",
             Self::Raise => "raise",
         }
-        .to_string()
+    }
+}
+
+impl Display for BytesWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -384,27 +1097,59 @@ impl From<BytesWarning> for String {
     }
 }
 
+impl_str_partial_eq!(BytesWarning);
+
 impl TryFrom<&str> for BytesWarning {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
+        match value.to_ascii_lowercase().as_str() {
             "none" => Ok(Self::None),
             "warn" => Ok(Self::Warn),
             "raise" => Ok(Self::Raise),
-            _ => Err(format!("{} is not a valid bytes warning value", value)),
+            _ => Err(ParseError {
+                type_name: "bytes warning",
+                bad_value: value.to_string(),
+                valid_values: Self::VALID_VALUES,
+            }),
         }
     }
 }
 
 impl TryFrom<String> for BytesWarning {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for BytesWarning {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for BytesWarning {
+    fn schema_name() -> String {
+        "BytesWarning".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_enum_schema(Self::VALID_VALUES)
+    }
+}
+
+impl FromStr for BytesWarning {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 impl From<i32> for BytesWarning {
     fn from(value: i32) -> BytesWarning {
         match value {
@@ -415,14 +1160,35 @@ impl From<i32> for BytesWarning {
     }
 }
 
+impl From<BytesWarning> for i32 {
+    fn from(value: BytesWarning) -> i32 {
+        value as i32
+    }
+}
+
 /// Control the validation behavior of hash-based .pyc files.
 ///
+/// This only affects hash-based pycs (PEP 552). Timestamp-based pycs, which
+/// is what CPython writes unless told otherwise, are always validated
+/// against the source file's modification time.
+///
+/// [Self::Default] is appropriate for most uses, and is what the
+/// interpreter uses if the mode isn't set: whoever compiled the pyc decided
+/// whether it should be checked. [Self::Always] suits CI and development,
+/// where sources change and a stale pyc must never be used, at the cost of
+/// reading and hashing the source of every module with a hash-based pyc on
+/// import. [Self::Never] avoids that cost when sources can't change after
+/// compilation, such as in packaged applications. Modified sources are then
+/// silently ignored in favor of the pyc.
+///
 /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.check_hash_pycs_mode>.
 ///
 /// Serialization type: `string`
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
 pub enum CheckHashPycsMode {
     /// Hash the source file for invalidation regardless of value of the `check_source` flag.
     ///
@@ -437,17 +1203,52 @@ pub enum CheckHashPycsMode {
     /// The `check_source` flag in hash-based pycs determines invalidation.
     ///
     /// Serialized value: `default`
+    #[default]
     Default,
 }
 
-impl ToString for CheckHashPycsMode {
-    fn to_string(&self) -> String {
+impl CheckHashPycsMode {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] = &["always", "never", "default"];
+
+    /// All variants known to this version of the crate.
+    pub fn all_known() -> &'static [Self] {
+        &[Self::Always, Self::Never, Self::Default]
+    }
+
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate.
+    pub fn is_known(&self) -> bool {
+        Self::all_known().contains(self)
+    }
+
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::Always => "always",
             Self::Never => "never",
             Self::Default => "default",
         }
-        .to_string()
+    }
+
+    /// The value of the `--check-hash-based-pycs` command line argument for this mode.
+    pub fn to_pyc_flag(&self) -> &'static str {
+        self.as_str()
+    }
+
+    /// Whether every hash-based pyc is validated against its source.
+    pub fn is_strict(&self) -> bool {
+        *self == Self::Always
+    }
+}
+
+impl Display for CheckHashPycsMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -457,42 +1258,79 @@ impl From<CheckHashPycsMode> for String {
     }
 }
 
+impl_str_partial_eq!(CheckHashPycsMode);
+
 impl TryFrom<&str> for CheckHashPycsMode {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
+        match value.to_ascii_lowercase().as_str() {
             "always" => Ok(Self::Always),
             "never" => Ok(Self::Never),
             "default" => Ok(Self::Default),
-            _ => Err(format!(
-                "{} is not a valid check hash pycs mode value",
-                value
-            )),
+            _ => Err(ParseError {
+                type_name: "check hash pycs mode",
+                bad_value: value.to_string(),
+                valid_values: Self::VALID_VALUES,
+            }),
         }
     }
 }
 
 impl TryFrom<String> for CheckHashPycsMode {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for CheckHashPycsMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for CheckHashPycsMode {
+    fn schema_name() -> String {
+        "CheckHashPycsMode".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_enum_schema(Self::VALID_VALUES)
+    }
+}
+
+impl FromStr for CheckHashPycsMode {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 /// Name of the Python memory allocators.
 ///
 /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.allocator>.
 ///
 /// Serialization type: `string`
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
+///
+/// The default value is [Allocator::NotSet]: CPython's default allocator is
+/// chosen when it is built (e.g. `pymalloc` may be disabled or debug hooks
+/// may be enabled by a debug build). Leaving the allocator unset defers to
+/// that choice instead of overriding it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
 pub enum Allocator {
     /// Don’t change memory allocators (use defaults).
     ///
     /// Serialized value: `not-set`
+    #[default]
     NotSet = 0,
 
     /// Default memory allocators.
@@ -526,8 +1364,83 @@ pub enum Allocator {
     PyMallocDebug = 6,
 }
 
-impl ToString for Allocator {
-    fn to_string(&self) -> String {
+impl Allocator {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] = &[
+        "not-set",
+        "default",
+        "debug",
+        "malloc",
+        "malloc-debug",
+        "py-malloc",
+        "py-malloc-debug",
+    ];
+
+    /// All variants known to this version of the crate.
+    pub fn all_known() -> &'static [Self] {
+        &[
+            Self::NotSet,
+            Self::Default,
+            Self::Debug,
+            Self::Malloc,
+            Self::MallocDebug,
+            Self::PyMalloc,
+            Self::PyMallocDebug,
+        ]
+    }
+
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate.
+    pub fn is_known(&self) -> bool {
+        Self::all_known().contains(self)
+    }
+
+    /// Whether this value requests a specific allocator.
+    ///
+    /// Returns `false` for [Self::NotSet], which retains the allocator
+    /// CPython was built with.
+    pub fn is_set(&self) -> bool {
+        *self != Self::NotSet
+    }
+
+    /// Whether this is one of the allocators that install debug hooks.
+    ///
+    /// Debug hooks detect memory errors at the expense of run-time
+    /// performance.
+    pub fn is_debug_variant(&self) -> bool {
+        matches!(self, Self::Debug | Self::MallocDebug | Self::PyMallocDebug)
+    }
+
+    /// Whether this allocator can be meaningfully combined with a memory allocator backend.
+    ///
+    /// The backend is installed via `PyMem_SetAllocator()` after CPython has
+    /// configured the allocator selected here. This replaces whatever
+    /// CPython installed for the affected domains, including debug hooks.
+    ///
+    /// | Allocator                                  | `default` backend | Other backends |
+    /// |--------------------------------------------|-------------------|----------------|
+    /// | `not-set`, `default`                       | yes               | yes            |
+    /// | `malloc`, `py-malloc`                      | yes               | yes            |
+    /// | `debug`, `malloc-debug`, `py-malloc-debug` | yes               | no             |
+    ///
+    /// `py-malloc` remains coherent with other backends: pymalloc serves
+    /// small object allocations and the backend serves the rest, optionally
+    /// including pymalloc's arenas.
+    ///
+    /// The debug variants aren't: the debug hooks they install are silently
+    /// removed from domains served by the backend. `pyembed`'s
+    /// `allocator_debug` setting should be used to combine debug hooks with
+    /// a custom backend instead.
+    pub fn is_compatible_with(&self, backend: &MemoryAllocatorBackend) -> bool {
+        *backend == MemoryAllocatorBackend::Default || !self.is_debug_variant()
+    }
+
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::NotSet => "not-set",
             Self::Default => "default",
@@ -537,7 +1450,12 @@ impl ToString for Allocator {
             Self::PyMalloc => "py-malloc",
             Self::PyMallocDebug => "py-malloc-debug",
         }
-        .to_string()
+    }
+}
+
+impl Display for Allocator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -547,11 +1465,13 @@ impl From<Allocator> for String {
     }
 }
 
+impl_str_partial_eq!(Allocator);
+
 impl TryFrom<&str> for Allocator {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
+        match value.to_ascii_lowercase().as_str() {
             "not-set" => Ok(Self::NotSet),
             "default" => Ok(Self::Default),
             "debug" => Ok(Self::Debug),
@@ -559,19 +1479,81 @@ impl TryFrom<&str> for Allocator {
             "malloc-debug" => Ok(Self::MallocDebug),
             "py-malloc" => Ok(Self::PyMalloc),
             "py-malloc-debug" => Ok(Self::PyMallocDebug),
-            _ => Err(format!("{} is not a valid allocator value", value)),
+            _ => Err(ParseError {
+                type_name: "allocator",
+                bad_value: value.to_string(),
+                valid_values: Self::VALID_VALUES,
+            }),
         }
     }
 }
 
 impl TryFrom<String> for Allocator {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for Allocator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for Allocator {
+    fn schema_name() -> String {
+        "Allocator".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_enum_schema(Self::VALID_VALUES)
+    }
+}
+
+impl FromStr for Allocator {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl From<Allocator> for i32 {
+    fn from(value: Allocator) -> i32 {
+        value as i32
+    }
+}
+
+/// The layers of memory allocation used by a Python interpreter.
+///
+/// [Self::cpython_layer] is the allocator CPython configures during
+/// pre-initialization. [Self::rust_layer] is the allocator backend the
+/// embedding application installs on top of it, replacing CPython's
+/// allocator for the memory domains it is configured for (normally just
+/// the `raw` domain).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AllocatorChain {
+    /// The allocator configured by CPython.
+    pub cpython_layer: Allocator,
+
+    /// The allocator backend installed by the embedding application.
+    pub rust_layer: MemoryAllocatorBackend,
+}
+
+impl AllocatorChain {
+    /// Whether the layers can be combined sensibly.
+    ///
+    /// This is [Allocator::is_compatible_with()] applied to the layers.
+    pub fn is_coherent(&self) -> bool {
+        self.cpython_layer.is_compatible_with(&self.rust_layer)
+    }
+}
+
 /// Defines how to call `multiprocessing.set_start_method()` when `multiprocessing` is imported.
 ///
 /// When set to a value that is not `none`, when `oxidized_importer.OxidizedFinder` services
@@ -582,9 +1564,11 @@ impl TryFrom<String> for Allocator {
 /// this setting has no effect.
 ///
 /// Serialization type: `string`
-#[derive(Clone, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(try_from = "String", into = "String"))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(into = "String"))]
+#[non_exhaustive]
 pub enum MultiprocessingStartMethod {
     /// Do not call `multiprocessing.set_start_method()`.
     ///
@@ -611,13 +1595,108 @@ pub enum MultiprocessingStartMethod {
     /// Call with a valid appropriate for the given environment.
     ///
     /// This likely maps to `spawn` on Windows and `fork` on non-Windows.
+    /// See [MultiprocessingStartMethod::detect()] for the exact logic.
     ///
     /// Serialized value: `auto`
     Auto,
 }
 
-impl ToString for MultiprocessingStartMethod {
-    fn to_string(&self) -> String {
+impl MultiprocessingStartMethod {
+    /// String values this type can be parsed from.
+    pub const VALID_VALUES: &'static [&'static str] =
+        &["none", "fork", "forkserver", "spawn", "auto"];
+
+    /// All variants known to this version of the crate.
+    pub fn all_known() -> &'static [Self] {
+        &[
+            Self::None,
+            Self::Fork,
+            Self::ForkServer,
+            Self::Spawn,
+            Self::Auto,
+        ]
+    }
+
+    /// Whether this value is a variant known to this version of the crate.
+    ///
+    /// The enum is `#[non_exhaustive]`. This always returns `true` for values
+    /// constructed by this version of the crate.
+    pub fn is_known(&self) -> bool {
+        Self::all_known().contains(self)
+    }
+
+    /// Resolve the start method to use for a given operating system.
+    ///
+    /// This is the logic used to resolve [MultiprocessingStartMethod::Auto].
+    /// It always returns a variant that can be passed to
+    /// `multiprocessing.set_start_method()`.
+    pub fn detect(os: &OsInfo) -> Self {
+        // `spawn` is the only method available without `fork()`. Everywhere
+        // else uses `fork`, including macOS. CPython defaults to `spawn` on
+        // macOS since 3.8 because system frameworks aren't fork safe (see
+        // https://bugs.python.org/issue33725), so [Self::is_safe_on_current_platform()]
+        // reports the result as unsafe there. We still use `fork` because
+        // `spawn` is known to be buggy with PyOxidizer outside of Windows.
+        if os.has_fork {
+            Self::Fork
+        } else {
+            Self::Spawn
+        }
+    }
+
+    /// Resolve [Self::Auto] to the start method used on a given operating system.
+    ///
+    /// Other variants are returned as-is.
+    pub fn resolve(&self, os: &OsInfo) -> Self {
+        match self {
+            Self::Auto => Self::detect(os),
+            method => method.clone(),
+        }
+    }
+
+    /// Whether this start method creates worker processes via `fork()`.
+    ///
+    /// This is `true` for [Self::Fork] and [Self::ForkServer]. Forking a
+    /// process that has spawned threads or loaded certain system libraries
+    /// (e.g. the Objective-C runtime on macOS) can lead to crashes or
+    /// deadlocks in the child. See
+    /// <https://docs.python.org/3/library/multiprocessing.html#contexts-and-start-methods>
+    /// for more.
+    ///
+    /// [Self::Auto] returns `false`, even though it may resolve to
+    /// [Self::Fork]. Call [Self::resolve()] first to account for that.
+    pub fn is_fork_based(&self) -> bool {
+        matches!(self, Self::Fork | Self::ForkServer)
+    }
+
+    /// Whether this start method is safe to use on the platform this crate was compiled for.
+    ///
+    /// Fork-based methods are considered unsafe on macOS, where system
+    /// frameworks aren't fork safe and Python defaults to `spawn` since
+    /// Python 3.8. See <https://bugs.python.org/issue33725>. [Self::Auto]
+    /// is resolved first, so it is unsafe on macOS too.
+    pub fn is_safe_on_current_platform(&self) -> bool {
+        !(cfg!(target_os = "macos") && self.resolve(&OsInfo::current()).is_fork_based())
+    }
+
+    /// Obtain the value to pass to `multiprocessing.set_start_method()`.
+    ///
+    /// Returns `None` for [Self::None], which doesn't call the function, and
+    /// for [Self::Auto], which must be resolved with [Self::detect()] first.
+    /// The returned values are identical to the serialized values.
+    pub fn to_python_str(&self) -> Option<&'static str> {
+        match self {
+            Self::Fork => Some("fork"),
+            Self::ForkServer => Some("forkserver"),
+            Self::Spawn => Some("spawn"),
+            Self::None | Self::Auto => None,
+        }
+    }
+
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
         match self {
             Self::None => "none",
             Self::Fork => "fork",
@@ -625,7 +1704,12 @@ impl ToString for MultiprocessingStartMethod {
             Self::Spawn => "spawn",
             Self::Auto => "auto",
         }
-        .to_string()
+    }
+}
+
+impl Display for MultiprocessingStartMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -635,23 +1719,29 @@ impl From<MultiprocessingStartMethod> for String {
     }
 }
 
+impl_str_partial_eq!(MultiprocessingStartMethod);
+
 impl FromStr for MultiprocessingStartMethod {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        match s.to_ascii_lowercase().as_str() {
             "none" => Ok(Self::None),
             "fork" => Ok(Self::Fork),
             "forkserver" => Ok(Self::ForkServer),
             "spawn" => Ok(Self::Spawn),
             "auto" => Ok(Self::Auto),
-            _ => Err(format!("{} is not a valid multiprocessing start method", s)),
+            _ => Err(ParseError {
+                type_name: "multiprocessing start method",
+                bad_value: s.to_string(),
+                valid_values: Self::VALID_VALUES,
+            }),
         }
     }
 }
 
 impl TryFrom<&str> for MultiprocessingStartMethod {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(v: &str) -> Result<Self, Self::Error> {
         Self::from_str(v)
@@ -659,77 +1749,636 @@ impl TryFrom<&str> for MultiprocessingStartMethod {
 }
 
 impl TryFrom<String> for MultiprocessingStartMethod {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::try_from(value.as_str())
     }
 }
 
-/// Holds configuration of a Python interpreter.
-///
-/// This struct holds fields that are exposed by `PyPreConfig` and
-/// `PyConfig` in the CPython API.
-///
-/// Other than the profile (which is used to initialize instances of
-/// `PyPreConfig` and `PyConfig`), all fields are optional. Only fields
-/// with `Some(T)` will be updated from the defaults.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
-#[cfg_attr(feature = "serialization", serde(default))]
-pub struct PythonInterpreterConfig {
-    /// Profile to use to initialize pre-config and config state of interpreter.
-    pub profile: PythonInterpreterProfile,
+#[cfg(feature = "serialization")]
+impl<'de> Deserialize<'de> for MultiprocessingStartMethod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_enum_value(deserializer, Self::VALID_VALUES)
+    }
+}
 
-    // The following fields are from PyPreConfig or are shared with PyConfig.
-    /// Name of the memory allocator.
-    ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.allocator>.
-    pub allocator: Option<Allocator>,
+#[cfg(feature = "schemars")]
+impl JsonSchema for MultiprocessingStartMethod {
+    fn schema_name() -> String {
+        "MultiprocessingStartMethod".to_string()
+    }
 
-    /// Whether to set the LC_CTYPE locale to the user preferred locale.
-    ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.configure_locale>.
-    pub configure_locale: Option<bool>,
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_enum_schema(Self::VALID_VALUES)
+    }
+}
 
-    /// How to coerce the locale settings.
+/// Describes the operating system a Python interpreter runs on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct OsInfo {
+    /// Name of the operating system.
     ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.coerce_c_locale>.
-    pub coerce_c_locale: Option<CoerceCLocale>,
+    /// Uses the values of [std::env::consts::OS], e.g. `linux`, `macos`, or
+    /// `windows`.
+    pub os: String,
 
-    /// Whether to emit a warning if the C locale is coerced.
-    ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.coerce_c_locale_warn>.
-    pub coerce_c_locale_warn: Option<bool>,
+    /// Whether the process has a usable `fork(2)`.
+    pub has_fork: bool,
+}
 
-    /// Whether to enable Python development mode.
+impl OsInfo {
+    /// Obtain an instance describing the currently running process.
+    pub fn current() -> Self {
+        Self {
+            os: std::env::consts::OS.to_string(),
+            has_fork: cfg!(unix),
+        }
+    }
+}
+
+/// An estimate of how a configuration affects interpreter startup time.
+///
+/// Obtained via [PythonInterpreterConfig::estimated_startup_overhead()].
+/// This is advisory only: it is derived from the configuration alone and
+/// not from measurements.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct StartupProfile {
+    /// Whether no settings known to add startup overhead are enabled.
+    pub baseline: bool,
+
+    /// Whether debugging features that add overhead are enabled.
     ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.dev_mode>.
-    pub development_mode: Option<bool>,
+    /// e.g. development mode, debug memory allocators, or verbose output.
+    pub debug_overhead: bool,
 
-    /// Isolated mode.
+    /// Whether tracing features that add overhead are enabled.
     ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.isolated>.
-    pub isolated: Option<bool>,
+    /// e.g. `tracemalloc` or import timing.
+    pub trace_overhead: bool,
 
-    /// Whether to use legacy filesystem encodings on Windows.
+    /// Rough multiplier of startup time relative to the baseline.
     ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.legacy_windows_fs_encoding>.
-    pub legacy_windows_fs_encoding: Option<bool>,
+    /// This is one of a fixed set of tiers derived from the other fields:
+    ///
+    /// * `1.0` if [Self::baseline].
+    /// * `1.5` if only [Self::debug_overhead].
+    /// * `2.0` if only [Self::trace_overhead].
+    /// * `3.0` if both.
+    ///
+    /// The tier values are placeholders: they haven't been measured and only
+    /// rank configurations relative to each other. Tracing ranks above
+    /// debugging because it does work for every import or allocation. The
+    /// values may change if measurements become available.
+    pub expected_slowdown_factor: f32,
+}
 
-    /// Whether argv should be parsed the way `python` parses them.
+/// How `sys.stdout` buffers writes.
+///
+/// Obtained via [PythonInterpreterConfig::stdout_will_buffer()].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum StdoutBuffering {
+    /// Writes are passed to the file descriptor immediately.
+    Unbuffered,
+    /// Writes are flushed at each newline.
+    LineBuffered,
+    /// Writes are flushed when the buffer fills up or on explicit flush.
+    FullyBuffered,
+}
+
+/// A security-relevant property of a [PythonInterpreterConfig].
+///
+/// Obtained via [PythonInterpreterConfig::security_audit()].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum SecurityWarning {
+    /// The isolated profile is used but `use_environment` is enabled.
     ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.parse_argv>.
-    pub parse_argv: Option<bool>,
+    /// `PYTHON*` environment variables such as `PYTHONPATH` or
+    /// `PYTHONSTARTUP` can alter the behavior of the interpreter and expose
+    /// it to code or configuration controlled by whoever controls the
+    /// environment.
+    EnvironmentInIsolatedProfile,
 
-    /// Whether environment variables are read to control the interpreter configuration.
+    /// `install_signal_handlers` is disabled.
     ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.use_environment>.
-    pub use_environment: Option<bool>,
+    /// Python won't install a `SIGINT` handler, so Ctrl-C won't raise
+    /// `KeyboardInterrupt`.
+    SignalHandlersDisabled,
 
-    /// Controls Python UTF-8 mode.
+    /// `hash_seed` is `0`, which disables hash randomization.
     ///
-    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.utf8_mode>.
+    /// This makes the interpreter susceptible to hash collision denial of
+    /// service attacks.
+    HashRandomizationDisabled,
+}
+
+impl Display for SecurityWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::EnvironmentInIsolatedProfile => {
+                "use_environment is enabled in the isolated profile; PYTHON* environment variables are honored"
+            }
+            Self::SignalHandlersDisabled => {
+                "install_signal_handlers is disabled; Ctrl-C will not interrupt the interpreter"
+            }
+            Self::HashRandomizationDisabled => {
+                "hash_seed is 0; hash randomization is disabled"
+            }
+        })
+    }
+}
+
+/// Settings preventing a [PythonInterpreterConfig] from behaving reproducibly.
+///
+/// Obtained via [PythonInterpreterConfig::reproducibility_report()].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReproducibilityReport {
+    /// `(field, reason)` pairs for each field that needs to change.
+    pub blockers: Vec<(&'static str, &'static str)>,
+}
+
+impl ReproducibilityReport {
+    /// Whether no fields prevent reproducibility.
+    pub fn is_reproducible(&self) -> bool {
+        self.blockers.is_empty()
+    }
+}
+
+impl Display for ReproducibilityReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_reproducible() {
+            return f.write_str("configuration is reproducible");
+        }
+
+        let blockers = self
+            .blockers
+            .iter()
+            .map(|(field, reason)| format!("{}: {}", field, reason))
+            .collect::<Vec<_>>();
+
+        f.write_str(&blockers.join("; "))
+    }
+}
+
+/// A [PythonInterpreterConfig] whose [Debug] output hides sensitive-looking paths.
+///
+/// Obtained via [PythonInterpreterConfig::debug_safe()]. Paths are
+/// formatted with the home directory hidden, as with
+/// [PythonInterpreterConfig::clone_scrubbed()]. In addition, path
+/// components that look like they may hold credentials are replaced with
+/// `<redacted>`: `.ssh` and any component containing `token` or `secret`,
+/// case-insensitively. The same caveats as for `clone_scrubbed()` apply to
+/// fields that aren't paths.
+#[derive(Clone, Copy)]
+pub struct DebugSafe<'a> {
+    config: &'a PythonInterpreterConfig,
+    home: &'a Path,
+}
+
+impl<'a> std::fmt::Debug for DebugSafe<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut config = self.config.clone_scrubbed(self.home);
+        config.remap_paths(redact_secret_path_components);
+
+        std::fmt::Debug::fmt(&config, f)
+    }
+}
+
+/// An error normalizing a [PythonInterpreterConfig] for a platform.
+///
+/// Obtained via [PythonInterpreterConfig::normalize_for_platform()].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NormalizationError {
+    /// The platform isn't a recognizable Rust target triple.
+    UnknownPlatform(String),
+
+    /// Two fields have values that can't both be honored on the platform.
+    Conflict {
+        /// The target triple.
+        platform: String,
+        /// The field whose value takes effect.
+        field: &'static str,
+        /// The field whose value would be overridden.
+        other: &'static str,
+    },
+}
+
+impl Display for NormalizationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPlatform(platform) => {
+                write!(f, "{} is not a recognized target triple", platform)
+            }
+            Self::Conflict {
+                platform,
+                field,
+                other,
+            } => write!(
+                f,
+                "{} conflicts with {} on {}; {} would be ignored",
+                field, other, platform, other
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NormalizationError {}
+
+/// Python versions a [PythonInterpreterConfig] can be used with.
+///
+/// Obtained via [PythonInterpreterConfig::python_version_constraints()].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct PythonVersionConstraints {
+    /// Minimum `(major, minor)` Python version supporting every set field.
+    pub min_version: (u32, u32),
+}
+
+impl PythonVersionConstraints {
+    /// Whether the given Python version satisfies these constraints.
+    pub fn is_satisfied_by(&self, major: u32, minor: u32) -> bool {
+        (major, minor) >= self.min_version
+    }
+}
+
+/// The first Python version providing the `PyConfig` initialization API.
+const PYCONFIG_MIN_VERSION: (u32, u32) = (3, 8);
+
+/// `-X` options introduced after [PYCONFIG_MIN_VERSION] and the version adding them.
+const X_OPTION_MIN_VERSIONS: &[(&str, (u32, u32))] = &[
+    ("warn_default_encoding", (3, 10)),
+    ("frozen_modules", (3, 11)),
+    ("int_max_str_digits", (3, 11)),
+    ("no_debug_ranges", (3, 11)),
+    ("perf", (3, 12)),
+    ("cpu_count", (3, 13)),
+    ("gil", (3, 13)),
+];
+
+/// The action of a [WarnFilter].
+///
+/// See <https://docs.python.org/3/library/warnings.html#the-warnings-filter>.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum WarnFilterAction {
+    /// Print the first occurrence of matching warnings for each location.
+    #[default]
+    Default,
+    /// Turn matching warnings into exceptions.
+    Error,
+    /// Never print matching warnings.
+    Ignore,
+    /// Always print matching warnings.
+    Always,
+    /// Print the first occurrence of matching warnings for each module.
+    Module,
+    /// Print only the first occurrence of matching warnings.
+    Once,
+}
+
+impl WarnFilterAction {
+    /// All variants known to this version of the crate.
+    ///
+    /// Ordered the way CPython resolves abbreviated actions.
+    pub fn all_known() -> &'static [Self] {
+        &[
+            Self::Default,
+            Self::Always,
+            Self::Ignore,
+            Self::Module,
+            Self::Once,
+            Self::Error,
+        ]
+    }
+
+    /// The string representation of this value.
+    ///
+    /// This is the same as [Self::to_string()] but doesn't allocate.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Error => "error",
+            Self::Ignore => "ignore",
+            Self::Always => "always",
+            Self::Module => "module",
+            Self::Once => "once",
+        }
+    }
+}
+
+impl Display for WarnFilterAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl_str_partial_eq!(WarnFilterAction, case_sensitive);
+
+/// A warning filter, as passed to `-W` or contained in `PYTHONWARNINGS`.
+///
+/// The syntax is `action:message:category:module:lineno`. Trailing fields
+/// may be omitted and empty fields match everything. Like CPython, the
+/// action may be abbreviated to a prefix (e.g. `i` for `ignore`).
+///
+/// See <https://docs.python.org/3/library/warnings.html#describing-warning-filters>.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct WarnFilter {
+    /// What to do with matching warnings.
+    pub action: WarnFilterAction,
+
+    /// Text the start of the warning message must match, case-insensitively.
+    pub message: String,
+
+    /// Name of the warning category class, e.g. `DeprecationWarning`.
+    pub category: String,
+
+    /// Fully qualified name the module issuing the warning must match.
+    pub module: String,
+
+    /// Line number the warning must be issued from. `0` matches all lines.
+    pub lineno: u32,
+}
+
+impl Display for WarnFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lineno = if self.lineno == 0 {
+            String::new()
+        } else {
+            self.lineno.to_string()
+        };
+
+        let fields = [
+            self.action.to_string(),
+            self.message.clone(),
+            self.category.clone(),
+            self.module.clone(),
+            lineno,
+        ];
+
+        // Omit trailing empty fields. The action is always present.
+        let count = fields
+            .iter()
+            .rposition(|field| !field.is_empty())
+            .unwrap_or(0)
+            + 1;
+
+        f.write_str(&fields[0..count].join(":"))
+    }
+}
+
+impl FromStr for WarnFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split(':').map(|field| field.trim()).collect::<Vec<_>>();
+
+        if fields.len() > 5 {
+            return Err(format!("{} has too many fields", s));
+        }
+
+        let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+
+        let action = match field(0) {
+            "" => WarnFilterAction::Default,
+            action => *WarnFilterAction::all_known()
+                .iter()
+                .find(|a| a.as_str().starts_with(action))
+                .ok_or_else(|| format!("{} is not a valid warning filter action", action))?,
+        };
+
+        let lineno = match field(4) {
+            "" => 0,
+            lineno => lineno
+                .parse::<u32>()
+                .map_err(|_| format!("{} is not a valid line number", lineno))?,
+        };
+
+        Ok(Self {
+            action,
+            message: field(1).to_string(),
+            category: field(2).to_string(),
+            module: field(3).to_string(),
+            lineno,
+        })
+    }
+}
+
+/// Whether two `-W` filter strings are equivalent.
+///
+/// Filters are compared in normalized form if both are valid. Otherwise
+/// they are compared verbatim.
+fn warn_filters_equal(a: &str, b: &str) -> bool {
+    match (WarnFilter::from_str(a), WarnFilter::from_str(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// What the interpreter runs after initialization.
+///
+/// Obtained via [PythonInterpreterConfig::active_run_target()].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum RunTarget {
+    /// Python code to execute. Corresponds to `python -c`.
+    Command(String),
+    /// Name of a module to run as `__main__`. Corresponds to `python -m`.
+    Module(String),
+    /// Path of a script to run. Corresponds to `python <filename>`.
+    Filename(PathBuf),
+}
+
+// Bit flags representing boolean fields of `PyPreConfig` and `PyConfig`.
+//
+// CPython doesn't define bitmasks for these structs: every field is its own
+// `int` member. (The `Py_TPFLAGS_*` constants are type object flags and are
+// unrelated.) The values below are defined by this crate and are stable, so
+// they can be used to exchange these settings across an FFI boundary.
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.configure_locale`.
+pub const PYPRECONFIG_FLAG_CONFIGURE_LOCALE: u32 = 1 << 0;
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.coerce_c_locale_warn`.
+pub const PYPRECONFIG_FLAG_COERCE_C_LOCALE_WARN: u32 = 1 << 1;
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.dev_mode`.
+pub const PYPRECONFIG_FLAG_DEV_MODE: u32 = 1 << 2;
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.isolated`.
+pub const PYPRECONFIG_FLAG_ISOLATED: u32 = 1 << 3;
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.legacy_windows_fs_encoding`.
+pub const PYPRECONFIG_FLAG_LEGACY_WINDOWS_FS_ENCODING: u32 = 1 << 4;
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.parse_argv`.
+pub const PYPRECONFIG_FLAG_PARSE_ARGV: u32 = 1 << 5;
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.use_environment`.
+pub const PYPRECONFIG_FLAG_USE_ENVIRONMENT: u32 = 1 << 6;
+
+/// Bit set by [PythonInterpreterConfig::to_pypreconfig_flags()] for `PyPreConfig.utf8_mode`.
+pub const PYPRECONFIG_FLAG_UTF8_MODE: u32 = 1 << 7;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.buffered_stdio`.
+pub const PYCONFIG_FLAG_BUFFERED_STDIO: u32 = 1 << 0;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.configure_c_stdio`.
+pub const PYCONFIG_FLAG_CONFIGURE_C_STDIO: u32 = 1 << 1;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.dev_mode`.
+pub const PYCONFIG_FLAG_DEV_MODE: u32 = 1 << 2;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.dump_refs`.
+pub const PYCONFIG_FLAG_DUMP_REFS: u32 = 1 << 3;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.faulthandler`.
+pub const PYCONFIG_FLAG_FAULTHANDLER: u32 = 1 << 4;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.import_time`.
+pub const PYCONFIG_FLAG_IMPORT_TIME: u32 = 1 << 5;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.inspect`.
+pub const PYCONFIG_FLAG_INSPECT: u32 = 1 << 6;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.install_signal_handlers`.
+pub const PYCONFIG_FLAG_INSTALL_SIGNAL_HANDLERS: u32 = 1 << 7;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.interactive`.
+pub const PYCONFIG_FLAG_INTERACTIVE: u32 = 1 << 8;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.isolated`.
+pub const PYCONFIG_FLAG_ISOLATED: u32 = 1 << 9;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.legacy_windows_stdio`.
+pub const PYCONFIG_FLAG_LEGACY_WINDOWS_STDIO: u32 = 1 << 10;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.malloc_stats`.
+pub const PYCONFIG_FLAG_MALLOC_STATS: u32 = 1 << 11;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.parse_argv`.
+pub const PYCONFIG_FLAG_PARSE_ARGV: u32 = 1 << 12;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.parser_debug`.
+pub const PYCONFIG_FLAG_PARSER_DEBUG: u32 = 1 << 13;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.pathconfig_warnings`.
+pub const PYCONFIG_FLAG_PATHCONFIG_WARNINGS: u32 = 1 << 14;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.quiet`.
+pub const PYCONFIG_FLAG_QUIET: u32 = 1 << 15;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.show_ref_count`.
+pub const PYCONFIG_FLAG_SHOW_REF_COUNT: u32 = 1 << 16;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.site_import`.
+pub const PYCONFIG_FLAG_SITE_IMPORT: u32 = 1 << 17;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.skip_source_first_line`.
+pub const PYCONFIG_FLAG_SKIP_SOURCE_FIRST_LINE: u32 = 1 << 18;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.tracemalloc`.
+pub const PYCONFIG_FLAG_TRACEMALLOC: u32 = 1 << 19;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.use_environment`.
+pub const PYCONFIG_FLAG_USE_ENVIRONMENT: u32 = 1 << 20;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.user_site_directory`.
+pub const PYCONFIG_FLAG_USER_SITE_DIRECTORY: u32 = 1 << 21;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.verbose`.
+pub const PYCONFIG_FLAG_VERBOSE: u32 = 1 << 22;
+
+/// Bit set by [PythonInterpreterConfig::to_pyconfig_flags()] for `PyConfig.write_bytecode`.
+pub const PYCONFIG_FLAG_WRITE_BYTECODE: u32 = 1 << 23;
+
+/// Holds configuration of a Python interpreter.
+///
+/// This struct holds fields that are exposed by `PyPreConfig` and
+/// `PyConfig` in the CPython API.
+///
+/// Other than the profile (which is used to initialize instances of
+/// `PyPreConfig` and `PyConfig`), all fields are optional. Only fields
+/// with `Some(T)` will be updated from the defaults.
+///
+/// When serialized, fields are grouped by the CPython struct they belong to
+/// rather than following declaration order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Deserialize))]
+#[cfg_attr(feature = "serialization", serde(default))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct PythonInterpreterConfig {
+    /// Profile to use to initialize pre-config and config state of interpreter.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(deserialize_with = "field_de::profile")
+    )]
+    pub profile: PythonInterpreterProfile,
+
+    // The following fields are from PyPreConfig or are shared with PyConfig.
+    /// Name of the memory allocator.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.allocator>.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(deserialize_with = "field_de::allocator")
+    )]
+    pub allocator: Option<Allocator>,
+
+    /// Whether to set the LC_CTYPE locale to the user preferred locale.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.configure_locale>.
+    pub configure_locale: Option<bool>,
+
+    /// How to coerce the locale settings.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.coerce_c_locale>.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(deserialize_with = "field_de::coerce_c_locale")
+    )]
+    pub coerce_c_locale: Option<CoerceCLocale>,
+
+    /// Whether to emit a warning if the C locale is coerced.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.coerce_c_locale_warn>.
+    pub coerce_c_locale_warn: Option<bool>,
+
+    /// Whether to enable Python development mode.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.dev_mode>.
+    pub development_mode: Option<bool>,
+
+    /// Isolated mode.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.isolated>.
+    pub isolated: Option<bool>,
+
+    /// Whether to use legacy filesystem encodings on Windows.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.legacy_windows_fs_encoding>.
+    pub legacy_windows_fs_encoding: Option<bool>,
+
+    /// Whether argv should be parsed the way `python` parses them.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.parse_argv>.
+    pub parse_argv: Option<bool>,
+
+    /// Whether environment variables are read to control the interpreter configuration.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.use_environment>.
+    pub use_environment: Option<bool>,
+
+    /// Controls Python UTF-8 mode.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#c.PyPreConfig.utf8_mode>.
     pub utf8_mode: Option<bool>,
     // The following fields are from PyConfig.
     /// Command line arguments.
@@ -737,6 +2386,16 @@ pub struct PythonInterpreterConfig {
     /// These will become `sys.argv`.
     ///
     /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.argv>.
+    ///
+    /// Each argument is serialized as a string if it is valid UTF-8 or as
+    /// `{"bytes": [...]}` otherwise. `{"utf8": "..."}` is also accepted when
+    /// deserializing. On Windows, arguments are UTF-16 and unpaired surrogates
+    /// will fail to serialize.
+    #[cfg_attr(feature = "serialization", serde(with = "os_string_serde"))]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(with = "Option<Vec<os_string_serde::OsStringValue>>")
+    )]
     pub argv: Option<Vec<OsString>>,
 
     /// Controls `sys.base_exec_prefix`.
@@ -762,11 +2421,19 @@ pub struct PythonInterpreterConfig {
     /// Controls warnings/errors for some bytes type coercions.
     ///
     /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.bytes_warning>.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(deserialize_with = "field_de::bytes_warning")
+    )]
     pub bytes_warning: Option<BytesWarning>,
 
     /// Validation mode for `.pyc` files.
     ///
     /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.check_hash_pycs_mode>.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(deserialize_with = "field_de::check_hash_pycs_mode")
+    )]
     pub check_hash_pycs_mode: Option<CheckHashPycsMode>,
 
     /// Controls binary mode and buffering on C standard streams.
@@ -862,6 +2529,10 @@ pub struct PythonInterpreterConfig {
     ///
     /// This setting is only relevant if `write_bytecode` is true and Python modules are
     /// being imported from the filesystem using Python’s standard filesystem importer.
+    #[cfg_attr(
+        feature = "serialization",
+        serde(deserialize_with = "field_de::optimization_level")
+    )]
     pub optimization_level: Option<BytecodeOptimizationLevel>,
 
     /// Parser debug mode.
@@ -973,6 +2644,9726 @@ pub struct PythonInterpreterConfig {
 
     /// Values of the `-X` command line options / `sys._xoptions`.
     ///
+    /// Options without a dedicated field go here. For example, `perf` enables
+    /// the Linux `perf` profiler trampoline on Python 3.12+ (`PyConfig.perf_profiling`),
+    /// after which `perf record --call-graph dwarf` attributes samples to Python
+    /// functions. Options newer than the targeted Python are reported by
+    /// [PythonInterpreterConfig::validate_for_version()].
+    ///
     /// See <https://docs.python.org/3/c-api/init_config.html#c.PyConfig.xoptions>.
     pub x_options: Option<Vec<String>>,
 }
+
+impl PythonInterpreterConfig {
+    /// Construct an instance with the defaults of a profile.
+    ///
+    /// [Self::default()] uses the isolated profile and leaves all other
+    /// fields unset. Switching such an instance to
+    /// [PythonInterpreterProfile::Python] by changing only [Self::profile] is
+    /// a common mistake.
+    ///
+    /// This explicitly sets the fields that CPython's
+    /// `PyConfig_InitPythonConfig()` and `PyConfig_InitIsolatedConfig()`
+    /// initialize differently, plus `site_import`. e.g. for
+    /// [PythonInterpreterProfile::Python], `use_environment`, `site_import`,
+    /// and `user_site_directory` are `Some(true)`. The resulting configuration
+    /// reflects the intent of the profile even if the profile is later
+    /// changed. `parse_argv` is left unset since it requires `argv`.
+    ///
+    /// See <https://docs.python.org/3/c-api/init_config.html#init-python-config>
+    /// and <https://docs.python.org/3/c-api/init_config.html#isolated-configuration>.
+    pub fn for_profile(profile: PythonInterpreterProfile) -> Self {
+        let enabled = profile == PythonInterpreterProfile::Python;
+
+        Self {
+            profile,
+            configure_locale: Some(enabled),
+            isolated: Some(!enabled),
+            use_environment: Some(enabled),
+            configure_c_stdio: Some(enabled),
+            install_signal_handlers: Some(enabled),
+            pathconfig_warnings: Some(enabled),
+            site_import: Some(true),
+            user_site_directory: Some(enabled),
+            ..Default::default()
+        }
+    }
+
+    /// Construct a fully specified, deterministic instance for use in tests.
+    ///
+    /// Every field except the `run_*` fields is set, so the configuration
+    /// doesn't change when defaults do and no value is inherited from the
+    /// build or run environment. The run fields are left unset since at most
+    /// one may be set and tests generally set the one they need. The result
+    /// passes [Self::validate()].
+    ///
+    /// Values are chosen to keep an interpreter hermetic:
+    ///
+    /// * The isolated profile with `isolated` set and `use_environment`,
+    ///   `site_import`, and `user_site_directory` disabled, so neither
+    ///   `PYTHON*` environment variables nor site-packages are consulted.
+    /// * `configure_locale`, `configure_c_stdio`, and
+    ///   `install_signal_handlers` disabled, so process-wide state isn't
+    ///   modified. `utf8_mode` is enabled and the encodings are set to
+    ///   `utf-8`, so behavior doesn't depend on the locale.
+    /// * `hash_seed` is `12345`, so `str` hashes are reproducible.
+    /// * Output producing and debugging features (`verbose`, `import_time`,
+    ///   `tracemalloc`, etc.) are disabled and `quiet` is enabled.
+    /// * `write_bytecode` is disabled and `pathconfig_warnings` is disabled.
+    /// * Paths are rooted at `/python` and don't need to exist.
+    pub fn for_testing() -> Self {
+        Self {
+            profile: PythonInterpreterProfile::Isolated,
+            allocator: Some(Allocator::PyMalloc),
+            configure_locale: Some(false),
+            coerce_c_locale: Some(CoerceCLocale::LCCtype),
+            coerce_c_locale_warn: Some(false),
+            development_mode: Some(false),
+            isolated: Some(true),
+            legacy_windows_fs_encoding: Some(false),
+            parse_argv: Some(false),
+            use_environment: Some(false),
+            utf8_mode: Some(true),
+            argv: Some(vec![OsString::from("python")]),
+            base_exec_prefix: Some(PathBuf::from("/python")),
+            base_executable: Some(PathBuf::from("/python/bin/python")),
+            base_prefix: Some(PathBuf::from("/python")),
+            buffered_stdio: Some(true),
+            bytes_warning: Some(BytesWarning::Warn),
+            check_hash_pycs_mode: Some(CheckHashPycsMode::Never),
+            configure_c_stdio: Some(false),
+            dump_refs: Some(false),
+            exec_prefix: Some(PathBuf::from("/python")),
+            executable: Some(PathBuf::from("/python/bin/python")),
+            fault_handler: Some(false),
+            filesystem_encoding: Some("utf-8".to_string()),
+            filesystem_errors: Some("surrogateescape".to_string()),
+            hash_seed: Some(12345),
+            home: Some(PathBuf::from("/python")),
+            import_time: Some(false),
+            inspect: Some(false),
+            install_signal_handlers: Some(false),
+            interactive: Some(false),
+            legacy_windows_stdio: Some(false),
+            malloc_stats: Some(false),
+            module_search_paths: Some(vec![PathBuf::from("/python/lib")]),
+            optimization_level: Some(BytecodeOptimizationLevel::Zero),
+            parser_debug: Some(false),
+            pathconfig_warnings: Some(false),
+            prefix: Some(PathBuf::from("/python")),
+            program_name: Some(PathBuf::from("/python/bin/python")),
+            pycache_prefix: Some(PathBuf::from("/python/pycache")),
+            python_path_env: Some(String::new()),
+            quiet: Some(true),
+            run_command: None,
+            run_filename: None,
+            run_module: None,
+            show_ref_count: Some(false),
+            site_import: Some(false),
+            skip_first_source_line: Some(false),
+            stdio_encoding: Some("utf-8".to_string()),
+            stdio_errors: Some("strict".to_string()),
+            tracemalloc: Some(false),
+            user_site_directory: Some(false),
+            verbose: Some(false),
+            warn_options: Some(vec![]),
+            write_bytecode: Some(false),
+            x_options: Some(vec![]),
+        }
+    }
+
+    /// Validate that the configuration is semantically consistent.
+    ///
+    /// Fields are individually well-typed. But some combinations of fields are
+    /// contradictory and would either be rejected by CPython at run-time or
+    /// silently ignored. This function detects such combinations.
+    ///
+    /// Returns `Ok(())` if the configuration is consistent or `Err` with a
+    /// human readable description of every problem found.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = vec![];
+
+        let run_fields = [
+            ("run_command", self.run_command.is_some()),
+            ("run_module", self.run_module.is_some()),
+            ("run_filename", self.run_filename.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| if set { Some(name) } else { None })
+        .collect::<Vec<_>>();
+
+        if run_fields.len() > 1 {
+            errors.push(format!(
+                "only 1 of run_command, run_module, and run_filename can be set; found {}",
+                run_fields.join(", ")
+            ));
+        }
+
+        if self.isolated == Some(true) {
+            if self.use_environment == Some(true) {
+                errors.push(
+                    "use_environment cannot be true when isolated is true; isolated mode ignores the environment"
+                        .to_string(),
+                );
+            }
+            if self.user_site_directory == Some(true) {
+                errors.push(
+                    "user_site_directory cannot be true when isolated is true; isolated mode disables the user site directory"
+                        .to_string(),
+                );
+            }
+        }
+
+        if self.parse_argv == Some(true) && self.argv.is_none() {
+            errors.push("parse_argv is true but argv is not set".to_string());
+        }
+
+        if self.skip_first_source_line == Some(true) && self.run_filename.is_none() {
+            errors.push("skip_first_source_line is true but run_filename is not set".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validate the configuration for use with a memory allocator backend.
+    ///
+    /// In addition to the checks of [Self::validate()], this verifies that
+    /// [Self::allocator] is compatible with `backend`. See
+    /// [Allocator::is_compatible_with()].
+    pub fn validate_with_allocator_backend(
+        &self,
+        backend: &MemoryAllocatorBackend,
+    ) -> Result<(), Vec<String>> {
+        let mut errors = match self.validate() {
+            Ok(()) => vec![],
+            Err(errors) => errors,
+        };
+
+        if let Some(allocator) = self.allocator {
+            if !allocator.is_compatible_with(backend) {
+                errors.push(format!(
+                    "allocator {} is not compatible with the {} memory allocator backend; its debug hooks would be removed",
+                    allocator, backend
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Overlay another configuration on top of this one.
+    ///
+    /// Every field that is `Some` in `other` replaces the value in `self`.
+    /// Fields that are `None` in `other` retain the value from `self`.
+    ///
+    /// [Self::profile] isn't optional and is always retained from `self`,
+    /// since there is no way to express that `other` doesn't define it.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            profile: self.profile,
+            allocator: other.allocator.or(self.allocator),
+            configure_locale: other.configure_locale.or(self.configure_locale),
+            coerce_c_locale: other.coerce_c_locale.or(self.coerce_c_locale),
+            coerce_c_locale_warn: other.coerce_c_locale_warn.or(self.coerce_c_locale_warn),
+            development_mode: other.development_mode.or(self.development_mode),
+            isolated: other.isolated.or(self.isolated),
+            legacy_windows_fs_encoding: other
+                .legacy_windows_fs_encoding
+                .or(self.legacy_windows_fs_encoding),
+            parse_argv: other.parse_argv.or(self.parse_argv),
+            use_environment: other.use_environment.or(self.use_environment),
+            utf8_mode: other.utf8_mode.or(self.utf8_mode),
+            argv: other.argv.clone().or_else(|| self.argv.clone()),
+            base_exec_prefix: other
+                .base_exec_prefix
+                .clone()
+                .or_else(|| self.base_exec_prefix.clone()),
+            base_executable: other
+                .base_executable
+                .clone()
+                .or_else(|| self.base_executable.clone()),
+            base_prefix: other
+                .base_prefix
+                .clone()
+                .or_else(|| self.base_prefix.clone()),
+            buffered_stdio: other.buffered_stdio.or(self.buffered_stdio),
+            bytes_warning: other.bytes_warning.or(self.bytes_warning),
+            check_hash_pycs_mode: other.check_hash_pycs_mode.or(self.check_hash_pycs_mode),
+            configure_c_stdio: other.configure_c_stdio.or(self.configure_c_stdio),
+            dump_refs: other.dump_refs.or(self.dump_refs),
+            exec_prefix: other
+                .exec_prefix
+                .clone()
+                .or_else(|| self.exec_prefix.clone()),
+            executable: other.executable.clone().or_else(|| self.executable.clone()),
+            fault_handler: other.fault_handler.or(self.fault_handler),
+            filesystem_encoding: other
+                .filesystem_encoding
+                .clone()
+                .or_else(|| self.filesystem_encoding.clone()),
+            filesystem_errors: other
+                .filesystem_errors
+                .clone()
+                .or_else(|| self.filesystem_errors.clone()),
+            hash_seed: other.hash_seed.or(self.hash_seed),
+            home: other.home.clone().or_else(|| self.home.clone()),
+            import_time: other.import_time.or(self.import_time),
+            inspect: other.inspect.or(self.inspect),
+            install_signal_handlers: other
+                .install_signal_handlers
+                .or(self.install_signal_handlers),
+            interactive: other.interactive.or(self.interactive),
+            legacy_windows_stdio: other.legacy_windows_stdio.or(self.legacy_windows_stdio),
+            malloc_stats: other.malloc_stats.or(self.malloc_stats),
+            module_search_paths: other
+                .module_search_paths
+                .clone()
+                .or_else(|| self.module_search_paths.clone()),
+            optimization_level: other.optimization_level.or(self.optimization_level),
+            parser_debug: other.parser_debug.or(self.parser_debug),
+            pathconfig_warnings: other.pathconfig_warnings.or(self.pathconfig_warnings),
+            prefix: other.prefix.clone().or_else(|| self.prefix.clone()),
+            program_name: other
+                .program_name
+                .clone()
+                .or_else(|| self.program_name.clone()),
+            pycache_prefix: other
+                .pycache_prefix
+                .clone()
+                .or_else(|| self.pycache_prefix.clone()),
+            python_path_env: other
+                .python_path_env
+                .clone()
+                .or_else(|| self.python_path_env.clone()),
+            quiet: other.quiet.or(self.quiet),
+            run_command: other
+                .run_command
+                .clone()
+                .or_else(|| self.run_command.clone()),
+            run_filename: other
+                .run_filename
+                .clone()
+                .or_else(|| self.run_filename.clone()),
+            run_module: other.run_module.clone().or_else(|| self.run_module.clone()),
+            show_ref_count: other.show_ref_count.or(self.show_ref_count),
+            site_import: other.site_import.or(self.site_import),
+            skip_first_source_line: other.skip_first_source_line.or(self.skip_first_source_line),
+            stdio_encoding: other
+                .stdio_encoding
+                .clone()
+                .or_else(|| self.stdio_encoding.clone()),
+            stdio_errors: other
+                .stdio_errors
+                .clone()
+                .or_else(|| self.stdio_errors.clone()),
+            tracemalloc: other.tracemalloc.or(self.tracemalloc),
+            user_site_directory: other.user_site_directory.or(self.user_site_directory),
+            verbose: other.verbose.or(self.verbose),
+            warn_options: other
+                .warn_options
+                .clone()
+                .or_else(|| self.warn_options.clone()),
+            write_bytecode: other.write_bytecode.or(self.write_bytecode),
+            x_options: other.x_options.clone().or_else(|| self.x_options.clone()),
+        }
+    }
+
+    /// Serialize this instance to a TOML document.
+    ///
+    /// Unset fields are omitted from the output.
+    ///
+    /// TOML integers are signed 64-bit values, so [Self::hash_seed] values
+    /// larger than `i64::MAX` cannot be represented.
+    #[cfg(feature = "toml")]
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        // Going through `toml::Value` ensures tables (e.g. `argv` entries) are
+        // emitted after plain values, as TOML requires.
+        toml::to_string(&toml::Value::try_from(self)?)
+    }
+
+    /// Construct an instance from a TOML document.
+    ///
+    /// Keys not present in the document are left unset.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    /// Obtain a bitmask of the boolean fields that map to `PyPreConfig`.
+    ///
+    /// A bit is set only if the corresponding field is `Some(true)`. Fields
+    /// that are `None` or `Some(false)` leave their bit cleared, so unset
+    /// fields can't be distinguished from disabled ones.
+    ///
+    /// See the `PYPRECONFIG_FLAG_*` constants for bit values.
+    pub fn to_pypreconfig_flags(&self) -> u32 {
+        [
+            (self.configure_locale, PYPRECONFIG_FLAG_CONFIGURE_LOCALE),
+            (
+                self.coerce_c_locale_warn,
+                PYPRECONFIG_FLAG_COERCE_C_LOCALE_WARN,
+            ),
+            (self.development_mode, PYPRECONFIG_FLAG_DEV_MODE),
+            (self.isolated, PYPRECONFIG_FLAG_ISOLATED),
+            (
+                self.legacy_windows_fs_encoding,
+                PYPRECONFIG_FLAG_LEGACY_WINDOWS_FS_ENCODING,
+            ),
+            (self.parse_argv, PYPRECONFIG_FLAG_PARSE_ARGV),
+            (self.use_environment, PYPRECONFIG_FLAG_USE_ENVIRONMENT),
+            (self.utf8_mode, PYPRECONFIG_FLAG_UTF8_MODE),
+        ]
+        .into_iter()
+        .filter(|(value, _)| *value == Some(true))
+        .fold(0, |acc, (_, flag)| acc | flag)
+    }
+
+    /// Obtain a bitmask of the boolean fields that map to `PyConfig`.
+    ///
+    /// Semantics are the same as [Self::to_pypreconfig_flags()].
+    ///
+    /// See the `PYCONFIG_FLAG_*` constants for bit values.
+    pub fn to_pyconfig_flags(&self) -> u32 {
+        [
+            (self.buffered_stdio, PYCONFIG_FLAG_BUFFERED_STDIO),
+            (self.configure_c_stdio, PYCONFIG_FLAG_CONFIGURE_C_STDIO),
+            (self.development_mode, PYCONFIG_FLAG_DEV_MODE),
+            (self.dump_refs, PYCONFIG_FLAG_DUMP_REFS),
+            (self.fault_handler, PYCONFIG_FLAG_FAULTHANDLER),
+            (self.import_time, PYCONFIG_FLAG_IMPORT_TIME),
+            (self.inspect, PYCONFIG_FLAG_INSPECT),
+            (
+                self.install_signal_handlers,
+                PYCONFIG_FLAG_INSTALL_SIGNAL_HANDLERS,
+            ),
+            (self.interactive, PYCONFIG_FLAG_INTERACTIVE),
+            (self.isolated, PYCONFIG_FLAG_ISOLATED),
+            (
+                self.legacy_windows_stdio,
+                PYCONFIG_FLAG_LEGACY_WINDOWS_STDIO,
+            ),
+            (self.malloc_stats, PYCONFIG_FLAG_MALLOC_STATS),
+            (self.parse_argv, PYCONFIG_FLAG_PARSE_ARGV),
+            (self.parser_debug, PYCONFIG_FLAG_PARSER_DEBUG),
+            (self.pathconfig_warnings, PYCONFIG_FLAG_PATHCONFIG_WARNINGS),
+            (self.quiet, PYCONFIG_FLAG_QUIET),
+            (self.show_ref_count, PYCONFIG_FLAG_SHOW_REF_COUNT),
+            (self.site_import, PYCONFIG_FLAG_SITE_IMPORT),
+            (
+                self.skip_first_source_line,
+                PYCONFIG_FLAG_SKIP_SOURCE_FIRST_LINE,
+            ),
+            (self.tracemalloc, PYCONFIG_FLAG_TRACEMALLOC),
+            (self.use_environment, PYCONFIG_FLAG_USE_ENVIRONMENT),
+            (self.user_site_directory, PYCONFIG_FLAG_USER_SITE_DIRECTORY),
+            (self.verbose, PYCONFIG_FLAG_VERBOSE),
+            (self.write_bytecode, PYCONFIG_FLAG_WRITE_BYTECODE),
+        ]
+        .into_iter()
+        .filter(|(value, _)| *value == Some(true))
+        .fold(0, |acc, (_, flag)| acc | flag)
+    }
+
+    /// Compute the fields that differ between this instance and another.
+    ///
+    /// Returns a [ConfigDiff] for every field whose value differs, in field
+    /// declaration order. An empty result means the instances are equal.
+    pub fn diff(&self, other: &Self) -> Vec<ConfigDiff> {
+        let mut diffs = vec![];
+
+        if self.profile != other.profile {
+            diffs.push(ConfigDiff::Profile {
+                before: self.profile,
+                after: other.profile,
+            });
+        }
+        if self.allocator != other.allocator {
+            diffs.push(ConfigDiff::Allocator {
+                before: self.allocator,
+                after: other.allocator,
+            });
+        }
+        if self.configure_locale != other.configure_locale {
+            diffs.push(ConfigDiff::ConfigureLocale {
+                before: self.configure_locale,
+                after: other.configure_locale,
+            });
+        }
+        if self.coerce_c_locale != other.coerce_c_locale {
+            diffs.push(ConfigDiff::CoerceCLocale {
+                before: self.coerce_c_locale,
+                after: other.coerce_c_locale,
+            });
+        }
+        if self.coerce_c_locale_warn != other.coerce_c_locale_warn {
+            diffs.push(ConfigDiff::CoerceCLocaleWarn {
+                before: self.coerce_c_locale_warn,
+                after: other.coerce_c_locale_warn,
+            });
+        }
+        if self.development_mode != other.development_mode {
+            diffs.push(ConfigDiff::DevelopmentMode {
+                before: self.development_mode,
+                after: other.development_mode,
+            });
+        }
+        if self.isolated != other.isolated {
+            diffs.push(ConfigDiff::Isolated {
+                before: self.isolated,
+                after: other.isolated,
+            });
+        }
+        if self.legacy_windows_fs_encoding != other.legacy_windows_fs_encoding {
+            diffs.push(ConfigDiff::LegacyWindowsFsEncoding {
+                before: self.legacy_windows_fs_encoding,
+                after: other.legacy_windows_fs_encoding,
+            });
+        }
+        if self.parse_argv != other.parse_argv {
+            diffs.push(ConfigDiff::ParseArgv {
+                before: self.parse_argv,
+                after: other.parse_argv,
+            });
+        }
+        if self.use_environment != other.use_environment {
+            diffs.push(ConfigDiff::UseEnvironment {
+                before: self.use_environment,
+                after: other.use_environment,
+            });
+        }
+        if self.utf8_mode != other.utf8_mode {
+            diffs.push(ConfigDiff::Utf8Mode {
+                before: self.utf8_mode,
+                after: other.utf8_mode,
+            });
+        }
+        if self.argv != other.argv {
+            diffs.push(ConfigDiff::Argv {
+                before: self.argv.clone(),
+                after: other.argv.clone(),
+            });
+        }
+        if self.base_exec_prefix != other.base_exec_prefix {
+            diffs.push(ConfigDiff::BaseExecPrefix {
+                before: self.base_exec_prefix.clone(),
+                after: other.base_exec_prefix.clone(),
+            });
+        }
+        if self.base_executable != other.base_executable {
+            diffs.push(ConfigDiff::BaseExecutable {
+                before: self.base_executable.clone(),
+                after: other.base_executable.clone(),
+            });
+        }
+        if self.base_prefix != other.base_prefix {
+            diffs.push(ConfigDiff::BasePrefix {
+                before: self.base_prefix.clone(),
+                after: other.base_prefix.clone(),
+            });
+        }
+        if self.buffered_stdio != other.buffered_stdio {
+            diffs.push(ConfigDiff::BufferedStdio {
+                before: self.buffered_stdio,
+                after: other.buffered_stdio,
+            });
+        }
+        if self.bytes_warning != other.bytes_warning {
+            diffs.push(ConfigDiff::BytesWarning {
+                before: self.bytes_warning,
+                after: other.bytes_warning,
+            });
+        }
+        if self.check_hash_pycs_mode != other.check_hash_pycs_mode {
+            diffs.push(ConfigDiff::CheckHashPycsMode {
+                before: self.check_hash_pycs_mode,
+                after: other.check_hash_pycs_mode,
+            });
+        }
+        if self.configure_c_stdio != other.configure_c_stdio {
+            diffs.push(ConfigDiff::ConfigureCStdio {
+                before: self.configure_c_stdio,
+                after: other.configure_c_stdio,
+            });
+        }
+        if self.dump_refs != other.dump_refs {
+            diffs.push(ConfigDiff::DumpRefs {
+                before: self.dump_refs,
+                after: other.dump_refs,
+            });
+        }
+        if self.exec_prefix != other.exec_prefix {
+            diffs.push(ConfigDiff::ExecPrefix {
+                before: self.exec_prefix.clone(),
+                after: other.exec_prefix.clone(),
+            });
+        }
+        if self.executable != other.executable {
+            diffs.push(ConfigDiff::Executable {
+                before: self.executable.clone(),
+                after: other.executable.clone(),
+            });
+        }
+        if self.fault_handler != other.fault_handler {
+            diffs.push(ConfigDiff::FaultHandler {
+                before: self.fault_handler,
+                after: other.fault_handler,
+            });
+        }
+        if self.filesystem_encoding != other.filesystem_encoding {
+            diffs.push(ConfigDiff::FilesystemEncoding {
+                before: self.filesystem_encoding.clone(),
+                after: other.filesystem_encoding.clone(),
+            });
+        }
+        if self.filesystem_errors != other.filesystem_errors {
+            diffs.push(ConfigDiff::FilesystemErrors {
+                before: self.filesystem_errors.clone(),
+                after: other.filesystem_errors.clone(),
+            });
+        }
+        if self.hash_seed != other.hash_seed {
+            diffs.push(ConfigDiff::HashSeed {
+                before: self.hash_seed,
+                after: other.hash_seed,
+            });
+        }
+        if self.home != other.home {
+            diffs.push(ConfigDiff::Home {
+                before: self.home.clone(),
+                after: other.home.clone(),
+            });
+        }
+        if self.import_time != other.import_time {
+            diffs.push(ConfigDiff::ImportTime {
+                before: self.import_time,
+                after: other.import_time,
+            });
+        }
+        if self.inspect != other.inspect {
+            diffs.push(ConfigDiff::Inspect {
+                before: self.inspect,
+                after: other.inspect,
+            });
+        }
+        if self.install_signal_handlers != other.install_signal_handlers {
+            diffs.push(ConfigDiff::InstallSignalHandlers {
+                before: self.install_signal_handlers,
+                after: other.install_signal_handlers,
+            });
+        }
+        if self.interactive != other.interactive {
+            diffs.push(ConfigDiff::Interactive {
+                before: self.interactive,
+                after: other.interactive,
+            });
+        }
+        if self.legacy_windows_stdio != other.legacy_windows_stdio {
+            diffs.push(ConfigDiff::LegacyWindowsStdio {
+                before: self.legacy_windows_stdio,
+                after: other.legacy_windows_stdio,
+            });
+        }
+        if self.malloc_stats != other.malloc_stats {
+            diffs.push(ConfigDiff::MallocStats {
+                before: self.malloc_stats,
+                after: other.malloc_stats,
+            });
+        }
+        if self.module_search_paths != other.module_search_paths {
+            diffs.push(ConfigDiff::ModuleSearchPaths {
+                before: self.module_search_paths.clone(),
+                after: other.module_search_paths.clone(),
+            });
+        }
+        if self.optimization_level != other.optimization_level {
+            diffs.push(ConfigDiff::OptimizationLevel {
+                before: self.optimization_level,
+                after: other.optimization_level,
+            });
+        }
+        if self.parser_debug != other.parser_debug {
+            diffs.push(ConfigDiff::ParserDebug {
+                before: self.parser_debug,
+                after: other.parser_debug,
+            });
+        }
+        if self.pathconfig_warnings != other.pathconfig_warnings {
+            diffs.push(ConfigDiff::PathconfigWarnings {
+                before: self.pathconfig_warnings,
+                after: other.pathconfig_warnings,
+            });
+        }
+        if self.prefix != other.prefix {
+            diffs.push(ConfigDiff::Prefix {
+                before: self.prefix.clone(),
+                after: other.prefix.clone(),
+            });
+        }
+        if self.program_name != other.program_name {
+            diffs.push(ConfigDiff::ProgramName {
+                before: self.program_name.clone(),
+                after: other.program_name.clone(),
+            });
+        }
+        if self.pycache_prefix != other.pycache_prefix {
+            diffs.push(ConfigDiff::PycachePrefix {
+                before: self.pycache_prefix.clone(),
+                after: other.pycache_prefix.clone(),
+            });
+        }
+        if self.python_path_env != other.python_path_env {
+            diffs.push(ConfigDiff::PythonPathEnv {
+                before: self.python_path_env.clone(),
+                after: other.python_path_env.clone(),
+            });
+        }
+        if self.quiet != other.quiet {
+            diffs.push(ConfigDiff::Quiet {
+                before: self.quiet,
+                after: other.quiet,
+            });
+        }
+        if self.run_command != other.run_command {
+            diffs.push(ConfigDiff::RunCommand {
+                before: self.run_command.clone(),
+                after: other.run_command.clone(),
+            });
+        }
+        if self.run_filename != other.run_filename {
+            diffs.push(ConfigDiff::RunFilename {
+                before: self.run_filename.clone(),
+                after: other.run_filename.clone(),
+            });
+        }
+        if self.run_module != other.run_module {
+            diffs.push(ConfigDiff::RunModule {
+                before: self.run_module.clone(),
+                after: other.run_module.clone(),
+            });
+        }
+        if self.show_ref_count != other.show_ref_count {
+            diffs.push(ConfigDiff::ShowRefCount {
+                before: self.show_ref_count,
+                after: other.show_ref_count,
+            });
+        }
+        if self.site_import != other.site_import {
+            diffs.push(ConfigDiff::SiteImport {
+                before: self.site_import,
+                after: other.site_import,
+            });
+        }
+        if self.skip_first_source_line != other.skip_first_source_line {
+            diffs.push(ConfigDiff::SkipFirstSourceLine {
+                before: self.skip_first_source_line,
+                after: other.skip_first_source_line,
+            });
+        }
+        if self.stdio_encoding != other.stdio_encoding {
+            diffs.push(ConfigDiff::StdioEncoding {
+                before: self.stdio_encoding.clone(),
+                after: other.stdio_encoding.clone(),
+            });
+        }
+        if self.stdio_errors != other.stdio_errors {
+            diffs.push(ConfigDiff::StdioErrors {
+                before: self.stdio_errors.clone(),
+                after: other.stdio_errors.clone(),
+            });
+        }
+        if self.tracemalloc != other.tracemalloc {
+            diffs.push(ConfigDiff::Tracemalloc {
+                before: self.tracemalloc,
+                after: other.tracemalloc,
+            });
+        }
+        if self.user_site_directory != other.user_site_directory {
+            diffs.push(ConfigDiff::UserSiteDirectory {
+                before: self.user_site_directory,
+                after: other.user_site_directory,
+            });
+        }
+        if self.verbose != other.verbose {
+            diffs.push(ConfigDiff::Verbose {
+                before: self.verbose,
+                after: other.verbose,
+            });
+        }
+        if self.warn_options != other.warn_options {
+            diffs.push(ConfigDiff::WarnOptions {
+                before: self.warn_options.clone(),
+                after: other.warn_options.clone(),
+            });
+        }
+        if self.write_bytecode != other.write_bytecode {
+            diffs.push(ConfigDiff::WriteBytecode {
+                before: self.write_bytecode,
+                after: other.write_bytecode,
+            });
+        }
+        if self.x_options != other.x_options {
+            diffs.push(ConfigDiff::XOptions {
+                before: self.x_options.clone(),
+                after: other.x_options.clone(),
+            });
+        }
+
+        diffs
+    }
+
+    /// Obtain a copy of this instance with overrides from environment variables applied.
+    ///
+    /// See [Self::apply_overrides()] for how environment variables are
+    /// interpreted.
+    pub fn apply_env_overrides(&self) -> Result<Self, String> {
+        self.apply_overrides(std::env::vars_os())
+    }
+
+    /// Obtain a copy of this instance with overrides from `(name, value)` pairs applied.
+    ///
+    /// Variables whose name begins with `PYOXIDIZER_CONFIG_` are considered
+    /// first. The remainder of the name is lowercased and interpreted as a
+    /// field name. e.g. `PYOXIDIZER_CONFIG_OPTIMIZATION_LEVEL` sets
+    /// [Self::optimization_level]. Other `PYOXIDIZER_` variables, such as `PYOXIDIZER_CACHE_DIR`, are used by
+    /// PyOxidizer itself and are ignored.
+    ///
+    /// Then, if the resulting configuration explicitly enables
+    /// `use_environment` and isn't isolated, the CPython variables listed in
+    /// [PYTHON_ENV_VARS] are applied. These are variables CPython itself
+    /// would honor in that configuration, including those consulted for
+    /// pre-initialization such as `PYTHONCOERCECLOCALE`, `PYTHONMALLOC`, and
+    /// `PYTHONUTF8`. This allows the output of [Self::to_env_vars()] to
+    /// round-trip.
+    ///
+    /// If a field is set by both a `PYOXIDIZER_CONFIG_` variable and a
+    /// CPython variable, the `PYOXIDIZER_CONFIG_` variable wins, regardless
+    /// of order. e.g. `PYOXIDIZER_CONFIG_DEVELOPMENT_MODE=0` takes precedence
+    /// over `PYTHONDEVMODE=1`.
+    ///
+    /// Values use the same string representation as serialization. Boolean
+    /// fields accept `1`, `true`, `yes`, `on`, `0`, `false`, `no`, and `off`.
+    /// `module_search_paths` is split using the platform's `PATH` separator.
+    /// Other list fields are split on whitespace. Variables with empty values
+    /// are ignored.
+    ///
+    /// Variables with the prefix that don't correspond to a field are logged
+    /// as warnings and ignored. Values that can't be parsed result in an
+    /// error.
+    pub fn apply_overrides<K, V>(
+        &self,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self, String>
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        let mut config = self.clone();
+        let mut python_vars = vec![];
+        let mut overridden_fields = vec![];
+
+        for (key, value) in vars {
+            let key = match key.as_ref().to_str() {
+                Some(key) => key,
+                None => continue,
+            };
+
+            let field = match key.strip_prefix(ENV_OVERRIDE_PREFIX) {
+                Some(field) => field.to_lowercase(),
+                None => {
+                    if let Some(entry) = PYTHON_ENV_VARS.iter().find(|(name, ..)| *name == key) {
+                        python_vars.push((entry, value));
+                    }
+                    continue;
+                }
+            };
+            let value = value
+                .as_ref()
+                .to_str()
+                .ok_or_else(|| format!("{} value is not valid UTF-8", key))?;
+
+            if value.is_empty() {
+                continue;
+            }
+
+            if config
+                .set_known_field_from_str(&field, value)
+                .map_err(|e| format!("{}: {}", key, e))?
+            {
+                overridden_fields.push(field);
+            } else {
+                warn!("ignoring unknown environment variable {}", key);
+            }
+        }
+
+        if !config.honors_python_env_vars() {
+            return Ok(config);
+        }
+
+        for ((key, field, kind), value) in python_vars {
+            let value = value
+                .as_ref()
+                .to_str()
+                .ok_or_else(|| format!("{} value is not valid UTF-8", key))?;
+
+            if value.is_empty() || overridden_fields.iter().any(|f| f == field) {
+                continue;
+            }
+
+            match kind {
+                PythonEnvVarKind::Value => {
+                    // `random` is CPython's way of requesting the default behavior.
+                    if *field == "hash_seed" && value == "random" {
+                        continue;
+                    }
+                    config.set_known_field_from_str(field, value)
+                }
+                PythonEnvVarKind::Enable => config.set_known_field_from_str(field, "true"),
+                PythonEnvVarKind::Disable => config.set_known_field_from_str(field, "false"),
+                PythonEnvVarKind::CommaList => {
+                    config.warn_options = Some(value.split(',').map(|s| s.to_string()).collect());
+                    Ok(true)
+                }
+                PythonEnvVarKind::Keyword => match (*field, value) {
+                    ("coerce_c_locale", "0") => Ok(true),
+                    ("coerce_c_locale", "warn") => {
+                        config.set_known_field_from_str("coerce_c_locale_warn", "true")
+                    }
+                    _ => {
+                        let keyword = PYTHON_ENV_KEYWORDS
+                            .iter()
+                            .find(|(f, v, _)| f == field && *v == value)
+                            .map(|(_, _, field_value)| *field_value);
+
+                        match keyword {
+                            Some(field_value) => {
+                                config.set_known_field_from_str(field, field_value)
+                            }
+                            None if *field == "coerce_c_locale" => {
+                                config.set_known_field_from_str(field, "LC_CTYPE")
+                            }
+                            None => Err(format!("{} is not a recognized value", value)),
+                        }
+                    }
+                },
+            }
+            .map_err(|e| format!("{}: {}", key, e))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Construct an instance from a `.env` file.
+    ///
+    /// The file consists of `KEY=VALUE` lines, as used by twelve-factor
+    /// style deployments. The variables are applied on top of
+    /// [Self::default()] via [Self::apply_overrides()], so
+    /// `PYOXIDIZER_CONFIG_*` variables set fields directly and CPython
+    /// `PYTHON*` variables are honored if the file also enables
+    /// `PYOXIDIZER_CONFIG_USE_ENVIRONMENT`.
+    /// Variables for other applications are ignored.
+    ///
+    /// Blank lines and lines starting with `#` are ignored, as is an
+    /// `export ` prefix. Unquoted values are trimmed and end at a ` #`
+    /// comment. Values in single quotes are taken literally. Values in double
+    /// quotes support the `\\`, `\"`, `\n`, `\r`, and `\t` escapes. Quoted
+    /// values can't span multiple lines. Variable references such as
+    /// `${VAR}` aren't expanded.
+    #[cfg(feature = "dotenv")]
+    pub fn from_dotenv_file(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("error reading {}: {}", path.display(), e))?;
+
+        let vars = parse_dotenv(&data).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        Self::default().apply_overrides(vars)
+    }
+
+    /// Obtain environment variables that reproduce this configuration in another process.
+    ///
+    /// If this configuration explicitly enables `use_environment` (see
+    /// [Self::apply_overrides()]), fields that have a CPython environment
+    /// variable equivalent (see [PYTHON_ENV_VARS]) are emitted using it, so
+    /// a child interpreter honors them without any special handling. Some
+    /// CPython variables can only enable (or only disable) a setting. Values
+    /// they can't express, and all other set fields, are emitted as
+    /// `PYOXIDIZER_CONFIG_<FIELD>` variables. [Self::profile] is always
+    /// emitted.
+    ///
+    /// Variables are sorted by name. [Self::apply_overrides()] recovers the
+    /// configuration, with the caveats of [Self::to_map()]. In addition,
+    /// empty string values are ignored and `warn_options` containing commas
+    /// won't round-trip when represented as `PYTHONWARNINGS`.
+    pub fn to_env_vars(&self) -> Vec<(String, String)> {
+        let use_python_vars = self.honors_python_env_vars();
+
+        let mut vars = self
+            .to_map()
+            .into_iter()
+            .map(|(field, value)| {
+                let python_var = PYTHON_ENV_VARS
+                    .iter()
+                    .find(|(_, f, _)| *f == field)
+                    .filter(|_| use_python_vars);
+
+                match python_var {
+                    Some((name, _, PythonEnvVarKind::Value)) => {
+                        // CPython expects integers for boolean values.
+                        let value = match value.as_str() {
+                            "true" => "1".to_string(),
+                            "false" => "0".to_string(),
+                            _ => value,
+                        };
+                        (name.to_string(), value)
+                    }
+                    Some((name, _, PythonEnvVarKind::Enable)) if value == "true" => {
+                        (name.to_string(), "1".to_string())
+                    }
+                    Some((name, _, PythonEnvVarKind::Disable)) if value == "false" => {
+                        (name.to_string(), "1".to_string())
+                    }
+                    Some((name, _, PythonEnvVarKind::CommaList)) => (
+                        name.to_string(),
+                        self.warn_options.clone().unwrap_or_default().join(","),
+                    ),
+                    Some((name, _, PythonEnvVarKind::Keyword)) => {
+                        match PYTHON_ENV_KEYWORDS
+                            .iter()
+                            .find(|(f, _, v)| *f == field && *v == value)
+                        {
+                            Some((_, keyword, _)) => (name.to_string(), keyword.to_string()),
+                            None => (
+                                format!("{}{}", ENV_OVERRIDE_PREFIX, field.to_uppercase()),
+                                value,
+                            ),
+                        }
+                    }
+                    _ => (
+                        format!("{}{}", ENV_OVERRIDE_PREFIX, field.to_uppercase()),
+                        value,
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        vars.sort();
+
+        vars
+    }
+
+    /// Whether CPython environment variables are honored by [Self::apply_overrides()].
+    ///
+    /// This requires `use_environment` to be explicitly enabled, so `PYTHON*`
+    /// variables never leak into a configuration that didn't opt in. As in
+    /// CPython, `isolated` takes precedence.
+    fn honors_python_env_vars(&self) -> bool {
+        self.use_environment == Some(true)
+            && !self
+                .isolated
+                .unwrap_or(self.profile == PythonInterpreterProfile::Isolated)
+    }
+
+    /// Set a field by name from its string representation.
+    ///
+    /// `field` is the name of a field of this struct, e.g.
+    /// `optimization_level`. `value` uses the same representation as
+    /// [Self::to_map()] and [Self::apply_overrides()].
+    ///
+    /// Returns an error if `field` isn't the name of a field or if `value`
+    /// can't be parsed.
+    pub fn set_field_from_str(&mut self, field: &str, value: &str) -> Result<(), String> {
+        if self.set_known_field_from_str(field, value)? {
+            Ok(())
+        } else {
+            Err(format!("{} is not a known field", field))
+        }
+    }
+
+    /// Set a field from its string representation.
+    ///
+    /// Returns `Ok(false)` if `name` isn't the name of a field.
+    fn set_known_field_from_str(&mut self, name: &str, value: &str) -> Result<bool, String> {
+        match name {
+            "profile" => self.profile = PythonInterpreterProfile::try_from(value)?,
+            "allocator" => self.allocator = Some(Allocator::try_from(value)?),
+            "configure_locale" => self.configure_locale = Some(parse_bool(value)?),
+            "coerce_c_locale" => self.coerce_c_locale = Some(CoerceCLocale::try_from(value)?),
+            "coerce_c_locale_warn" => self.coerce_c_locale_warn = Some(parse_bool(value)?),
+            "development_mode" => self.development_mode = Some(parse_bool(value)?),
+            "isolated" => self.isolated = Some(parse_bool(value)?),
+            "legacy_windows_fs_encoding" => {
+                self.legacy_windows_fs_encoding = Some(parse_bool(value)?)
+            }
+            "parse_argv" => self.parse_argv = Some(parse_bool(value)?),
+            "use_environment" => self.use_environment = Some(parse_bool(value)?),
+            "utf8_mode" => self.utf8_mode = Some(parse_bool(value)?),
+            "argv" => self.argv = Some(value.split_whitespace().map(OsString::from).collect()),
+            "base_exec_prefix" => self.base_exec_prefix = Some(PathBuf::from(value)),
+            "base_executable" => self.base_executable = Some(PathBuf::from(value)),
+            "base_prefix" => self.base_prefix = Some(PathBuf::from(value)),
+            "buffered_stdio" => self.buffered_stdio = Some(parse_bool(value)?),
+            "bytes_warning" => self.bytes_warning = Some(BytesWarning::try_from(value)?),
+            "check_hash_pycs_mode" => {
+                self.check_hash_pycs_mode = Some(CheckHashPycsMode::try_from(value)?)
+            }
+            "configure_c_stdio" => self.configure_c_stdio = Some(parse_bool(value)?),
+            "dump_refs" => self.dump_refs = Some(parse_bool(value)?),
+            "exec_prefix" => self.exec_prefix = Some(PathBuf::from(value)),
+            "executable" => self.executable = Some(PathBuf::from(value)),
+            "fault_handler" => self.fault_handler = Some(parse_bool(value)?),
+            "filesystem_encoding" => self.filesystem_encoding = Some(value.to_string()),
+            "filesystem_errors" => self.filesystem_errors = Some(value.to_string()),
+            "hash_seed" => {
+                self.hash_seed = Some(
+                    value
+                        .parse::<c_ulong>()
+                        .map_err(|_| format!("{} is not a valid hash seed", value))?,
+                )
+            }
+            "home" => self.home = Some(PathBuf::from(value)),
+            "import_time" => self.import_time = Some(parse_bool(value)?),
+            "inspect" => self.inspect = Some(parse_bool(value)?),
+            "install_signal_handlers" => self.install_signal_handlers = Some(parse_bool(value)?),
+            "interactive" => self.interactive = Some(parse_bool(value)?),
+            "legacy_windows_stdio" => self.legacy_windows_stdio = Some(parse_bool(value)?),
+            "malloc_stats" => self.malloc_stats = Some(parse_bool(value)?),
+            "module_search_paths" => {
+                self.module_search_paths = Some(std::env::split_paths(value).collect())
+            }
+            "optimization_level" => {
+                self.optimization_level = Some(BytecodeOptimizationLevel::try_from(
+                    value
+                        .parse::<i32>()
+                        .map_err(|_| format!("{} is not a valid optimization level", value))?,
+                )?)
+            }
+            "parser_debug" => self.parser_debug = Some(parse_bool(value)?),
+            "pathconfig_warnings" => self.pathconfig_warnings = Some(parse_bool(value)?),
+            "prefix" => self.prefix = Some(PathBuf::from(value)),
+            "program_name" => self.program_name = Some(PathBuf::from(value)),
+            "pycache_prefix" => self.pycache_prefix = Some(PathBuf::from(value)),
+            "python_path_env" => self.python_path_env = Some(value.to_string()),
+            "quiet" => self.quiet = Some(parse_bool(value)?),
+            "run_command" => self.run_command = Some(value.to_string()),
+            "run_filename" => self.run_filename = Some(PathBuf::from(value)),
+            "run_module" => self.run_module = Some(value.to_string()),
+            "show_ref_count" => self.show_ref_count = Some(parse_bool(value)?),
+            "site_import" => self.site_import = Some(parse_bool(value)?),
+            "skip_first_source_line" => self.skip_first_source_line = Some(parse_bool(value)?),
+            "stdio_encoding" => self.stdio_encoding = Some(value.to_string()),
+            "stdio_errors" => self.stdio_errors = Some(value.to_string()),
+            "tracemalloc" => self.tracemalloc = Some(parse_bool(value)?),
+            "user_site_directory" => self.user_site_directory = Some(parse_bool(value)?),
+            "verbose" => self.verbose = Some(parse_bool(value)?),
+            "warn_options" => {
+                self.warn_options = Some(value.split_whitespace().map(|s| s.to_string()).collect())
+            }
+            "write_bytecode" => self.write_bytecode = Some(parse_bool(value)?),
+            "x_options" => {
+                self.x_options = Some(value.split_whitespace().map(|s| s.to_string()).collect())
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Obtain a JSON Schema describing the serialized form of this type.
+    ///
+    /// Field descriptions are derived from the documentation of each field.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema() -> schemars::schema::RootSchema {
+        schemars::schema_for!(PythonInterpreterConfig)
+    }
+
+    /// Obtain a copy of this instance configured to run a Python script.
+    ///
+    /// [Self::run_filename] is set and [Self::run_command] and
+    /// [Self::run_module] are cleared.
+    pub fn with_run_script(&self, path: impl AsRef<Path>) -> Self {
+        Self {
+            run_command: None,
+            run_filename: Some(path.as_ref().to_path_buf()),
+            run_module: None,
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance configured to run a Python module.
+    ///
+    /// [Self::run_module] is set and [Self::run_command] and
+    /// [Self::run_filename] are cleared.
+    pub fn with_run_module(&self, name: impl ToString) -> Self {
+        Self {
+            run_command: None,
+            run_filename: None,
+            run_module: Some(name.to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance configured to run Python code.
+    ///
+    /// [Self::run_command] is set and [Self::run_filename] and
+    /// [Self::run_module] are cleared.
+    pub fn with_run_command(&self, code: impl ToString) -> Self {
+        Self {
+            run_command: Some(code.to_string()),
+            run_filename: None,
+            run_module: None,
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance with [Self::argv] set.
+    pub fn with_argv<T: Into<OsString>>(&self, args: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            argv: Some(args.into_iter().map(|a| a.into()).collect()),
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance with [Self::optimization_level] set.
+    pub fn with_optimization(&self, level: BytecodeOptimizationLevel) -> Self {
+        Self {
+            optimization_level: Some(level),
+            ..self.clone()
+        }
+    }
+
+    /// Whether this configuration fully isolates the interpreter from its environment.
+    ///
+    /// An interpreter is *effectively isolated* when it neither consults
+    /// `PYTHON*` environment variables nor adds the user site-packages
+    /// directory to `sys.path`.
+    ///
+    /// This resolves unset fields to the defaults of [Self::profile] and
+    /// applies CPython's semantics for the `isolated`, `use_environment`,
+    /// `site_import`, and `user_site_directory` fields:
+    ///
+    /// * `isolated` forces `use_environment` and `user_site_directory` off,
+    ///   regardless of their values.
+    /// * The user site directory is only added by the `site` module, so
+    ///   disabling `site_import` also disables it.
+    pub fn is_effectively_isolated(&self) -> bool {
+        let python_profile = self.profile == PythonInterpreterProfile::Python;
+
+        if self.isolated.unwrap_or(!python_profile) {
+            return true;
+        }
+
+        let use_environment = self.use_environment.unwrap_or(python_profile);
+        let site_import = self.site_import.unwrap_or(true);
+        let user_site_directory = self.user_site_directory.unwrap_or(python_profile);
+
+        !use_environment && (!site_import || !user_site_directory)
+    }
+
+    /// Construct an instance from a map of field names to string values.
+    ///
+    /// Keys are the field names as they appear in serialized form (e.g.
+    /// `optimization_level`). Values use the same string representation as
+    /// [Self::apply_overrides()]. Fields not present in the map are `None`.
+    ///
+    /// Keys that don't correspond to a field don't result in an error, to
+    /// allow maps written for newer versions of this type to be consumed.
+    /// Instead, a warning message for each is returned alongside the
+    /// configuration.
+    pub fn from_map(map: &HashMap<String, String>) -> Result<(Self, Vec<String>), String> {
+        let mut config = Self::default();
+        let mut warnings = vec![];
+
+        let mut keys = map.keys().collect::<Vec<_>>();
+        keys.sort();
+
+        for key in keys {
+            if !config
+                .set_known_field_from_str(key, &map[key])
+                .map_err(|e| format!("{}: {}", key, e))?
+            {
+                warnings.push(format!("unknown configuration key: {}", key));
+            }
+        }
+
+        Ok((config, warnings))
+    }
+
+    /// Convert this instance to a map of field names to string values.
+    ///
+    /// This is the inverse of [Self::from_map()]. Fields that are `None` are
+    /// omitted. [Self::profile] is always present.
+    ///
+    /// Paths and arguments that aren't valid Unicode are converted lossily.
+    /// List values containing whitespace (or the `PATH` separator for
+    /// `module_search_paths`) won't round-trip.
+    pub fn to_map(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+
+        map.insert("profile".to_string(), self.profile.to_string());
+        if let Some(v) = self.allocator {
+            map.insert("allocator".to_string(), v.to_string());
+        }
+        if let Some(v) = self.configure_locale {
+            map.insert("configure_locale".to_string(), v.to_string());
+        }
+        if let Some(v) = self.coerce_c_locale {
+            map.insert("coerce_c_locale".to_string(), v.to_string());
+        }
+        if let Some(v) = self.coerce_c_locale_warn {
+            map.insert("coerce_c_locale_warn".to_string(), v.to_string());
+        }
+        if let Some(v) = self.development_mode {
+            map.insert("development_mode".to_string(), v.to_string());
+        }
+        if let Some(v) = self.isolated {
+            map.insert("isolated".to_string(), v.to_string());
+        }
+        if let Some(v) = self.legacy_windows_fs_encoding {
+            map.insert("legacy_windows_fs_encoding".to_string(), v.to_string());
+        }
+        if let Some(v) = self.parse_argv {
+            map.insert("parse_argv".to_string(), v.to_string());
+        }
+        if let Some(v) = self.use_environment {
+            map.insert("use_environment".to_string(), v.to_string());
+        }
+        if let Some(v) = self.utf8_mode {
+            map.insert("utf8_mode".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.argv {
+            map.insert(
+                "argv".to_string(),
+                v.iter()
+                    .map(|s| s.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        if let Some(v) = &self.base_exec_prefix {
+            map.insert("base_exec_prefix".to_string(), v.display().to_string());
+        }
+        if let Some(v) = &self.base_executable {
+            map.insert("base_executable".to_string(), v.display().to_string());
+        }
+        if let Some(v) = &self.base_prefix {
+            map.insert("base_prefix".to_string(), v.display().to_string());
+        }
+        if let Some(v) = self.buffered_stdio {
+            map.insert("buffered_stdio".to_string(), v.to_string());
+        }
+        if let Some(v) = self.bytes_warning {
+            map.insert("bytes_warning".to_string(), v.to_string());
+        }
+        if let Some(v) = self.check_hash_pycs_mode {
+            map.insert("check_hash_pycs_mode".to_string(), v.to_string());
+        }
+        if let Some(v) = self.configure_c_stdio {
+            map.insert("configure_c_stdio".to_string(), v.to_string());
+        }
+        if let Some(v) = self.dump_refs {
+            map.insert("dump_refs".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.exec_prefix {
+            map.insert("exec_prefix".to_string(), v.display().to_string());
+        }
+        if let Some(v) = &self.executable {
+            map.insert("executable".to_string(), v.display().to_string());
+        }
+        if let Some(v) = self.fault_handler {
+            map.insert("fault_handler".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.filesystem_encoding {
+            map.insert("filesystem_encoding".to_string(), v.clone());
+        }
+        if let Some(v) = &self.filesystem_errors {
+            map.insert("filesystem_errors".to_string(), v.clone());
+        }
+        if let Some(v) = self.hash_seed {
+            map.insert("hash_seed".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.home {
+            map.insert("home".to_string(), v.display().to_string());
+        }
+        if let Some(v) = self.import_time {
+            map.insert("import_time".to_string(), v.to_string());
+        }
+        if let Some(v) = self.inspect {
+            map.insert("inspect".to_string(), v.to_string());
+        }
+        if let Some(v) = self.install_signal_handlers {
+            map.insert("install_signal_handlers".to_string(), v.to_string());
+        }
+        if let Some(v) = self.interactive {
+            map.insert("interactive".to_string(), v.to_string());
+        }
+        if let Some(v) = self.legacy_windows_stdio {
+            map.insert("legacy_windows_stdio".to_string(), v.to_string());
+        }
+        if let Some(v) = self.malloc_stats {
+            map.insert("malloc_stats".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.module_search_paths {
+            map.insert(
+                "module_search_paths".to_string(),
+                v.iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(PATH_LIST_SEPARATOR),
+            );
+        }
+        if let Some(v) = self.optimization_level {
+            map.insert("optimization_level".to_string(), i32::from(v).to_string());
+        }
+        if let Some(v) = self.parser_debug {
+            map.insert("parser_debug".to_string(), v.to_string());
+        }
+        if let Some(v) = self.pathconfig_warnings {
+            map.insert("pathconfig_warnings".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.prefix {
+            map.insert("prefix".to_string(), v.display().to_string());
+        }
+        if let Some(v) = &self.program_name {
+            map.insert("program_name".to_string(), v.display().to_string());
+        }
+        if let Some(v) = &self.pycache_prefix {
+            map.insert("pycache_prefix".to_string(), v.display().to_string());
+        }
+        if let Some(v) = &self.python_path_env {
+            map.insert("python_path_env".to_string(), v.clone());
+        }
+        if let Some(v) = self.quiet {
+            map.insert("quiet".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.run_command {
+            map.insert("run_command".to_string(), v.clone());
+        }
+        if let Some(v) = &self.run_filename {
+            map.insert("run_filename".to_string(), v.display().to_string());
+        }
+        if let Some(v) = &self.run_module {
+            map.insert("run_module".to_string(), v.clone());
+        }
+        if let Some(v) = self.show_ref_count {
+            map.insert("show_ref_count".to_string(), v.to_string());
+        }
+        if let Some(v) = self.site_import {
+            map.insert("site_import".to_string(), v.to_string());
+        }
+        if let Some(v) = self.skip_first_source_line {
+            map.insert("skip_first_source_line".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.stdio_encoding {
+            map.insert("stdio_encoding".to_string(), v.clone());
+        }
+        if let Some(v) = &self.stdio_errors {
+            map.insert("stdio_errors".to_string(), v.clone());
+        }
+        if let Some(v) = self.tracemalloc {
+            map.insert("tracemalloc".to_string(), v.to_string());
+        }
+        if let Some(v) = self.user_site_directory {
+            map.insert("user_site_directory".to_string(), v.to_string());
+        }
+        if let Some(v) = self.verbose {
+            map.insert("verbose".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.warn_options {
+            map.insert("warn_options".to_string(), v.join(" "));
+        }
+        if let Some(v) = self.write_bytecode {
+            map.insert("write_bytecode".to_string(), v.to_string());
+        }
+        if let Some(v) = &self.x_options {
+            map.insert("x_options".to_string(), v.join(" "));
+        }
+
+        map
+    }
+
+    /// Convert this instance to a deterministic, line-oriented representation.
+    ///
+    /// Each set field is written on its own line as `name = value`, with
+    /// lines sorted by field name. Fields that are `None` are omitted.
+    /// [Self::profile] is always present. This makes the output well suited
+    /// for storing in version control, as a change to a field shows up as a
+    /// change to a single line.
+    ///
+    /// Values use the same string representation as [Self::to_map()]. Values
+    /// that are empty, begin with `"`, have leading or trailing whitespace, or
+    /// contain control characters are double-quoted, with `"`, `\`, and
+    /// control characters escaped. List fields are written as
+    /// `["a", "b"]`, with each entry quoted.
+    ///
+    /// [Self::from_compact_repr()] parses this representation. Paths and
+    /// arguments that aren't valid Unicode are converted lossily.
+    pub fn to_compact_repr(&self) -> String {
+        let lists = [
+            (
+                "argv",
+                self.argv.as_ref().map(|args| {
+                    args.iter()
+                        .map(|arg| arg.to_string_lossy().into_owned())
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            (
+                "module_search_paths",
+                self.module_search_paths.as_ref().map(|paths| {
+                    paths
+                        .iter()
+                        .map(|path| path.to_string_lossy().into_owned())
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            ("warn_options", self.warn_options.clone()),
+            ("x_options", self.x_options.clone()),
+        ];
+
+        let mut lines = self
+            .to_map()
+            .into_iter()
+            .filter(|(name, _)| !lists.iter().any(|(list, _)| list == name))
+            .map(|(name, value)| {
+                let needs_quoting = value.is_empty()
+                    || value.starts_with('"')
+                    || value.trim() != value
+                    || value.chars().any(|c| c.is_control());
+
+                if needs_quoting {
+                    (name, quote_compact_string(&value))
+                } else {
+                    (name, value)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (name, values) in lists {
+            if let Some(values) = values {
+                let values = values
+                    .iter()
+                    .map(|value| quote_compact_string(value))
+                    .collect::<Vec<_>>();
+
+                lines.push((name.to_string(), format!("[{}]", values.join(", "))));
+            }
+        }
+
+        lines.sort();
+
+        lines
+            .into_iter()
+            .map(|(name, value)| format!("{} = {}\n", name, value))
+            .collect()
+    }
+
+    /// Construct an instance from the representation produced by [Self::to_compact_repr()].
+    ///
+    /// Blank lines are ignored. Unlike [Self::from_map()], unknown field names
+    /// are an error.
+    pub fn from_compact_repr(s: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (name, value) = line
+                .split_once(" = ")
+                .ok_or_else(|| format!("line {}: expected `name = value`", i + 1))?;
+
+            let mut set_field = || -> Result<(), String> {
+                match name {
+                    "argv" => {
+                        config.argv = Some(
+                            parse_compact_list(value)?
+                                .into_iter()
+                                .map(OsString::from)
+                                .collect(),
+                        )
+                    }
+                    "module_search_paths" => {
+                        config.module_search_paths = Some(
+                            parse_compact_list(value)?
+                                .into_iter()
+                                .map(PathBuf::from)
+                                .collect(),
+                        )
+                    }
+                    "warn_options" => config.warn_options = Some(parse_compact_list(value)?),
+                    "x_options" => config.x_options = Some(parse_compact_list(value)?),
+                    _ => {
+                        let value = if value.starts_with('"') {
+                            match parse_compact_string(value)? {
+                                (value, "") => value,
+                                _ => return Err("unexpected content after quoted value".into()),
+                            }
+                        } else {
+                            value.to_string()
+                        };
+
+                        config.set_field_from_str(name, &value)?
+                    }
+                }
+
+                Ok(())
+            };
+
+            set_field().map_err(|e| format!("line {}: {}: {}", i + 1, name, e))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Estimate the startup overhead of this configuration.
+    ///
+    /// Settings are examined as set: unset fields are assumed to retain
+    /// CPython's defaults, which add no overhead. See [StartupProfile].
+    pub fn estimated_startup_overhead(&self) -> StartupProfile {
+        let debug_overhead = [
+            self.development_mode,
+            self.allocator.map(|a| a.is_debug_variant()),
+            self.verbose,
+            self.parser_debug,
+            self.show_ref_count,
+            self.dump_refs,
+            self.malloc_stats,
+        ]
+        .contains(&Some(true));
+        let trace_overhead = [self.import_time, self.tracemalloc].contains(&Some(true));
+
+        // Placeholder tiers. See StartupProfile::expected_slowdown_factor.
+        let expected_slowdown_factor = match (debug_overhead, trace_overhead) {
+            (false, false) => 1.0,
+            (true, false) => 1.5,
+            (false, true) => 2.0,
+            (true, true) => 3.0,
+        };
+
+        StartupProfile {
+            baseline: !debug_overhead && !trace_overhead,
+            debug_overhead,
+            trace_overhead,
+            expected_slowdown_factor,
+        }
+    }
+
+    /// Obtain warnings about security-relevant settings in this configuration.
+    ///
+    /// Only explicitly set fields are considered. See [SecurityWarning] for
+    /// the conditions that are detected.
+    pub fn security_audit(&self) -> Vec<SecurityWarning> {
+        let mut warnings = vec![];
+
+        if self.profile == PythonInterpreterProfile::Isolated && self.use_environment == Some(true)
+        {
+            warnings.push(SecurityWarning::EnvironmentInIsolatedProfile);
+        }
+        if self.install_signal_handlers == Some(false) {
+            warnings.push(SecurityWarning::SignalHandlersDisabled);
+        }
+        warnings.extend(self.warn_if_hash_not_randomized());
+
+        warnings
+    }
+
+    /// Whether hash randomization is enabled.
+    ///
+    /// This mirrors `sys.flags.hash_randomization`: randomization is only
+    /// disabled by a [Self::hash_seed] of `0`. When unset, CPython picks a
+    /// random seed at startup. Any other value is used as the seed.
+    ///
+    /// Note that a fixed non-zero seed still makes hashes predictable to
+    /// anyone who knows the seed, e.g. by inspecting the binary.
+    pub fn hash_seed_is_randomized(&self) -> bool {
+        self.hash_seed != Some(0)
+    }
+
+    /// Obtain a warning if hash randomization is disabled.
+    ///
+    /// Returns [SecurityWarning::HashRandomizationDisabled] if
+    /// [Self::hash_seed_is_randomized()] is false.
+    pub fn warn_if_hash_not_randomized(&self) -> Option<SecurityWarning> {
+        if self.hash_seed_is_randomized() {
+            None
+        } else {
+            Some(SecurityWarning::HashRandomizationDisabled)
+        }
+    }
+
+    /// Obtain the settings preventing this configuration from behaving reproducibly.
+    ///
+    /// A configuration is reproducible if [Self::hash_seed] is a fixed
+    /// non-zero value, so `str` and `bytes` hashes and set iteration order
+    /// don't vary between runs, [Self::write_bytecode] is `Some(false)`, so
+    /// no `.pyc` files with embedded timestamps are written, and
+    /// [Self::import_time] is `Some(false)`, so no timing output is emitted.
+    /// A seed of `0` doesn't qualify: it disables hash randomization instead
+    /// of fixing a seed.
+    ///
+    /// Unset fields are reported because `PYTHON*` environment variables
+    /// can change them unless the environment is ignored.
+    pub fn reproducibility_report(&self) -> ReproducibilityReport {
+        let mut blockers = vec![];
+
+        match self.hash_seed {
+            None => blockers.push(("hash_seed", "hashes are randomized per process")),
+            Some(0) => blockers.push(("hash_seed", "0 disables hash randomization")),
+            Some(_) => {}
+        }
+        if self.write_bytecode != Some(false) {
+            blockers.push(("write_bytecode", "bytecode files embed source timestamps"));
+        }
+        if self.import_time != Some(false) {
+            blockers.push(("import_time", "import timings vary between runs"));
+        }
+
+        ReproducibilityReport { blockers }
+    }
+
+    /// Whether [Self::reproducibility_report()] finds nothing preventing reproducibility.
+    pub fn is_reproducible_build(&self) -> bool {
+        self.reproducibility_report().is_reproducible()
+    }
+
+    /// Change the settings reported by [Self::reproducibility_report()].
+    ///
+    /// After this, [Self::is_reproducible_build()] returns `true`.
+    ///
+    /// A `seed` of `0` doesn't fix a hash seed and results in an error. The
+    /// configuration isn't modified in that case.
+    pub fn make_reproducible(&mut self, seed: c_ulong) -> Result<(), String> {
+        if seed == 0 {
+            return Err("reproducible builds require a non-zero hash seed".to_string());
+        }
+
+        self.hash_seed = Some(seed);
+        self.write_bytecode = Some(false);
+        self.import_time = Some(false);
+
+        Ok(())
+    }
+
+    /// Obtain a copy of this instance with diagnostics-only settings cleared.
+    ///
+    /// `dump_refs`, `import_time`, `malloc_stats`, `parser_debug`,
+    /// `show_ref_count`, and `tracemalloc` are useful during development but
+    /// shouldn't be enabled in production. `config.without_debug_fields() ==
+    /// config` can be used as a release-gate assertion.
+    pub fn without_debug_fields(&self) -> Self {
+        // Fields are cleared rather than set to `Some(false)`: CPython disables
+        // all of these by default, and a configuration that never set them
+        // should pass the release gate unchanged.
+        Self {
+            dump_refs: None,
+            import_time: None,
+            malloc_stats: None,
+            parser_debug: None,
+            show_ref_count: None,
+            tracemalloc: None,
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance with development and debugging aids enabled.
+    ///
+    /// Enables [Self::development_mode], [Self::fault_handler],
+    /// [Self::tracemalloc], [Self::import_time], [Self::verbose], and
+    /// [Self::malloc_stats], and sets [Self::bytes_warning] to
+    /// [BytesWarning::Warn].
+    ///
+    /// This is intended for running an application locally. These settings
+    /// slow down the interpreter and write diagnostics to stderr, so the
+    /// result should never be used for a production build. Note that
+    /// [Self::without_debug_fields()] only reverts some of these settings.
+    pub fn with_development_defaults(&self) -> Self {
+        Self {
+            development_mode: Some(true),
+            fault_handler: Some(true),
+            tracemalloc: Some(true),
+            import_time: Some(true),
+            verbose: Some(true),
+            malloc_stats: Some(true),
+            bytes_warning: Some(BytesWarning::Warn),
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance that doesn't write bytecode files.
+    ///
+    /// Clears [Self::pycache_prefix] and sets [Self::write_bytecode] to
+    /// `Some(false)`.
+    ///
+    /// This is required when the interpreter runs from a read-only
+    /// filesystem, such as a signed `.app` bundle or a read-only container
+    /// image. Otherwise the importer will attempt to write `.pyc` files
+    /// next to the sources (or under the cache prefix) when importing
+    /// modules from the filesystem, which fails or modifies the deployed
+    /// files.
+    pub fn strip_write_paths(&self) -> Self {
+        Self {
+            pycache_prefix: None,
+            write_bytecode: Some(false),
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance that imports stdlib modules from the filesystem.
+    ///
+    /// Sets the `frozen_modules=off` `-X` option, replacing any existing
+    /// `frozen_modules` value. Python 3.11+ freezes many stdlib modules used
+    /// during startup (e.g. `os`, `site`, `codecs`) into the interpreter, so
+    /// edits to their sources have no effect until the interpreter is
+    /// rebuilt. With frozen modules disabled, they are imported like any
+    /// other module, which is useful when developing stdlib patches.
+    ///
+    /// The option requires Python 3.11+ and is reported by
+    /// [Self::validate_for_version()] for older versions. The
+    /// `importlib._bootstrap` modules are always frozen regardless.
+    ///
+    /// Importing these modules from source or bytecode files noticeably
+    /// slows down interpreter startup. Don't use this in production.
+    pub fn with_frozen_modules_disabled(&self) -> Self {
+        let mut config = self.clone();
+        config.xoption_set("frozen_modules", Some("off"));
+        config
+    }
+
+    /// Whether [Self::x_options] disables frozen stdlib modules.
+    ///
+    /// See [Self::with_frozen_modules_disabled()].
+    pub fn has_frozen_modules_disabled(&self) -> bool {
+        self.xoption_get("frozen_modules") == Some(Some("off"))
+    }
+
+    /// Obtain a copy of this instance with [Self::module_search_paths] normalized.
+    ///
+    /// Relative paths are resolved against `base`. Duplicate entries are
+    /// removed, retaining the first occurrence. An error is returned if a path
+    /// doesn't exist.
+    ///
+    /// Paths are compared and resolved lexically: `.` and `..` components are
+    /// collapsed without consulting the filesystem. Symlinks aren't resolved.
+    /// Paths with placeholders such as `$ORIGIN` must be expanded before
+    /// calling this.
+    pub fn normalize_module_search_paths(&self, base: &Path) -> Result<Self, String> {
+        let paths = match &self.module_search_paths {
+            Some(paths) => paths,
+            None => return Ok(self.clone()),
+        };
+
+        let mut normalized: Vec<PathBuf> = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let joined = base.join(path);
+            let mut components: Vec<Component> = vec![];
+            for component in joined.components() {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir => match components.last() {
+                        Some(Component::Normal(_)) => {
+                            components.pop();
+                        }
+                        // `..` of the root is the root.
+                        Some(Component::RootDir | Component::Prefix(_)) => {}
+                        _ => components.push(component),
+                    },
+                    _ => components.push(component),
+                }
+            }
+            let path = components.into_iter().collect::<PathBuf>();
+
+            if !path.exists() {
+                return Err(format!(
+                    "module search path does not exist: {}",
+                    path.display()
+                ));
+            }
+
+            if !normalized.contains(&path) {
+                normalized.push(path);
+            }
+        }
+
+        Ok(Self {
+            module_search_paths: Some(normalized),
+            ..self.clone()
+        })
+    }
+
+    /// Whether [Self::module_search_paths] contains the given path.
+    ///
+    /// Paths are compared by component, so `a/./b` matches `a/b`. No other
+    /// normalization is performed.
+    pub fn has_module_search_path(&self, path: &Path) -> bool {
+        matches!(&self.module_search_paths, Some(paths) if paths.iter().any(|p| p == path))
+    }
+
+    /// Obtain a copy of this instance with paths added to the front of [Self::module_search_paths].
+    ///
+    /// [Self::module_search_paths] is initialized if it isn't set. Duplicate
+    /// paths are removed from the result, retaining the first occurrence. So
+    /// a prepended path already present later in the list is moved to the
+    /// front.
+    pub fn with_sys_path_prepended(&self, paths: &[PathBuf]) -> Self {
+        let existing = self.module_search_paths.iter().flatten();
+
+        Self {
+            module_search_paths: Some(dedupe_paths(paths.iter().chain(existing))),
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance with paths added to the end of [Self::module_search_paths].
+    ///
+    /// [Self::module_search_paths] is initialized if it isn't set. Duplicate
+    /// paths are removed from the result, retaining the first occurrence. So
+    /// an appended path already present in the list keeps its position.
+    pub fn with_sys_path_appended(&self, paths: &[PathBuf]) -> Self {
+        let existing = self.module_search_paths.iter().flatten();
+
+        Self {
+            module_search_paths: Some(dedupe_paths(existing.chain(paths.iter()))),
+            ..self.clone()
+        }
+    }
+
+    /// Obtain a copy of this instance with a path removed from [Self::module_search_paths].
+    ///
+    /// All occurrences of the path are removed. Paths are compared the same
+    /// way as [Self::has_module_search_path()].
+    ///
+    /// If [Self::module_search_paths] isn't set, it remains unset. If the last
+    /// entry is removed, it is set to an empty list, as clearing it would
+    /// have Python compute a default search path.
+    pub fn with_sys_path_removed(&self, path: &Path) -> Self {
+        Self {
+            module_search_paths: self
+                .module_search_paths
+                .as_ref()
+                .map(|paths| paths.iter().filter(|p| *p != path).cloned().collect()),
+            ..self.clone()
+        }
+    }
+
+    /// Obtain the range of Python versions this configuration can be used with.
+    ///
+    /// Every configuration requires the `PyConfig` API introduced in Python
+    /// 3.8. Some fields and `-X` options impose a newer minimum version.
+    pub fn python_version_constraints(&self) -> PythonVersionConstraints {
+        PythonVersionConstraints {
+            min_version: self
+                .version_requirements()
+                .into_iter()
+                .map(|(_, version)| version)
+                .fold(PYCONFIG_MIN_VERSION, std::cmp::max),
+        }
+    }
+
+    /// Validate that this configuration can be used with a given Python version.
+    ///
+    /// Returns `Err` with a description of every setting that the given
+    /// version doesn't support.
+    pub fn validate_for_version(&self, major: u32, minor: u32) -> Result<(), Vec<String>> {
+        let mut errors = vec![];
+
+        if (major, minor) < PYCONFIG_MIN_VERSION {
+            errors.push(format!(
+                "Python {}.{} does not support PyConfig; Python {}.{}+ is required",
+                major, minor, PYCONFIG_MIN_VERSION.0, PYCONFIG_MIN_VERSION.1
+            ));
+        }
+
+        for (setting, version) in self.version_requirements() {
+            if (major, minor) < version {
+                errors.push(format!(
+                    "{} requires Python {}.{}+; got {}.{}",
+                    setting, version.0, version.1, major, minor
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Describe set fields having a minimum Python version beyond [PYCONFIG_MIN_VERSION].
+    fn version_requirements(&self) -> Vec<(String, (u32, u32))> {
+        let mut requirements = vec![];
+
+        // Hash-based pycs predate `PyConfig`. But the field is only exposed
+        // through it.
+        if self.check_hash_pycs_mode.is_some() {
+            requirements.push(("check_hash_pycs_mode".to_string(), (3, 8)));
+        }
+
+        if let Some(options) = &self.x_options {
+            for option in options {
+                let name = split_xoption(option).0;
+
+                if let Some((_, version)) = X_OPTION_MIN_VERSIONS.iter().find(|(n, _)| *n == name) {
+                    requirements.push((format!("x_options entry {}", option), *version));
+                }
+            }
+        }
+
+        requirements
+    }
+
+    /// Whether the C stdio streams will be in binary mode.
+    ///
+    /// This mirrors `config_init_stdio()` in CPython's `Python/initconfig.c`,
+    /// which only runs when `configure_c_stdio` is enabled (the default for
+    /// the `python` profile but not the `isolated` profile):
+    ///
+    /// * On Windows, `stdin`, `stdout`, and `stderr` are switched to
+    ///   `O_BINARY` so newlines aren't translated. Otherwise they retain the
+    ///   C runtime's default text mode.
+    /// * On other platforms, C stdio doesn't distinguish between text and
+    ///   binary mode, so streams are always binary.
+    ///
+    /// `buffered_stdio` is consulted by the same CPython function but only
+    /// controls buffering (`setvbuf()`), not the stream mode. It therefore
+    /// has no influence on the result.
+    pub fn stdio_is_binary(&self) -> bool {
+        let configure_c_stdio = self
+            .configure_c_stdio
+            .unwrap_or(self.profile == PythonInterpreterProfile::Python);
+
+        !cfg!(windows) || configure_c_stdio
+    }
+
+    /// Determine how `sys.stdout` will buffer writes.
+    ///
+    /// This mirrors `create_stdio()` in CPython's `Python/pylifecycle.c`:
+    ///
+    /// * If [Self::buffered_stdio] is `Some(false)` (`-u`), the binary layer
+    ///   is unbuffered and the text layer writes through, so output is
+    ///   unbuffered.
+    /// * Otherwise, output is line buffered if stdout is a TTY and fully
+    ///   buffered if not.
+    ///
+    /// [Self::interactive] (`-i`) has no influence. It only changes the
+    /// buffering of the C-level `stdout` `FILE*` in `config_init_stdio()`,
+    /// which `sys.stdout` doesn't use.
+    ///
+    /// `is_tty` is whether the stdout file descriptor is a terminal. `None`
+    /// means unknown and is treated as not a terminal, which is the case for
+    /// pipes, files, and most embedding scenarios.
+    ///
+    /// The `PYTHONUNBUFFERED` environment variable isn't consulted. Apply it
+    /// with [Self::apply_env_overrides()] first if it should be considered.
+    pub fn stdout_will_buffer(&self, is_tty: Option<bool>) -> StdoutBuffering {
+        if self.buffered_stdio == Some(false) {
+            StdoutBuffering::Unbuffered
+        } else if is_tty == Some(true) {
+            StdoutBuffering::LineBuffered
+        } else {
+            StdoutBuffering::FullyBuffered
+        }
+    }
+
+    /// Obtain the value of an `-X` option in [Self::x_options].
+    ///
+    /// Returns `None` if the option isn't present, `Some(None)` if it is
+    /// present without a value (e.g. `dev`), or `Some(Some(value))` if it
+    /// has a value (e.g. `utf8=1`). If an option is present multiple times,
+    /// the last occurrence wins, consistent with `sys._xoptions`.
+    pub fn xoption_get(&self, key: &str) -> Option<Option<&str>> {
+        self.x_options
+            .as_ref()?
+            .iter()
+            .rev()
+            .map(|option| split_xoption(option))
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Set an `-X` option in [Self::x_options].
+    ///
+    /// `value` of `None` sets a bare flag. An existing occurrence of the
+    /// option is replaced in place and any further occurrences are removed.
+    /// Otherwise the option is appended.
+    pub fn xoption_set(&mut self, key: &str, value: Option<&str>) {
+        let option = match value {
+            Some(value) => format!("{}={}", key, value),
+            None => key.to_string(),
+        };
+
+        let options = self.x_options.get_or_insert_with(Vec::new);
+
+        match options.iter().position(|o| split_xoption(o).0 == key) {
+            Some(index) => {
+                options[index] = option;
+
+                let rest = options.split_off(index + 1);
+                options.extend(rest.into_iter().filter(|o| split_xoption(o).0 != key));
+            }
+            None => options.push(option),
+        }
+    }
+
+    /// Remove all occurrences of an `-X` option from [Self::x_options].
+    pub fn xoption_remove(&mut self, key: &str) {
+        if let Some(options) = &mut self.x_options {
+            options.retain(|o| split_xoption(o).0 != key);
+        }
+    }
+
+    /// Obtain [Self::x_options] as a map of option names to values.
+    ///
+    /// Options without a value (e.g. `dev`) map to `None` and options with
+    /// a value (e.g. `utf8=1`) to `Some(value)`. If an option is present
+    /// multiple times, the last occurrence wins, consistent with
+    /// `sys._xoptions`. The map is empty if [Self::x_options] isn't set.
+    pub fn x_options_as_map(&self) -> HashMap<String, Option<String>> {
+        self.x_options
+            .iter()
+            .flatten()
+            .map(|option| {
+                let (key, value) = split_xoption(option);
+                (key.to_string(), value.map(|v| v.to_string()))
+            })
+            .collect()
+    }
+
+    /// Construct an instance with [Self::x_options] populated from a map.
+    ///
+    /// This is the inverse of [Self::x_options_as_map()]. Options are sorted
+    /// by name so the result is deterministic. An empty map leaves
+    /// [Self::x_options] unset. All other fields have their default values.
+    pub fn x_options_from_map(map: HashMap<String, Option<String>>) -> Self {
+        let mut options = map.into_iter().collect::<Vec<_>>();
+        options.sort();
+
+        Self {
+            x_options: if options.is_empty() {
+                None
+            } else {
+                Some(
+                    options
+                        .into_iter()
+                        .map(|(key, value)| match value {
+                            Some(value) => format!("{}={}", key, value),
+                            None => key,
+                        })
+                        .collect(),
+                )
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Whether [Self::warn_options] contains a warning filter.
+    ///
+    /// Filters are compared in their normalized [WarnFilter] form, so
+    /// `i::DeprecationWarning` matches `ignore::DeprecationWarning`. Filters
+    /// that can't be parsed are compared verbatim.
+    pub fn warn_options_contains(&self, filter: &str) -> bool {
+        matches!(&self.warn_options, Some(options) if options.iter().any(|o| warn_filters_equal(o, filter)))
+    }
+
+    /// Append a warning filter to [Self::warn_options] unless already present.
+    ///
+    /// Presence is determined as in [Self::warn_options_contains()].
+    pub fn warn_options_add(&mut self, filter: String) {
+        if !self.warn_options_contains(&filter) {
+            self.warn_options.get_or_insert_with(Vec::new).push(filter);
+        }
+    }
+
+    /// Remove a warning filter from [Self::warn_options].
+    ///
+    /// All equivalent filters are removed, as determined by
+    /// [Self::warn_options_contains()]. Returns whether any were removed.
+    pub fn warn_options_remove(&mut self, filter: &str) -> bool {
+        match &mut self.warn_options {
+            Some(options) => {
+                let count = options.len();
+                options.retain(|o| !warn_filters_equal(o, filter));
+                options.len() != count
+            }
+            None => false,
+        }
+    }
+
+    /// Group [Self::warn_options] by the action of each filter.
+    ///
+    /// Keys are the full action names (e.g. `ignore`), including for
+    /// abbreviated filters like `i::DeprecationWarning`. Filter strings are
+    /// kept verbatim and in their original order. Filters that can't be
+    /// parsed as a [WarnFilter] are omitted.
+    pub fn warn_options_by_action(&self) -> HashMap<String, Vec<String>> {
+        let mut res: HashMap<String, Vec<String>> = HashMap::new();
+
+        for option in self.warn_options.iter().flatten() {
+            if let Ok(filter) = WarnFilter::from_str(option) {
+                res.entry(filter.action.to_string())
+                    .or_default()
+                    .push(option.clone());
+            }
+        }
+
+        res
+    }
+
+    /// Obtain [Self::argv] as strings, replacing invalid UTF-8.
+    ///
+    /// Invalid sequences are replaced with `U+FFFD`. This is suitable for
+    /// display or logging. Use [Self::argv_as_strings_strict()] if values
+    /// must be preserved exactly.
+    pub fn argv_as_strings(&self) -> Option<Vec<String>> {
+        self.argv.as_ref().map(|argv| {
+            argv.iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect()
+        })
+    }
+
+    /// Obtain [Self::argv] as strings, failing on invalid UTF-8.
+    ///
+    /// The error holds the index of the first argument that isn't valid
+    /// UTF-8.
+    pub fn argv_as_strings_strict(&self) -> Option<Result<Vec<String>, usize>> {
+        self.argv.as_ref().map(|argv| {
+            argv.iter()
+                .enumerate()
+                .map(|(i, arg)| arg.to_str().map(|s| s.to_string()).ok_or(i))
+                .collect()
+        })
+    }
+
+    /// Obtain [Self::module_search_paths] as strings.
+    ///
+    /// Paths are converted as by [Self::module_search_paths_as_os_strings()].
+    /// An error is returned if a path isn't valid Unicode.
+    pub fn module_search_paths_as_string_vec(&self) -> Result<Option<Vec<String>>, String> {
+        self.module_search_paths_as_os_strings()
+            .map(|paths| {
+                paths
+                    .into_iter()
+                    .map(|path| {
+                        path.into_string().map_err(|path| {
+                            format!(
+                                "module search path is not valid Unicode: {}",
+                                path.to_string_lossy()
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
+
+    /// Obtain [Self::module_search_paths] as [OsString] for passing to CPython.
+    ///
+    /// On Windows, verbatim disk paths (e.g. `\\?\C:\Python`) are converted
+    /// to their conventional form (`C:\Python`) using [dunce::simplified()],
+    /// since not all Python code handles the `\\?\` prefix. Paths are left
+    /// verbatim if the conventional form wouldn't be equivalent, e.g. because
+    /// it would exceed `MAX_PATH`. UNC paths, verbatim or not, are unchanged.
+    /// On other platforms, paths are unchanged.
+    pub fn module_search_paths_as_os_strings(&self) -> Option<Vec<OsString>> {
+        self.module_search_paths.as_ref().map(|paths| {
+            paths
+                .iter()
+                .map(|path| dunce::simplified(path).as_os_str().to_os_string())
+                .collect()
+        })
+    }
+
+    /// Obtain what the interpreter will run, if anything.
+    ///
+    /// [Self::validate()] rejects configurations with more than 1 run field
+    /// set. If that happens anyway, the field CPython gives priority to is
+    /// returned: `run_command`, then `run_module`, then `run_filename`.
+    pub fn active_run_target(&self) -> Option<RunTarget> {
+        if let Some(command) = &self.run_command {
+            Some(RunTarget::Command(command.clone()))
+        } else if let Some(module) = &self.run_module {
+            Some(RunTarget::Module(module.clone()))
+        } else {
+            self.run_filename
+                .as_ref()
+                .map(|path| RunTarget::Filename(path.clone()))
+        }
+    }
+
+    /// Obtain the `sys.argv` CPython constructs for the active run target.
+    ///
+    /// `argv[0]` of [Self::argv] is the program name and is replaced. The
+    /// remaining elements are the arguments following the run target on a
+    /// `python` command line. The new `argv[0]` follows CPython's rules for
+    /// each mode:
+    ///
+    /// * [RunTarget::Command] (`python -c`): `"-c"`.
+    /// * [RunTarget::Module] (`python -m`): `"-m"`. `runpy` replaces this with
+    ///   the module's path once the module is found.
+    /// * [RunTarget::Filename]: the filename.
+    ///
+    /// Returns `None` if there is no run target.
+    pub fn run_target_as_argv(&self) -> Option<Vec<OsString>> {
+        let argv0 = match self.active_run_target()? {
+            RunTarget::Command(_) => OsString::from("-c"),
+            RunTarget::Module(_) => OsString::from("-m"),
+            RunTarget::Filename(path) => path.into_os_string(),
+        };
+
+        Some(
+            std::iter::once(argv0)
+                .chain(self.argv.iter().flatten().skip(1).cloned())
+                .collect(),
+        )
+    }
+
+    /// Whether any of `run_command`, `run_module`, or `run_filename` is set.
+    pub fn has_any_run_target(&self) -> bool {
+        self.active_run_target().is_some()
+    }
+
+    /// Construct an instance describing the configuration of a Python executable.
+    ///
+    /// `python_binary` is run with `-c` and no other arguments, so the result
+    /// reflects how the executable behaves when invoked normally, including
+    /// the effect of `PYTHON*` environment variables of the current process.
+    /// Python 3.8 or newer is required.
+    ///
+    /// Fields are derived from `sys.flags` (e.g. `optimize`, `dev_mode`,
+    /// `no_site`), `sys.warnoptions`, `sys._xoptions`, `sys.path`, and the
+    /// prefix and executable attributes of `sys`. [Self::profile] is
+    /// [PythonInterpreterProfile::Python].
+    ///
+    /// Some settings can't be recovered from a running interpreter:
+    ///
+    /// * [Self::module_search_paths] is `sys.path` without the entry for the
+    ///   `-c` working directory. It includes directories added by the `site`
+    ///   module and `.pth` files.
+    /// * [Self::hash_seed] is only set if hash randomization is disabled. A
+    ///   fixed non-zero seed isn't observable.
+    /// * [Self::argv], [Self::home], and stdio settings (stdio is a pipe
+    ///   while querying) are left unset, as are fields that only influence
+    ///   initialization, such as [Self::allocator].
+    pub fn from_running_interpreter(python_binary: &Path) -> Result<Self, String> {
+        let output = Command::new(python_binary)
+            .arg("-c")
+            .arg(QUERY_INTERPRETER_SCRIPT)
+            .output()
+            .map_err(|e| format!("error running {}: {}", python_binary.display(), e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "{} exited with {}: {}",
+                python_binary.display(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        config_from_interpreter_query(&output.stdout)
+    }
+
+    /// Obtain a [Command] running a stand-alone Python executable with equivalent settings.
+    ///
+    /// Fields are mapped to command line flags (e.g. `-I`, `-E`, `-O`, `-W`,
+    /// and `-X`) where CPython provides one and to environment variables
+    /// otherwise. The run target (see [Self::active_run_target()]) is
+    /// translated to `-c`, `-m`, or a script path, followed by
+    /// `argv[1..]`. `argv[0]` is determined by the executable and isn't
+    /// passed.
+    ///
+    /// Environment variables are ignored by a Python process running with
+    /// `-I` or `-E`. So `hash_seed`, `home`, `python_path_env`, `allocator`,
+    /// `malloc_stats`, `stdio_encoding`, `stdio_errors`,
+    /// `legacy_windows_fs_encoding`, and `legacy_windows_stdio` only take
+    /// effect if the configuration isn't isolated and doesn't disable
+    /// `use_environment`.
+    ///
+    /// The following fields are not reproducible via the command line and are
+    /// ignored: `allocator` values without a `PYTHONMALLOC` equivalent,
+    /// `base_exec_prefix`, `base_executable`, `base_prefix`,
+    /// `coerce_c_locale`, `coerce_c_locale_warn`, `configure_c_stdio`,
+    /// `configure_locale`, `dump_refs`, `exec_prefix`, `executable`,
+    /// `filesystem_encoding`, `filesystem_errors`, `install_signal_handlers`,
+    /// `interactive`, `module_search_paths`, `parse_argv`,
+    /// `pathconfig_warnings`, `prefix`, and `program_name`. The profile is
+    /// only reflected through its effect on `isolated`.
+    pub fn to_python_command(&self, python_binary: &Path) -> Command {
+        let mut command = Command::new(python_binary);
+
+        let isolated = self
+            .isolated
+            .unwrap_or(self.profile == PythonInterpreterProfile::Isolated);
+
+        if isolated {
+            command.arg("-I");
+        } else if self.use_environment == Some(false) {
+            command.arg("-E");
+        }
+
+        // (enabled, flag)
+        let flags = [
+            (self.site_import == Some(false), "-S"),
+            (!isolated && self.user_site_directory == Some(false), "-s"),
+            (self.write_bytecode == Some(false), "-B"),
+            (self.buffered_stdio == Some(false), "-u"),
+            (self.verbose == Some(true), "-v"),
+            (self.quiet == Some(true), "-q"),
+            (self.inspect == Some(true), "-i"),
+            (self.parser_debug == Some(true), "-d"),
+            (self.skip_first_source_line == Some(true), "-x"),
+        ];
+        for (enabled, flag) in flags {
+            if enabled {
+                command.arg(flag);
+            }
+        }
+
+        match self.bytes_warning {
+            Some(BytesWarning::Warn) => {
+                command.arg("-b");
+            }
+            Some(BytesWarning::Raise) => {
+                command.arg("-bb");
+            }
+            _ => {}
+        }
+
+        match self.optimization_level {
+            Some(BytecodeOptimizationLevel::One) => {
+                command.arg("-O");
+            }
+            Some(BytecodeOptimizationLevel::Two) => {
+                command.arg("-OO");
+            }
+            _ => {}
+        }
+
+        if let Some(mode) = self.check_hash_pycs_mode {
+            command.args(["--check-hash-based-pycs", mode.to_pyc_flag()]);
+        }
+
+        for option in self.warn_options.iter().flatten() {
+            command.arg("-W").arg(option);
+        }
+
+        let mut x_options = vec![];
+        if self.development_mode == Some(true) {
+            x_options.push(OsString::from("dev"));
+        }
+        if let Some(value) = self.utf8_mode {
+            x_options.push(OsString::from(format!("utf8={}", value as u8)));
+        }
+        if self.import_time == Some(true) {
+            x_options.push(OsString::from("importtime"));
+        }
+        if self.tracemalloc == Some(true) {
+            x_options.push(OsString::from("tracemalloc"));
+        }
+        if self.fault_handler == Some(true) {
+            x_options.push(OsString::from("faulthandler"));
+        }
+        if self.show_ref_count == Some(true) {
+            x_options.push(OsString::from("showrefcount"));
+        }
+        if let Some(path) = &self.pycache_prefix {
+            let mut option = OsString::from("pycache_prefix=");
+            option.push(path);
+            x_options.push(option);
+        }
+        x_options.extend(self.x_options.iter().flatten().map(OsString::from));
+        for option in x_options {
+            command.arg("-X").arg(option);
+        }
+
+        if let Some(seed) = self.hash_seed {
+            command.env("PYTHONHASHSEED", seed.to_string());
+        }
+        if let Some(home) = &self.home {
+            command.env("PYTHONHOME", home);
+        }
+        if let Some(path) = &self.python_path_env {
+            command.env("PYTHONPATH", path);
+        }
+        if let Some(allocator) = self.allocator {
+            let allocator = allocator.to_string();
+            if let Some((_, keyword, _)) = PYTHON_ENV_KEYWORDS
+                .iter()
+                .find(|(field, _, value)| *field == "allocator" && *value == allocator)
+            {
+                command.env("PYTHONMALLOC", keyword);
+            }
+        }
+        if self.malloc_stats == Some(true) {
+            command.env("PYTHONMALLOCSTATS", "1");
+        }
+        match (&self.stdio_encoding, &self.stdio_errors) {
+            (Some(encoding), Some(errors)) => {
+                command.env("PYTHONIOENCODING", format!("{}:{}", encoding, errors));
+            }
+            (Some(encoding), None) => {
+                command.env("PYTHONIOENCODING", encoding);
+            }
+            (None, Some(errors)) => {
+                command.env("PYTHONIOENCODING", format!(":{}", errors));
+            }
+            (None, None) => {}
+        }
+        if self.legacy_windows_fs_encoding == Some(true) {
+            command.env("PYTHONLEGACYWINDOWSFSENCODING", "1");
+        }
+        if self.legacy_windows_stdio == Some(true) {
+            command.env("PYTHONLEGACYWINDOWSSTDIO", "1");
+        }
+
+        match self.active_run_target() {
+            Some(RunTarget::Command(code)) => {
+                command.arg("-c").arg(code);
+            }
+            Some(RunTarget::Module(name)) => {
+                command.arg("-m").arg(name);
+            }
+            Some(RunTarget::Filename(path)) => {
+                command.arg(path);
+            }
+            None => {
+                // Arguments would otherwise be interpreted as a script path.
+                if self.argv.as_ref().map(|argv| argv.len()).unwrap_or(0) > 1 {
+                    command.arg("-");
+                }
+            }
+        }
+
+        command.args(self.argv.iter().flatten().skip(1));
+
+        command
+    }
+
+    /// Obtain a copy of this instance with a [PythonInterpreterConfigDelta] applied.
+    pub fn apply_delta(&self, delta: &PythonInterpreterConfigDelta) -> Self {
+        Self {
+            profile: delta.profile.unwrap_or(self.profile),
+            allocator: apply_field(&self.allocator, &delta.allocator),
+            configure_locale: apply_field(&self.configure_locale, &delta.configure_locale),
+            coerce_c_locale: apply_field(&self.coerce_c_locale, &delta.coerce_c_locale),
+            coerce_c_locale_warn: apply_field(
+                &self.coerce_c_locale_warn,
+                &delta.coerce_c_locale_warn,
+            ),
+            development_mode: apply_field(&self.development_mode, &delta.development_mode),
+            isolated: apply_field(&self.isolated, &delta.isolated),
+            legacy_windows_fs_encoding: apply_field(
+                &self.legacy_windows_fs_encoding,
+                &delta.legacy_windows_fs_encoding,
+            ),
+            parse_argv: apply_field(&self.parse_argv, &delta.parse_argv),
+            use_environment: apply_field(&self.use_environment, &delta.use_environment),
+            utf8_mode: apply_field(&self.utf8_mode, &delta.utf8_mode),
+            argv: apply_field(&self.argv, &delta.argv),
+            base_exec_prefix: apply_field(&self.base_exec_prefix, &delta.base_exec_prefix),
+            base_executable: apply_field(&self.base_executable, &delta.base_executable),
+            base_prefix: apply_field(&self.base_prefix, &delta.base_prefix),
+            buffered_stdio: apply_field(&self.buffered_stdio, &delta.buffered_stdio),
+            bytes_warning: apply_field(&self.bytes_warning, &delta.bytes_warning),
+            check_hash_pycs_mode: apply_field(
+                &self.check_hash_pycs_mode,
+                &delta.check_hash_pycs_mode,
+            ),
+            configure_c_stdio: apply_field(&self.configure_c_stdio, &delta.configure_c_stdio),
+            dump_refs: apply_field(&self.dump_refs, &delta.dump_refs),
+            exec_prefix: apply_field(&self.exec_prefix, &delta.exec_prefix),
+            executable: apply_field(&self.executable, &delta.executable),
+            fault_handler: apply_field(&self.fault_handler, &delta.fault_handler),
+            filesystem_encoding: apply_field(&self.filesystem_encoding, &delta.filesystem_encoding),
+            filesystem_errors: apply_field(&self.filesystem_errors, &delta.filesystem_errors),
+            hash_seed: apply_field(&self.hash_seed, &delta.hash_seed),
+            home: apply_field(&self.home, &delta.home),
+            import_time: apply_field(&self.import_time, &delta.import_time),
+            inspect: apply_field(&self.inspect, &delta.inspect),
+            install_signal_handlers: apply_field(
+                &self.install_signal_handlers,
+                &delta.install_signal_handlers,
+            ),
+            interactive: apply_field(&self.interactive, &delta.interactive),
+            legacy_windows_stdio: apply_field(
+                &self.legacy_windows_stdio,
+                &delta.legacy_windows_stdio,
+            ),
+            malloc_stats: apply_field(&self.malloc_stats, &delta.malloc_stats),
+            module_search_paths: apply_field(&self.module_search_paths, &delta.module_search_paths),
+            optimization_level: apply_field(&self.optimization_level, &delta.optimization_level),
+            parser_debug: apply_field(&self.parser_debug, &delta.parser_debug),
+            pathconfig_warnings: apply_field(&self.pathconfig_warnings, &delta.pathconfig_warnings),
+            prefix: apply_field(&self.prefix, &delta.prefix),
+            program_name: apply_field(&self.program_name, &delta.program_name),
+            pycache_prefix: apply_field(&self.pycache_prefix, &delta.pycache_prefix),
+            python_path_env: apply_field(&self.python_path_env, &delta.python_path_env),
+            quiet: apply_field(&self.quiet, &delta.quiet),
+            run_command: apply_field(&self.run_command, &delta.run_command),
+            run_filename: apply_field(&self.run_filename, &delta.run_filename),
+            run_module: apply_field(&self.run_module, &delta.run_module),
+            show_ref_count: apply_field(&self.show_ref_count, &delta.show_ref_count),
+            site_import: apply_field(&self.site_import, &delta.site_import),
+            skip_first_source_line: apply_field(
+                &self.skip_first_source_line,
+                &delta.skip_first_source_line,
+            ),
+            stdio_encoding: apply_field(&self.stdio_encoding, &delta.stdio_encoding),
+            stdio_errors: apply_field(&self.stdio_errors, &delta.stdio_errors),
+            tracemalloc: apply_field(&self.tracemalloc, &delta.tracemalloc),
+            user_site_directory: apply_field(&self.user_site_directory, &delta.user_site_directory),
+            verbose: apply_field(&self.verbose, &delta.verbose),
+            warn_options: apply_field(&self.warn_options, &delta.warn_options),
+            write_bytecode: apply_field(&self.write_bytecode, &delta.write_bytecode),
+            x_options: apply_field(&self.x_options, &delta.x_options),
+        }
+    }
+
+    /// Iterate over all set path fields.
+    ///
+    /// Yields `(field name, path)` for each set `PathBuf` field, followed by
+    /// each entry of [Self::module_search_paths].
+    pub fn paths_iter(&self) -> impl Iterator<Item = (&'static str, &Path)> {
+        [
+            ("base_exec_prefix", &self.base_exec_prefix),
+            ("base_executable", &self.base_executable),
+            ("base_prefix", &self.base_prefix),
+            ("exec_prefix", &self.exec_prefix),
+            ("executable", &self.executable),
+            ("home", &self.home),
+            ("prefix", &self.prefix),
+            ("program_name", &self.program_name),
+            ("pycache_prefix", &self.pycache_prefix),
+            ("run_filename", &self.run_filename),
+        ]
+        .into_iter()
+        .filter_map(|(name, path)| path.as_deref().map(|path| (name, path)))
+        .chain(
+            self.module_search_paths
+                .iter()
+                .flatten()
+                .map(|path| ("module_search_paths", path.as_path())),
+        )
+    }
+
+    /// Iterate over mutable references to all set path fields.
+    ///
+    /// Paths are yielded in the same order as [Self::paths_iter()].
+    pub fn paths_iter_mut(&mut self) -> impl Iterator<Item = (&'static str, &mut PathBuf)> {
+        [
+            ("base_exec_prefix", &mut self.base_exec_prefix),
+            ("base_executable", &mut self.base_executable),
+            ("base_prefix", &mut self.base_prefix),
+            ("exec_prefix", &mut self.exec_prefix),
+            ("executable", &mut self.executable),
+            ("home", &mut self.home),
+            ("prefix", &mut self.prefix),
+            ("program_name", &mut self.program_name),
+            ("pycache_prefix", &mut self.pycache_prefix),
+            ("run_filename", &mut self.run_filename),
+        ]
+        .into_iter()
+        .filter_map(|(name, path)| path.as_mut().map(|path| (name, path)))
+        .chain(
+            self.module_search_paths
+                .iter_mut()
+                .flatten()
+                .map(|path| ("module_search_paths", path)),
+        )
+    }
+
+    /// Replace every set path with the result of a function.
+    ///
+    /// e.g. to relocate a configuration to a different prefix. See
+    /// [Self::paths_iter_mut()] for which fields are affected.
+    pub fn remap_paths(&mut self, f: impl Fn(&Path) -> PathBuf) {
+        for (_, path) in self.paths_iter_mut() {
+            *path = f(path);
+        }
+    }
+
+    /// Obtain a copy of this instance with the user's home directory hidden in paths.
+    ///
+    /// Paths under `home` have it replaced with `<home>`, e.g.
+    /// `/home/alice/app/lib` becomes `<home>/app/lib`. This is intended for
+    /// including configurations in logs or bug reports. See
+    /// [Self::paths_iter()] for which fields are affected.
+    ///
+    /// `home` is normally the current user's home directory, e.g. from
+    /// `dirs::home_dir()`. A `home` of `/` leaves paths unchanged.
+    ///
+    /// This is best-effort. Only paths lexically under the home directory
+    /// are rewritten: symlinks aren't resolved and relative paths aren't
+    /// considered. Fields that aren't paths, such as [Self::argv],
+    /// [Self::python_path_env], and [Self::run_command], are left as is and
+    /// may still reveal the username.
+    pub fn clone_scrubbed(&self, home: &Path) -> Self {
+        let mut config = self.clone();
+
+        // A home directory of `/` would match every absolute path.
+        if home.parent().is_some() {
+            config.remap_paths(|path| match path.strip_prefix(home) {
+                Ok(rest) if rest.as_os_str().is_empty() => PathBuf::from("<home>"),
+                Ok(rest) => Path::new("<home>").join(rest),
+                Err(_) => path.to_path_buf(),
+            });
+        }
+
+        config
+    }
+
+    /// Obtain a wrapper whose [Debug] output hides sensitive-looking paths.
+    ///
+    /// `home` is hidden as in [Self::clone_scrubbed()]. See [DebugSafe] for
+    /// what else is hidden.
+    pub fn debug_safe<'a>(&'a self, home: &'a Path) -> DebugSafe<'a> {
+        DebugSafe { config: self, home }
+    }
+
+    /// Obtain the bytecode optimization level the interpreter will run with.
+    ///
+    /// An unset [Self::optimization_level] resolves to level 0, the default
+    /// for both profiles. `PyConfig_InitPythonConfig()` and
+    /// `PyConfig_InitIsolatedConfig()` in CPython's `Python/initconfig.c`
+    /// both initialize `optimization_level` to 0.
+    ///
+    /// [Self::development_mode] has no effect on the result. Python
+    /// Development Mode (`-X dev`) enables extra runtime checks, but
+    /// `config_read()` in `Python/initconfig.c` doesn't alter
+    /// `optimization_level` when `dev_mode` is set: `python -X dev -O`
+    /// still strips assertions.
+    ///
+    /// The `PYTHONOPTIMIZE` environment variable isn't consulted. Apply it
+    /// with [Self::apply_env_overrides()] first if it should be considered.
+    pub fn effective_optimization_level(&self) -> BytecodeOptimizationLevel {
+        self.optimization_level
+            .unwrap_or(BytecodeOptimizationLevel::Zero)
+    }
+
+    /// Whether Python UTF-8 Mode (PEP 540) will be enabled.
+    ///
+    /// `env` holds the environment variables the interpreter will see and
+    /// `locale` the name of the `LC_CTYPE` locale it will run under, as
+    /// reported by `setlocale(LC_CTYPE, NULL)`. `None` means the locale is
+    /// unknown and is treated as not being the C locale.
+    ///
+    /// This follows `preconfig_init_utf8_mode()` in CPython's
+    /// `Python/preconfig.c`:
+    ///
+    /// 1. On Windows, [Self::legacy_windows_fs_encoding] disables UTF-8 Mode.
+    /// 2. An explicit [Self::utf8_mode] is used as-is. The isolated profile
+    ///    defaults it to disabled, so the remaining rules only apply to the
+    ///    Python profile.
+    /// 3. If the environment is used, `PYTHONUTF8=1` enables and
+    ///    `PYTHONUTF8=0` disables UTF-8 Mode. Empty values are ignored.
+    ///    Other values make interpreter initialization fail and are
+    ///    ignored here.
+    /// 4. Outside Windows, the `C` and `POSIX` locales enable UTF-8 Mode.
+    ///
+    /// The locale name is compared verbatim. This matters for musl libc,
+    /// whose C locale is UTF-8 based: callers targeting musl should pass
+    /// the name the target actually reports rather than assuming glibc's.
+    pub fn effective_utf8_mode(&self, env: &HashMap<String, String>, locale: Option<&str>) -> bool {
+        let python_profile = self.profile == PythonInterpreterProfile::Python;
+
+        if cfg!(windows) && self.legacy_windows_fs_encoding == Some(true) {
+            return false;
+        }
+
+        if let Some(value) = self.utf8_mode {
+            return value;
+        }
+
+        if !python_profile {
+            return false;
+        }
+
+        let use_environment =
+            !self.isolated.unwrap_or(false) && self.use_environment.unwrap_or(true);
+
+        if use_environment {
+            match env.get("PYTHONUTF8").map(|s| s.as_str()) {
+                Some("1") => return true,
+                Some("0") => return false,
+                _ => {}
+            }
+        }
+
+        !cfg!(windows) && matches!(locale, Some("C" | "POSIX"))
+    }
+
+    /// Obtain the `repr()` of `sys.flags` in an interpreter using this configuration.
+    ///
+    /// `major` and `minor` are the Python version, which determines the
+    /// members of `sys.flags`: 3.10 added `warn_default_encoding` and 3.11
+    /// added `safe_path` and `int_max_str_digits`. Versions newer than 3.12
+    /// are rendered like 3.12.
+    ///
+    /// Only fields that map to a `sys.flags` member are considered. Unset
+    /// fields take the default of [Self::profile], assuming no `PYTHON*`
+    /// environment variables are set. [Self::utf8_mode] is resolved by
+    /// [Self::effective_utf8_mode()] without an environment or locale, so
+    /// the Python profile running under the C locale reports `utf8_mode=1`
+    /// while this returns `0`.
+    pub fn to_sys_flags_repr(&self, major: u32, minor: u32) -> String {
+        let python_profile = self.profile == PythonInterpreterProfile::Python;
+        let isolated = self.isolated.unwrap_or(!python_profile);
+
+        let int = |value: bool| if value { "1" } else { "0" }.to_string();
+        let py_bool = |value: bool| if value { "True" } else { "False" }.to_string();
+
+        let mut flags = vec![
+            ("debug", int(self.parser_debug == Some(true))),
+            ("inspect", int(self.inspect == Some(true))),
+            ("interactive", int(self.interactive == Some(true))),
+            (
+                "optimize",
+                i32::from(self.effective_optimization_level()).to_string(),
+            ),
+            (
+                "dont_write_bytecode",
+                int(self.write_bytecode == Some(false)),
+            ),
+            (
+                "no_user_site",
+                int(isolated || !self.user_site_directory.unwrap_or(python_profile)),
+            ),
+            ("no_site", int(self.site_import == Some(false))),
+            (
+                "ignore_environment",
+                int(isolated || !self.use_environment.unwrap_or(python_profile)),
+            ),
+            ("verbose", int(self.verbose == Some(true))),
+            (
+                "bytes_warning",
+                self.bytes_warning.map_or(0, i32::from).to_string(),
+            ),
+            ("quiet", int(self.quiet == Some(true))),
+            ("hash_randomization", int(self.hash_seed_is_randomized())),
+            ("isolated", int(isolated)),
+            ("dev_mode", py_bool(self.development_mode == Some(true))),
+            (
+                "utf8_mode",
+                int(self.effective_utf8_mode(&HashMap::new(), None)),
+            ),
+        ];
+
+        if (major, minor) >= (3, 10) {
+            flags.push((
+                "warn_default_encoding",
+                int(self.xoption_get("warn_default_encoding").is_some()),
+            ));
+        }
+
+        if (major, minor) >= (3, 11) {
+            // Isolated mode implies -P.
+            flags.push(("safe_path", py_bool(isolated)));
+            flags.push((
+                "int_max_str_digits",
+                self.xoption_get("int_max_str_digits")
+                    .flatten()
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .unwrap_or(-1)
+                    .to_string(),
+            ));
+        }
+
+        format!(
+            "sys.flags({})",
+            flags
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    /// Obtain the `sysconfig.get_config_vars()` values determined by this configuration.
+    ///
+    /// Most `sysconfig` variables (e.g. `Py_DEBUG`, `EXT_SUFFIX`, `CC`) are
+    /// recorded when CPython is built and can't be influenced by the
+    /// interpreter configuration. The variables that are derived from the
+    /// running interpreter's `sys` attributes, and the fields they
+    /// correspond to, are:
+    ///
+    /// | Variable             | Source                             |
+    /// |----------------------|------------------------------------|
+    /// | `prefix`             | [Self::prefix]                     |
+    /// | `base`               | [Self::prefix]                     |
+    /// | `exec_prefix`        | [Self::exec_prefix]                |
+    /// | `platbase`           | [Self::exec_prefix]                |
+    /// | `installed_base`     | [Self::base_prefix]                |
+    /// | `installed_platbase` | [Self::base_exec_prefix]           |
+    /// | `projectbase`        | directory of [Self::executable]    |
+    ///
+    /// Variables are only present if their source field is set. CPython
+    /// computes unset fields during path configuration, which this function
+    /// doesn't attempt. Paths that aren't valid Unicode are converted
+    /// lossily.
+    pub fn sysconfig_vars(&self) -> HashMap<String, String> {
+        let mut vars = HashMap::new();
+
+        let mut insert = |names: &[&str], path: Option<&Path>| {
+            if let Some(path) = path {
+                for name in names {
+                    vars.insert(name.to_string(), path.to_string_lossy().to_string());
+                }
+            }
+        };
+
+        insert(&["prefix", "base"], self.prefix.as_deref());
+        insert(&["exec_prefix", "platbase"], self.exec_prefix.as_deref());
+        insert(&["installed_base"], self.base_prefix.as_deref());
+        insert(&["installed_platbase"], self.base_exec_prefix.as_deref());
+        insert(
+            &["projectbase"],
+            self.executable
+                .as_deref()
+                .and_then(|p| p.parent())
+                .filter(|p| !p.as_os_str().is_empty()),
+        );
+
+        vars
+    }
+
+    /// Obtain [Self::sysconfig_vars()] as JSON values.
+    ///
+    /// This matches the shape of `sysconfig.get_config_vars()` as emitted by
+    /// tools that dump it to JSON, so the result can be merged into such
+    /// output. All values are strings.
+    ///
+    /// Build-time variables like `EXT_SUFFIX`, `SOABI`, the platform tag, and
+    /// compiler optimization flags (`OPT`) describe the Python distribution
+    /// rather than its interpreter configuration and aren't included.
+    #[cfg(feature = "serde_json")]
+    pub fn to_python_sysconfig_dict(&self) -> HashMap<String, serde_json::Value> {
+        self.sysconfig_vars()
+            .into_iter()
+            .map(|(k, v)| (k, serde_json::Value::String(v)))
+            .collect()
+    }
+
+    /// Obtain the names of set fields that influence where the standard library is found.
+    ///
+    /// These are [Self::base_exec_prefix], [Self::base_prefix],
+    /// [Self::exec_prefix], [Self::home], [Self::module_search_paths],
+    /// [Self::prefix], and [Self::site_import]. Only fields that are `Some`
+    /// are returned, in that order.
+    ///
+    /// A change in the result between two configurations indicates the
+    /// standard library may need to be packaged differently.
+    pub fn fields_touching_stdlib(&self) -> Vec<&'static str> {
+        [
+            ("base_exec_prefix", self.base_exec_prefix.is_some()),
+            ("base_prefix", self.base_prefix.is_some()),
+            ("exec_prefix", self.exec_prefix.is_some()),
+            ("home", self.home.is_some()),
+            ("module_search_paths", self.module_search_paths.is_some()),
+            ("prefix", self.prefix.is_some()),
+            ("site_import", self.site_import.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| if set { Some(name) } else { None })
+        .collect()
+    }
+
+    /// Render this instance as Rust source code constructing it.
+    ///
+    /// The result is a `PythonInterpreterConfig` struct literal, suitable for
+    /// embedding in generated Rust source, e.g. via `include!()`. All fields
+    /// are listed explicitly and types are referenced by their full path
+    /// in the `python_packaging` crate, so the code compiles in any crate
+    /// depending on `python_packaging`.
+    ///
+    /// The literal is preceded by a `// generated` comment containing a
+    /// checksum of the literal, which can be used to detect modifications
+    /// or whether regenerating the code would change it.
+    ///
+    /// Paths and arguments that aren't valid Unicode are rendered as byte
+    /// vectors on Unix, making the code specific to Unix targets. On other
+    /// platforms, they are converted lossily.
+    pub fn embed_in_generated_rust_code(&self) -> String {
+        let literal = self.to_rust_struct_literal(
+            "python_packaging::interpreter",
+            "python_packaging::resource",
+        );
+
+        format!(
+            "// generated by PythonInterpreterConfig::embed_in_generated_rust_code(); checksum fnv1a64:{:016x}\n{}",
+            fnv1a64(literal.as_bytes()),
+            literal
+        )
+    }
+
+    /// Render this instance as a Rust struct literal constructing it.
+    ///
+    /// All fields are listed explicitly. Types defined in this module are
+    /// referenced via `interpreter_path` and [BytecodeOptimizationLevel] via
+    /// `resource_path`. e.g. `pyembed` re-exports all of them, so code for a
+    /// crate depending on `pyembed` can pass `"pyembed"` for both.
+    ///
+    /// The literal ends with a newline. See
+    /// [Self::embed_in_generated_rust_code()] for how values are rendered.
+    pub fn to_rust_struct_literal(&self, interpreter_path: &str, resource_path: &str) -> String {
+        let fields = [
+            (
+                "profile",
+                format!(
+                    "{}::PythonInterpreterProfile::{:?}",
+                    interpreter_path, self.profile
+                ),
+            ),
+            (
+                "allocator",
+                rust_option(&self.allocator, |v| {
+                    format!("{}::Allocator::{:?}", interpreter_path, v)
+                }),
+            ),
+            (
+                "configure_locale",
+                rust_option(&self.configure_locale, |v| v.to_string()),
+            ),
+            (
+                "coerce_c_locale",
+                rust_option(&self.coerce_c_locale, |v| {
+                    format!("{}::CoerceCLocale::{:?}", interpreter_path, v)
+                }),
+            ),
+            (
+                "coerce_c_locale_warn",
+                rust_option(&self.coerce_c_locale_warn, |v| v.to_string()),
+            ),
+            (
+                "development_mode",
+                rust_option(&self.development_mode, |v| v.to_string()),
+            ),
+            ("isolated", rust_option(&self.isolated, |v| v.to_string())),
+            (
+                "legacy_windows_fs_encoding",
+                rust_option(&self.legacy_windows_fs_encoding, |v| v.to_string()),
+            ),
+            (
+                "parse_argv",
+                rust_option(&self.parse_argv, |v| v.to_string()),
+            ),
+            (
+                "use_environment",
+                rust_option(&self.use_environment, |v| v.to_string()),
+            ),
+            ("utf8_mode", rust_option(&self.utf8_mode, |v| v.to_string())),
+            (
+                "argv",
+                rust_option(&self.argv, |v| rust_vec(v, |v| rust_os_string(v))),
+            ),
+            (
+                "base_exec_prefix",
+                rust_option(&self.base_exec_prefix, |v| rust_path_buf(v)),
+            ),
+            (
+                "base_executable",
+                rust_option(&self.base_executable, |v| rust_path_buf(v)),
+            ),
+            (
+                "base_prefix",
+                rust_option(&self.base_prefix, |v| rust_path_buf(v)),
+            ),
+            (
+                "buffered_stdio",
+                rust_option(&self.buffered_stdio, |v| v.to_string()),
+            ),
+            (
+                "bytes_warning",
+                rust_option(&self.bytes_warning, |v| {
+                    format!("{}::BytesWarning::{:?}", interpreter_path, v)
+                }),
+            ),
+            (
+                "check_hash_pycs_mode",
+                rust_option(&self.check_hash_pycs_mode, |v| {
+                    format!("{}::CheckHashPycsMode::{:?}", interpreter_path, v)
+                }),
+            ),
+            (
+                "configure_c_stdio",
+                rust_option(&self.configure_c_stdio, |v| v.to_string()),
+            ),
+            ("dump_refs", rust_option(&self.dump_refs, |v| v.to_string())),
+            (
+                "exec_prefix",
+                rust_option(&self.exec_prefix, |v| rust_path_buf(v)),
+            ),
+            (
+                "executable",
+                rust_option(&self.executable, |v| rust_path_buf(v)),
+            ),
+            (
+                "fault_handler",
+                rust_option(&self.fault_handler, |v| v.to_string()),
+            ),
+            (
+                "filesystem_encoding",
+                rust_option(&self.filesystem_encoding, |v| rust_string(v)),
+            ),
+            (
+                "filesystem_errors",
+                rust_option(&self.filesystem_errors, |v| rust_string(v)),
+            ),
+            ("hash_seed", rust_option(&self.hash_seed, |v| v.to_string())),
+            ("home", rust_option(&self.home, |v| rust_path_buf(v))),
+            (
+                "import_time",
+                rust_option(&self.import_time, |v| v.to_string()),
+            ),
+            ("inspect", rust_option(&self.inspect, |v| v.to_string())),
+            (
+                "install_signal_handlers",
+                rust_option(&self.install_signal_handlers, |v| v.to_string()),
+            ),
+            (
+                "interactive",
+                rust_option(&self.interactive, |v| v.to_string()),
+            ),
+            (
+                "legacy_windows_stdio",
+                rust_option(&self.legacy_windows_stdio, |v| v.to_string()),
+            ),
+            (
+                "malloc_stats",
+                rust_option(&self.malloc_stats, |v| v.to_string()),
+            ),
+            (
+                "module_search_paths",
+                rust_option(&self.module_search_paths, |v| {
+                    rust_vec(v, |v| rust_path_buf(v))
+                }),
+            ),
+            (
+                "optimization_level",
+                rust_option(&self.optimization_level, |v| {
+                    format!("{}::BytecodeOptimizationLevel::{:?}", resource_path, v)
+                }),
+            ),
+            (
+                "parser_debug",
+                rust_option(&self.parser_debug, |v| v.to_string()),
+            ),
+            (
+                "pathconfig_warnings",
+                rust_option(&self.pathconfig_warnings, |v| v.to_string()),
+            ),
+            ("prefix", rust_option(&self.prefix, |v| rust_path_buf(v))),
+            (
+                "program_name",
+                rust_option(&self.program_name, |v| rust_path_buf(v)),
+            ),
+            (
+                "pycache_prefix",
+                rust_option(&self.pycache_prefix, |v| rust_path_buf(v)),
+            ),
+            (
+                "python_path_env",
+                rust_option(&self.python_path_env, |v| rust_string(v)),
+            ),
+            ("quiet", rust_option(&self.quiet, |v| v.to_string())),
+            (
+                "run_command",
+                rust_option(&self.run_command, |v| rust_string(v)),
+            ),
+            (
+                "run_filename",
+                rust_option(&self.run_filename, |v| rust_path_buf(v)),
+            ),
+            (
+                "run_module",
+                rust_option(&self.run_module, |v| rust_string(v)),
+            ),
+            (
+                "show_ref_count",
+                rust_option(&self.show_ref_count, |v| v.to_string()),
+            ),
+            (
+                "site_import",
+                rust_option(&self.site_import, |v| v.to_string()),
+            ),
+            (
+                "skip_first_source_line",
+                rust_option(&self.skip_first_source_line, |v| v.to_string()),
+            ),
+            (
+                "stdio_encoding",
+                rust_option(&self.stdio_encoding, |v| rust_string(v)),
+            ),
+            (
+                "stdio_errors",
+                rust_option(&self.stdio_errors, |v| rust_string(v)),
+            ),
+            (
+                "tracemalloc",
+                rust_option(&self.tracemalloc, |v| v.to_string()),
+            ),
+            (
+                "user_site_directory",
+                rust_option(&self.user_site_directory, |v| v.to_string()),
+            ),
+            ("verbose", rust_option(&self.verbose, |v| v.to_string())),
+            (
+                "warn_options",
+                rust_option(&self.warn_options, |v| rust_vec(v, |v| rust_string(v))),
+            ),
+            (
+                "write_bytecode",
+                rust_option(&self.write_bytecode, |v| v.to_string()),
+            ),
+            (
+                "x_options",
+                rust_option(&self.x_options, |v| rust_vec(v, |v| rust_string(v))),
+            ),
+        ];
+
+        let mut literal = format!("{}::PythonInterpreterConfig {{\n", interpreter_path);
+        for (name, value) in fields {
+            literal.push_str(&format!("    {}: {},\n", name, value));
+        }
+        literal.push_str("}\n");
+
+        literal
+    }
+
+    /// Obtain the name of every optional field and whether it is set.
+    ///
+    /// Fields are in declaration order. [Self::profile] isn't optional and
+    /// isn't included.
+    fn field_set_states(&self) -> [(&'static str, bool); 55] {
+        [
+            ("allocator", self.allocator.is_some()),
+            ("configure_locale", self.configure_locale.is_some()),
+            ("coerce_c_locale", self.coerce_c_locale.is_some()),
+            ("coerce_c_locale_warn", self.coerce_c_locale_warn.is_some()),
+            ("development_mode", self.development_mode.is_some()),
+            ("isolated", self.isolated.is_some()),
+            (
+                "legacy_windows_fs_encoding",
+                self.legacy_windows_fs_encoding.is_some(),
+            ),
+            ("parse_argv", self.parse_argv.is_some()),
+            ("use_environment", self.use_environment.is_some()),
+            ("utf8_mode", self.utf8_mode.is_some()),
+            ("argv", self.argv.is_some()),
+            ("base_exec_prefix", self.base_exec_prefix.is_some()),
+            ("base_executable", self.base_executable.is_some()),
+            ("base_prefix", self.base_prefix.is_some()),
+            ("buffered_stdio", self.buffered_stdio.is_some()),
+            ("bytes_warning", self.bytes_warning.is_some()),
+            ("check_hash_pycs_mode", self.check_hash_pycs_mode.is_some()),
+            ("configure_c_stdio", self.configure_c_stdio.is_some()),
+            ("dump_refs", self.dump_refs.is_some()),
+            ("exec_prefix", self.exec_prefix.is_some()),
+            ("executable", self.executable.is_some()),
+            ("fault_handler", self.fault_handler.is_some()),
+            ("filesystem_encoding", self.filesystem_encoding.is_some()),
+            ("filesystem_errors", self.filesystem_errors.is_some()),
+            ("hash_seed", self.hash_seed.is_some()),
+            ("home", self.home.is_some()),
+            ("import_time", self.import_time.is_some()),
+            ("inspect", self.inspect.is_some()),
+            (
+                "install_signal_handlers",
+                self.install_signal_handlers.is_some(),
+            ),
+            ("interactive", self.interactive.is_some()),
+            ("legacy_windows_stdio", self.legacy_windows_stdio.is_some()),
+            ("malloc_stats", self.malloc_stats.is_some()),
+            ("module_search_paths", self.module_search_paths.is_some()),
+            ("optimization_level", self.optimization_level.is_some()),
+            ("parser_debug", self.parser_debug.is_some()),
+            ("pathconfig_warnings", self.pathconfig_warnings.is_some()),
+            ("prefix", self.prefix.is_some()),
+            ("program_name", self.program_name.is_some()),
+            ("pycache_prefix", self.pycache_prefix.is_some()),
+            ("python_path_env", self.python_path_env.is_some()),
+            ("quiet", self.quiet.is_some()),
+            ("run_command", self.run_command.is_some()),
+            ("run_filename", self.run_filename.is_some()),
+            ("run_module", self.run_module.is_some()),
+            ("show_ref_count", self.show_ref_count.is_some()),
+            ("site_import", self.site_import.is_some()),
+            (
+                "skip_first_source_line",
+                self.skip_first_source_line.is_some(),
+            ),
+            ("stdio_encoding", self.stdio_encoding.is_some()),
+            ("stdio_errors", self.stdio_errors.is_some()),
+            ("tracemalloc", self.tracemalloc.is_some()),
+            ("user_site_directory", self.user_site_directory.is_some()),
+            ("verbose", self.verbose.is_some()),
+            ("warn_options", self.warn_options.is_some()),
+            ("write_bytecode", self.write_bytecode.is_some()),
+            ("x_options", self.x_options.is_some()),
+        ]
+    }
+
+    /// Obtain the number of optional fields that are set.
+    ///
+    /// [Self::profile] is always set and isn't counted.
+    pub fn count_set_fields(&self) -> usize {
+        self.field_set_states()
+            .iter()
+            .filter(|(_, set)| *set)
+            .count()
+    }
+
+    /// Obtain the fraction of optional fields that are set.
+    ///
+    /// Returns a value between `0.0` for [Self::default()] and `1.0` when
+    /// every optional field is set.
+    pub fn coverage_fraction(&self) -> f64 {
+        let states = self.field_set_states();
+
+        self.count_set_fields() as f64 / states.len() as f64
+    }
+
+    /// Obtain the names of optional fields that are unset.
+    ///
+    /// Names are in declaration order.
+    pub fn unset_fields(&self) -> Vec<&'static str> {
+        self.field_set_states()
+            .into_iter()
+            .filter_map(|(name, set)| if set { None } else { Some(name) })
+            .collect()
+    }
+
+    /// Verify that paths referenced by this configuration exist.
+    ///
+    /// Every set path field and [Self::module_search_paths] entry is checked,
+    /// with relative paths resolved against `base`. [Self::program_name] and
+    /// [Self::pycache_prefix] are skipped: the former is commonly a bare
+    /// program name and the latter is created by Python on demand.
+    ///
+    /// Returns the resolved paths that don't exist, in the order of
+    /// [Self::paths_iter()]. Paths with placeholders such as `$ORIGIN` must
+    /// be expanded before calling this.
+    pub fn verify_paths_exist(&self, base: &Path) -> Result<(), Vec<PathBuf>> {
+        let missing = self
+            .paths_iter()
+            .filter(|(name, _)| !matches!(*name, "program_name" | "pycache_prefix"))
+            .map(|(_, path)| base.join(path))
+            .filter(|path| !path.exists())
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+
+    /// Log a warning for each path referenced by this configuration that doesn't exist.
+    ///
+    /// This is [Self::verify_paths_exist()] for callers that want to surface
+    /// likely typos without failing.
+    pub fn warn_on_missing_paths(&self, base: &Path) {
+        if let Err(missing) = self.verify_paths_exist(base) {
+            for path in missing {
+                warn!(
+                    "path referenced by interpreter config does not exist: {}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    /// Render this instance as a snippet of a PyOxidizer Starlark configuration file.
+    ///
+    /// The snippet obtains a `PythonInterpreterConfig` Starlark value via
+    /// `dist.make_python_interpreter_config()` and assigns [Self::profile]
+    /// and every field as attributes on it. Unset fields are assigned
+    /// `None`, overriding any default the distribution provides, so the
+    /// resulting Starlark value equals this instance. It
+    /// can be pasted into the body of a target function in `pyoxidizer.bzl`
+    /// that has a `dist` variable holding a `PythonDistribution`, like the one
+    /// generated by `pyoxidizer init-config-file`.
+    ///
+    /// Booleans render as `True` / `False`, enums as their canonical string
+    /// value, [Self::optimization_level] as an integer, and paths as strings.
+    /// Paths that aren't valid Unicode are converted lossily.
+    ///
+    /// [Self::argv] isn't exposed to Starlark and is always omitted.
+    pub fn to_pyoxidizer_starlark_snippet(&self) -> String {
+        let fields = [
+            (
+                "allocator",
+                self.allocator
+                    .as_ref()
+                    .map(|v| starlark_string(&v.to_string())),
+            ),
+            (
+                "configure_locale",
+                self.configure_locale.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "coerce_c_locale",
+                self.coerce_c_locale
+                    .as_ref()
+                    .map(|v| starlark_string(&v.to_string())),
+            ),
+            (
+                "coerce_c_locale_warn",
+                self.coerce_c_locale_warn
+                    .as_ref()
+                    .map(|v| starlark_bool(*v)),
+            ),
+            (
+                "development_mode",
+                self.development_mode.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "isolated",
+                self.isolated.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "legacy_windows_fs_encoding",
+                self.legacy_windows_fs_encoding
+                    .as_ref()
+                    .map(|v| starlark_bool(*v)),
+            ),
+            (
+                "parse_argv",
+                self.parse_argv.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "use_environment",
+                self.use_environment.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "utf8_mode",
+                self.utf8_mode.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "base_exec_prefix",
+                self.base_exec_prefix.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "base_executable",
+                self.base_executable.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "base_prefix",
+                self.base_prefix.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "buffered_stdio",
+                self.buffered_stdio.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "bytes_warning",
+                self.bytes_warning
+                    .as_ref()
+                    .map(|v| starlark_string(&v.to_string())),
+            ),
+            (
+                "check_hash_pycs_mode",
+                self.check_hash_pycs_mode
+                    .as_ref()
+                    .map(|v| starlark_string(&v.to_string())),
+            ),
+            (
+                "configure_c_stdio",
+                self.configure_c_stdio.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "dump_refs",
+                self.dump_refs.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "exec_prefix",
+                self.exec_prefix.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "executable",
+                self.executable.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "fault_handler",
+                self.fault_handler.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "filesystem_encoding",
+                self.filesystem_encoding
+                    .as_ref()
+                    .map(|v| starlark_string(v)),
+            ),
+            (
+                "filesystem_errors",
+                self.filesystem_errors.as_ref().map(|v| starlark_string(v)),
+            ),
+            ("hash_seed", self.hash_seed.as_ref().map(|v| v.to_string())),
+            ("home", self.home.as_ref().map(|v| starlark_path(v))),
+            (
+                "import_time",
+                self.import_time.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            ("inspect", self.inspect.as_ref().map(|v| starlark_bool(*v))),
+            (
+                "install_signal_handlers",
+                self.install_signal_handlers
+                    .as_ref()
+                    .map(|v| starlark_bool(*v)),
+            ),
+            (
+                "interactive",
+                self.interactive.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "legacy_windows_stdio",
+                self.legacy_windows_stdio
+                    .as_ref()
+                    .map(|v| starlark_bool(*v)),
+            ),
+            (
+                "malloc_stats",
+                self.malloc_stats.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "module_search_paths",
+                self.module_search_paths
+                    .as_ref()
+                    .map(|v| starlark_list(v, |v| starlark_path(v))),
+            ),
+            (
+                "optimization_level",
+                self.optimization_level
+                    .as_ref()
+                    .map(|v| u8::from(*v).to_string()),
+            ),
+            (
+                "parser_debug",
+                self.parser_debug.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "pathconfig_warnings",
+                self.pathconfig_warnings.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            ("prefix", self.prefix.as_ref().map(|v| starlark_path(v))),
+            (
+                "program_name",
+                self.program_name.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "pycache_prefix",
+                self.pycache_prefix.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "python_path_env",
+                self.python_path_env.as_ref().map(|v| starlark_string(v)),
+            ),
+            ("quiet", self.quiet.as_ref().map(|v| starlark_bool(*v))),
+            (
+                "run_command",
+                self.run_command.as_ref().map(|v| starlark_string(v)),
+            ),
+            (
+                "run_filename",
+                self.run_filename.as_ref().map(|v| starlark_path(v)),
+            ),
+            (
+                "run_module",
+                self.run_module.as_ref().map(|v| starlark_string(v)),
+            ),
+            (
+                "show_ref_count",
+                self.show_ref_count.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "site_import",
+                self.site_import.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "skip_first_source_line",
+                self.skip_first_source_line
+                    .as_ref()
+                    .map(|v| starlark_bool(*v)),
+            ),
+            (
+                "stdio_encoding",
+                self.stdio_encoding.as_ref().map(|v| starlark_string(v)),
+            ),
+            (
+                "stdio_errors",
+                self.stdio_errors.as_ref().map(|v| starlark_string(v)),
+            ),
+            (
+                "tracemalloc",
+                self.tracemalloc.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "user_site_directory",
+                self.user_site_directory.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            ("verbose", self.verbose.as_ref().map(|v| starlark_bool(*v))),
+            (
+                "warn_options",
+                self.warn_options
+                    .as_ref()
+                    .map(|v| starlark_list(v, |v| starlark_string(v))),
+            ),
+            (
+                "write_bytecode",
+                self.write_bytecode.as_ref().map(|v| starlark_bool(*v)),
+            ),
+            (
+                "x_options",
+                self.x_options
+                    .as_ref()
+                    .map(|v| starlark_list(v, |v| starlark_string(v))),
+            ),
+        ];
+
+        let mut lines = vec![
+            "python_config = dist.make_python_interpreter_config()".to_string(),
+            format!(
+                "python_config.config_profile = {}",
+                starlark_string(&self.profile.to_string())
+            ),
+        ];
+        lines.extend(fields.into_iter().map(|(name, value)| {
+            format!(
+                "python_config.{} = {}",
+                name,
+                value.unwrap_or_else(|| "None".to_string())
+            )
+        }));
+
+        let mut snippet = lines.join("\n");
+        snippet.push('\n');
+        snippet
+    }
+
+    /// Obtain a copy of this instance normalized for a target platform.
+    ///
+    /// `platform` is a Rust target triple, e.g. `x86_64-pc-windows-msvc`.
+    ///
+    /// Fields CPython ignores on the platform are cleared and a warning is
+    /// logged for each:
+    ///
+    /// * [Self::legacy_windows_fs_encoding] and [Self::legacy_windows_stdio]
+    ///   only have an effect on Windows.
+    /// * [Self::coerce_c_locale] and [Self::coerce_c_locale_warn] have no
+    ///   effect on Windows, which doesn't perform C locale coercion.
+    ///
+    /// No field needs a value on any platform, so nothing is filled in.
+    ///
+    /// An error is returned if `platform` doesn't look like a target triple
+    /// or if explicitly set fields contradict each other on the platform.
+    /// On Windows, enabling both [Self::legacy_windows_fs_encoding] and
+    /// [Self::utf8_mode] is a conflict: CPython disables UTF-8 mode when the
+    /// legacy filesystem encoding is requested.
+    pub fn normalize_for_platform(&self, platform: &str) -> Result<Self, NormalizationError> {
+        if platform.split('-').filter(|s| !s.is_empty()).count() < 2 {
+            return Err(NormalizationError::UnknownPlatform(platform.to_string()));
+        }
+
+        let windows = platform.contains("-windows");
+
+        if windows && self.legacy_windows_fs_encoding == Some(true) && self.utf8_mode == Some(true)
+        {
+            return Err(NormalizationError::Conflict {
+                platform: platform.to_string(),
+                field: "legacy_windows_fs_encoding",
+                other: "utf8_mode",
+            });
+        }
+
+        let mut config = self.clone();
+
+        let mut ignored = vec![];
+
+        if windows {
+            if config.coerce_c_locale.take().is_some() {
+                ignored.push("coerce_c_locale");
+            }
+            if config.coerce_c_locale_warn.take().is_some() {
+                ignored.push("coerce_c_locale_warn");
+            }
+        } else {
+            if config.legacy_windows_fs_encoding.take().is_some() {
+                ignored.push("legacy_windows_fs_encoding");
+            }
+            if config.legacy_windows_stdio.take().is_some() {
+                ignored.push("legacy_windows_stdio");
+            }
+        }
+
+        for name in ignored {
+            warn!("{} has no effect on {}; ignoring", name, platform);
+        }
+
+        Ok(config)
+    }
+}
+
+/// A set of changes to apply to a [PythonInterpreterConfig].
+///
+/// Unlike overlaying a [PythonInterpreterConfig] via
+/// [PythonInterpreterConfig::merge()], a delta can unset fields. Each field
+/// is `None` to leave the field unchanged, `Some(None)` to unset it, or
+/// `Some(Some(value))` to set it to `value`.
+///
+/// Apply via [PythonInterpreterConfig::apply_delta()].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct PythonInterpreterConfigDelta {
+    /// Change to [PythonInterpreterConfig::profile].
+    ///
+    /// The profile can't be unset, so this is a single `Option`.
+    pub profile: Option<PythonInterpreterProfile>,
+
+    /// Change to [PythonInterpreterConfig::allocator].
+    pub allocator: Option<Option<Allocator>>,
+
+    /// Change to [PythonInterpreterConfig::configure_locale].
+    pub configure_locale: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::coerce_c_locale].
+    pub coerce_c_locale: Option<Option<CoerceCLocale>>,
+
+    /// Change to [PythonInterpreterConfig::coerce_c_locale_warn].
+    pub coerce_c_locale_warn: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::development_mode].
+    pub development_mode: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::isolated].
+    pub isolated: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::legacy_windows_fs_encoding].
+    pub legacy_windows_fs_encoding: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::parse_argv].
+    pub parse_argv: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::use_environment].
+    pub use_environment: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::utf8_mode].
+    pub utf8_mode: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::argv].
+    pub argv: Option<Option<Vec<OsString>>>,
+
+    /// Change to [PythonInterpreterConfig::base_exec_prefix].
+    pub base_exec_prefix: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::base_executable].
+    pub base_executable: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::base_prefix].
+    pub base_prefix: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::buffered_stdio].
+    pub buffered_stdio: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::bytes_warning].
+    pub bytes_warning: Option<Option<BytesWarning>>,
+
+    /// Change to [PythonInterpreterConfig::check_hash_pycs_mode].
+    pub check_hash_pycs_mode: Option<Option<CheckHashPycsMode>>,
+
+    /// Change to [PythonInterpreterConfig::configure_c_stdio].
+    pub configure_c_stdio: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::dump_refs].
+    pub dump_refs: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::exec_prefix].
+    pub exec_prefix: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::executable].
+    pub executable: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::fault_handler].
+    pub fault_handler: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::filesystem_encoding].
+    pub filesystem_encoding: Option<Option<String>>,
+
+    /// Change to [PythonInterpreterConfig::filesystem_errors].
+    pub filesystem_errors: Option<Option<String>>,
+
+    /// Change to [PythonInterpreterConfig::hash_seed].
+    pub hash_seed: Option<Option<c_ulong>>,
+
+    /// Change to [PythonInterpreterConfig::home].
+    pub home: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::import_time].
+    pub import_time: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::inspect].
+    pub inspect: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::install_signal_handlers].
+    pub install_signal_handlers: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::interactive].
+    pub interactive: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::legacy_windows_stdio].
+    pub legacy_windows_stdio: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::malloc_stats].
+    pub malloc_stats: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::module_search_paths].
+    pub module_search_paths: Option<Option<Vec<PathBuf>>>,
+
+    /// Change to [PythonInterpreterConfig::optimization_level].
+    pub optimization_level: Option<Option<BytecodeOptimizationLevel>>,
+
+    /// Change to [PythonInterpreterConfig::parser_debug].
+    pub parser_debug: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::pathconfig_warnings].
+    pub pathconfig_warnings: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::prefix].
+    pub prefix: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::program_name].
+    pub program_name: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::pycache_prefix].
+    pub pycache_prefix: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::python_path_env].
+    pub python_path_env: Option<Option<String>>,
+
+    /// Change to [PythonInterpreterConfig::quiet].
+    pub quiet: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::run_command].
+    pub run_command: Option<Option<String>>,
+
+    /// Change to [PythonInterpreterConfig::run_filename].
+    pub run_filename: Option<Option<PathBuf>>,
+
+    /// Change to [PythonInterpreterConfig::run_module].
+    pub run_module: Option<Option<String>>,
+
+    /// Change to [PythonInterpreterConfig::show_ref_count].
+    pub show_ref_count: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::site_import].
+    pub site_import: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::skip_first_source_line].
+    pub skip_first_source_line: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::stdio_encoding].
+    pub stdio_encoding: Option<Option<String>>,
+
+    /// Change to [PythonInterpreterConfig::stdio_errors].
+    pub stdio_errors: Option<Option<String>>,
+
+    /// Change to [PythonInterpreterConfig::tracemalloc].
+    pub tracemalloc: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::user_site_directory].
+    pub user_site_directory: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::verbose].
+    pub verbose: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::warn_options].
+    pub warn_options: Option<Option<Vec<String>>>,
+
+    /// Change to [PythonInterpreterConfig::write_bytecode].
+    pub write_bytecode: Option<Option<bool>>,
+
+    /// Change to [PythonInterpreterConfig::x_options].
+    pub x_options: Option<Option<Vec<String>>>,
+}
+
+impl From<PythonInterpreterConfig> for PythonInterpreterConfigDelta {
+    /// Obtain a delta that replaces every field with the value from `config`.
+    fn from(config: PythonInterpreterConfig) -> Self {
+        Self {
+            profile: Some(config.profile),
+            allocator: Some(config.allocator),
+            configure_locale: Some(config.configure_locale),
+            coerce_c_locale: Some(config.coerce_c_locale),
+            coerce_c_locale_warn: Some(config.coerce_c_locale_warn),
+            development_mode: Some(config.development_mode),
+            isolated: Some(config.isolated),
+            legacy_windows_fs_encoding: Some(config.legacy_windows_fs_encoding),
+            parse_argv: Some(config.parse_argv),
+            use_environment: Some(config.use_environment),
+            utf8_mode: Some(config.utf8_mode),
+            argv: Some(config.argv),
+            base_exec_prefix: Some(config.base_exec_prefix),
+            base_executable: Some(config.base_executable),
+            base_prefix: Some(config.base_prefix),
+            buffered_stdio: Some(config.buffered_stdio),
+            bytes_warning: Some(config.bytes_warning),
+            check_hash_pycs_mode: Some(config.check_hash_pycs_mode),
+            configure_c_stdio: Some(config.configure_c_stdio),
+            dump_refs: Some(config.dump_refs),
+            exec_prefix: Some(config.exec_prefix),
+            executable: Some(config.executable),
+            fault_handler: Some(config.fault_handler),
+            filesystem_encoding: Some(config.filesystem_encoding),
+            filesystem_errors: Some(config.filesystem_errors),
+            hash_seed: Some(config.hash_seed),
+            home: Some(config.home),
+            import_time: Some(config.import_time),
+            inspect: Some(config.inspect),
+            install_signal_handlers: Some(config.install_signal_handlers),
+            interactive: Some(config.interactive),
+            legacy_windows_stdio: Some(config.legacy_windows_stdio),
+            malloc_stats: Some(config.malloc_stats),
+            module_search_paths: Some(config.module_search_paths),
+            optimization_level: Some(config.optimization_level),
+            parser_debug: Some(config.parser_debug),
+            pathconfig_warnings: Some(config.pathconfig_warnings),
+            prefix: Some(config.prefix),
+            program_name: Some(config.program_name),
+            pycache_prefix: Some(config.pycache_prefix),
+            python_path_env: Some(config.python_path_env),
+            quiet: Some(config.quiet),
+            run_command: Some(config.run_command),
+            run_filename: Some(config.run_filename),
+            run_module: Some(config.run_module),
+            show_ref_count: Some(config.show_ref_count),
+            site_import: Some(config.site_import),
+            skip_first_source_line: Some(config.skip_first_source_line),
+            stdio_encoding: Some(config.stdio_encoding),
+            stdio_errors: Some(config.stdio_errors),
+            tracemalloc: Some(config.tracemalloc),
+            user_site_directory: Some(config.user_site_directory),
+            verbose: Some(config.verbose),
+            warn_options: Some(config.warn_options),
+            write_bytecode: Some(config.write_bytecode),
+            x_options: Some(config.x_options),
+        }
+    }
+}
+
+/// Apply a [PythonInterpreterConfigDelta] field change to a field value.
+fn apply_field<T: Clone>(current: &Option<T>, change: &Option<Option<T>>) -> Option<T> {
+    match change {
+        Some(value) => value.clone(),
+        None => current.clone(),
+    }
+}
+
+/// Incrementally construct a [PythonInterpreterConfig].
+///
+/// Each setter assigns `Some(value)` to the field of the same name on
+/// [PythonInterpreterConfig]. [Self::build()] validates the configuration
+/// via [PythonInterpreterConfig::validate()] before returning it.
+#[derive(Clone, Debug, Default)]
+pub struct PythonInterpreterConfigBuilder {
+    config: PythonInterpreterConfig,
+}
+
+impl From<PythonInterpreterConfig> for PythonInterpreterConfigBuilder {
+    fn from(config: PythonInterpreterConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl PythonInterpreterConfigBuilder {
+    /// Construct a builder seeded with the defaults of a profile.
+    ///
+    /// See [PythonInterpreterConfig::for_profile()].
+    pub fn with_profile(profile: PythonInterpreterProfile) -> Self {
+        Self {
+            config: PythonInterpreterConfig::for_profile(profile),
+        }
+    }
+
+    /// Set [PythonInterpreterConfig::profile].
+    pub fn profile(&mut self, value: PythonInterpreterProfile) -> &mut Self {
+        self.config.profile = value;
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::allocator].
+    pub fn allocator(&mut self, value: Allocator) -> &mut Self {
+        self.config.allocator = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::configure_locale].
+    pub fn configure_locale(&mut self, value: bool) -> &mut Self {
+        self.config.configure_locale = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::coerce_c_locale].
+    pub fn coerce_c_locale(&mut self, value: CoerceCLocale) -> &mut Self {
+        self.config.coerce_c_locale = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::coerce_c_locale_warn].
+    pub fn coerce_c_locale_warn(&mut self, value: bool) -> &mut Self {
+        self.config.coerce_c_locale_warn = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::development_mode].
+    pub fn development_mode(&mut self, value: bool) -> &mut Self {
+        self.config.development_mode = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::isolated].
+    pub fn isolated(&mut self, value: bool) -> &mut Self {
+        self.config.isolated = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::legacy_windows_fs_encoding].
+    pub fn legacy_windows_fs_encoding(&mut self, value: bool) -> &mut Self {
+        self.config.legacy_windows_fs_encoding = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::parse_argv].
+    pub fn parse_argv(&mut self, value: bool) -> &mut Self {
+        self.config.parse_argv = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::use_environment].
+    pub fn use_environment(&mut self, value: bool) -> &mut Self {
+        self.config.use_environment = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::utf8_mode].
+    pub fn utf8_mode(&mut self, value: bool) -> &mut Self {
+        self.config.utf8_mode = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::argv].
+    pub fn argv(&mut self, value: Vec<OsString>) -> &mut Self {
+        self.config.argv = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::base_exec_prefix].
+    pub fn base_exec_prefix(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.base_exec_prefix = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::base_executable].
+    pub fn base_executable(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.base_executable = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::base_prefix].
+    pub fn base_prefix(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.base_prefix = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::buffered_stdio].
+    pub fn buffered_stdio(&mut self, value: bool) -> &mut Self {
+        self.config.buffered_stdio = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::bytes_warning].
+    pub fn bytes_warning(&mut self, value: BytesWarning) -> &mut Self {
+        self.config.bytes_warning = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::check_hash_pycs_mode].
+    pub fn check_hash_pycs_mode(&mut self, value: CheckHashPycsMode) -> &mut Self {
+        self.config.check_hash_pycs_mode = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::configure_c_stdio].
+    pub fn configure_c_stdio(&mut self, value: bool) -> &mut Self {
+        self.config.configure_c_stdio = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::dump_refs].
+    pub fn dump_refs(&mut self, value: bool) -> &mut Self {
+        self.config.dump_refs = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::exec_prefix].
+    pub fn exec_prefix(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.exec_prefix = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::executable].
+    pub fn executable(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.executable = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::fault_handler].
+    pub fn fault_handler(&mut self, value: bool) -> &mut Self {
+        self.config.fault_handler = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::filesystem_encoding].
+    pub fn filesystem_encoding(&mut self, value: impl ToString) -> &mut Self {
+        self.config.filesystem_encoding = Some(value.to_string());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::filesystem_errors].
+    pub fn filesystem_errors(&mut self, value: impl ToString) -> &mut Self {
+        self.config.filesystem_errors = Some(value.to_string());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::hash_seed].
+    pub fn hash_seed(&mut self, value: c_ulong) -> &mut Self {
+        self.config.hash_seed = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::home].
+    pub fn home(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.home = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::import_time].
+    pub fn import_time(&mut self, value: bool) -> &mut Self {
+        self.config.import_time = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::inspect].
+    pub fn inspect(&mut self, value: bool) -> &mut Self {
+        self.config.inspect = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::install_signal_handlers].
+    pub fn install_signal_handlers(&mut self, value: bool) -> &mut Self {
+        self.config.install_signal_handlers = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::interactive].
+    pub fn interactive(&mut self, value: bool) -> &mut Self {
+        self.config.interactive = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::legacy_windows_stdio].
+    pub fn legacy_windows_stdio(&mut self, value: bool) -> &mut Self {
+        self.config.legacy_windows_stdio = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::malloc_stats].
+    pub fn malloc_stats(&mut self, value: bool) -> &mut Self {
+        self.config.malloc_stats = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::module_search_paths].
+    pub fn module_search_paths(&mut self, value: Vec<PathBuf>) -> &mut Self {
+        self.config.module_search_paths = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::optimization_level].
+    pub fn optimization_level(&mut self, value: BytecodeOptimizationLevel) -> &mut Self {
+        self.config.optimization_level = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::parser_debug].
+    pub fn parser_debug(&mut self, value: bool) -> &mut Self {
+        self.config.parser_debug = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::pathconfig_warnings].
+    pub fn pathconfig_warnings(&mut self, value: bool) -> &mut Self {
+        self.config.pathconfig_warnings = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::prefix].
+    pub fn prefix(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.prefix = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::program_name].
+    pub fn program_name(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.program_name = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::pycache_prefix].
+    pub fn pycache_prefix(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.pycache_prefix = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::python_path_env].
+    pub fn python_path_env(&mut self, value: impl ToString) -> &mut Self {
+        self.config.python_path_env = Some(value.to_string());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::quiet].
+    pub fn quiet(&mut self, value: bool) -> &mut Self {
+        self.config.quiet = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::run_command].
+    pub fn run_command(&mut self, value: impl ToString) -> &mut Self {
+        self.config.run_command = Some(value.to_string());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::run_filename].
+    pub fn run_filename(&mut self, value: impl AsRef<Path>) -> &mut Self {
+        self.config.run_filename = Some(value.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::run_module].
+    pub fn run_module(&mut self, value: impl ToString) -> &mut Self {
+        self.config.run_module = Some(value.to_string());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::show_ref_count].
+    pub fn show_ref_count(&mut self, value: bool) -> &mut Self {
+        self.config.show_ref_count = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::site_import].
+    pub fn site_import(&mut self, value: bool) -> &mut Self {
+        self.config.site_import = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::skip_first_source_line].
+    pub fn skip_first_source_line(&mut self, value: bool) -> &mut Self {
+        self.config.skip_first_source_line = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::stdio_encoding].
+    pub fn stdio_encoding(&mut self, value: impl ToString) -> &mut Self {
+        self.config.stdio_encoding = Some(value.to_string());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::stdio_errors].
+    pub fn stdio_errors(&mut self, value: impl ToString) -> &mut Self {
+        self.config.stdio_errors = Some(value.to_string());
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::tracemalloc].
+    pub fn tracemalloc(&mut self, value: bool) -> &mut Self {
+        self.config.tracemalloc = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::user_site_directory].
+    pub fn user_site_directory(&mut self, value: bool) -> &mut Self {
+        self.config.user_site_directory = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::verbose].
+    pub fn verbose(&mut self, value: bool) -> &mut Self {
+        self.config.verbose = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::warn_options].
+    pub fn warn_options(&mut self, value: Vec<String>) -> &mut Self {
+        self.config.warn_options = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::write_bytecode].
+    pub fn write_bytecode(&mut self, value: bool) -> &mut Self {
+        self.config.write_bytecode = Some(value);
+        self
+    }
+
+    /// Set [PythonInterpreterConfig::x_options].
+    pub fn x_options(&mut self, value: Vec<String>) -> &mut Self {
+        self.config.x_options = Some(value);
+        self
+    }
+
+    /// Validate and obtain the built [PythonInterpreterConfig].
+    pub fn build(&self) -> Result<PythonInterpreterConfig, String> {
+        self.config.validate().map_err(|errors| errors.join("; "))?;
+
+        Ok(self.config.clone())
+    }
+}
+
+/// Serde helpers to serialize `OsString` values without loss.
+///
+/// Values that are valid UTF-8 are serialized as plain strings. Other values
+/// are serialized as `{"bytes": [...]}` holding their raw bytes. Only UNIX
+/// has a byte representation of `OsString`: on other platforms, values that
+/// aren't valid Unicode fail to serialize.
+///
+/// `{"utf8": "..."}` is also accepted when deserializing.
+#[cfg(feature = "serialization")]
+mod os_string_serde {
+    use {
+        serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+        std::ffi::{OsStr, OsString},
+    };
+
+    #[derive(Serialize)]
+    struct Bytes<'a> {
+        bytes: &'a [u8],
+    }
+
+    /// The accepted deserialized forms of an `OsString`.
+    #[derive(Deserialize)]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[serde(untagged)]
+    pub enum OsStringValue {
+        String(String),
+        Utf8 { utf8: String },
+        Bytes { bytes: Vec<u8> },
+    }
+
+    struct SerializeOsStr<'a>(&'a OsStr);
+
+    impl Serialize for SerializeOsStr<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if let Some(value) = self.0.to_str() {
+                return serializer.serialize_str(value);
+            }
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::ffi::OsStrExt;
+                Bytes {
+                    bytes: self.0.as_bytes(),
+                }
+                .serialize(serializer)
+            }
+
+            #[cfg(not(unix))]
+            {
+                Err(<S::Error as serde::ser::Error>::custom(format!(
+                    "{} is not valid Unicode",
+                    self.0.to_string_lossy()
+                )))
+            }
+        }
+    }
+
+    fn to_os_string<E: de::Error>(value: OsStringValue) -> Result<OsString, E> {
+        match value {
+            OsStringValue::String(value) | OsStringValue::Utf8 { utf8: value } => {
+                Ok(OsString::from(value))
+            }
+            OsStringValue::Bytes { bytes } => {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::ffi::OsStringExt;
+                    Ok(OsString::from_vec(bytes))
+                }
+
+                #[cfg(not(unix))]
+                {
+                    String::from_utf8(bytes)
+                        .map(OsString::from)
+                        .map_err(|e| E::custom(format!("bytes value is not valid UTF-8: {}", e)))
+                }
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Vec<OsString>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(values) => {
+                serializer.collect_seq(values.iter().map(|v| SerializeOsStr(v.as_os_str())))
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<OsString>>, D::Error> {
+        Option::<Vec<OsStringValue>>::deserialize(deserializer)?
+            .map(|values| values.into_iter().map(to_os_string).collect())
+            .transpose()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for PythonInterpreterConfig {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            profile: u.arbitrary()?,
+            allocator: u.arbitrary()?,
+            configure_locale: u.arbitrary()?,
+            coerce_c_locale: u.arbitrary()?,
+            coerce_c_locale_warn: u.arbitrary()?,
+            development_mode: u.arbitrary()?,
+            isolated: u.arbitrary()?,
+            legacy_windows_fs_encoding: u.arbitrary()?,
+            parse_argv: u.arbitrary()?,
+            use_environment: u.arbitrary()?,
+            utf8_mode: u.arbitrary()?,
+            // Most consumers expect valid UTF-8 arguments.
+            argv: Option::<Vec<String>>::arbitrary(u)?
+                .map(|args| args.into_iter().map(OsString::from).collect()),
+            base_exec_prefix: u.arbitrary()?,
+            base_executable: u.arbitrary()?,
+            base_prefix: u.arbitrary()?,
+            buffered_stdio: u.arbitrary()?,
+            bytes_warning: u.arbitrary()?,
+            check_hash_pycs_mode: u.arbitrary()?,
+            configure_c_stdio: u.arbitrary()?,
+            dump_refs: u.arbitrary()?,
+            exec_prefix: u.arbitrary()?,
+            executable: u.arbitrary()?,
+            fault_handler: u.arbitrary()?,
+            filesystem_encoding: u.arbitrary()?,
+            filesystem_errors: u.arbitrary()?,
+            // A seed of 0 disables hash randomization.
+            hash_seed: Option::<c_ulong>::arbitrary(u)?.map(|seed| seed.max(1)),
+            home: u.arbitrary()?,
+            import_time: u.arbitrary()?,
+            inspect: u.arbitrary()?,
+            install_signal_handlers: u.arbitrary()?,
+            interactive: u.arbitrary()?,
+            legacy_windows_stdio: u.arbitrary()?,
+            malloc_stats: u.arbitrary()?,
+            module_search_paths: u.arbitrary()?,
+            optimization_level: u.arbitrary()?,
+            parser_debug: u.arbitrary()?,
+            pathconfig_warnings: u.arbitrary()?,
+            prefix: u.arbitrary()?,
+            program_name: u.arbitrary()?,
+            pycache_prefix: u.arbitrary()?,
+            python_path_env: u.arbitrary()?,
+            quiet: u.arbitrary()?,
+            run_command: u.arbitrary()?,
+            run_filename: u.arbitrary()?,
+            run_module: u.arbitrary()?,
+            show_ref_count: u.arbitrary()?,
+            site_import: u.arbitrary()?,
+            skip_first_source_line: u.arbitrary()?,
+            stdio_encoding: u.arbitrary()?,
+            stdio_errors: u.arbitrary()?,
+            tracemalloc: u.arbitrary()?,
+            user_site_directory: u.arbitrary()?,
+            verbose: u.arbitrary()?,
+            warn_options: u.arbitrary()?,
+            write_bytecode: u.arbitrary()?,
+            x_options: u.arbitrary()?,
+        })
+    }
+}
+
+/// Describes a difference in a field between 2 [PythonInterpreterConfig].
+///
+/// There is a variant for each field of [PythonInterpreterConfig]. `before`
+/// holds the value from the instance [PythonInterpreterConfig::diff()] was
+/// called on and `after` the value from the instance passed to it.
+///
+/// When serialized, the name of the field is stored in a `field` key
+/// alongside `before` and `after`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "serialization",
+    serde(tag = "field", rename_all = "snake_case")
+)]
+pub enum ConfigDiff {
+    /// [PythonInterpreterConfig::profile] differs.
+    Profile {
+        before: PythonInterpreterProfile,
+        after: PythonInterpreterProfile,
+    },
+
+    /// [PythonInterpreterConfig::allocator] differs.
+    Allocator {
+        before: Option<Allocator>,
+        after: Option<Allocator>,
+    },
+
+    /// [PythonInterpreterConfig::configure_locale] differs.
+    ConfigureLocale {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::coerce_c_locale] differs.
+    CoerceCLocale {
+        before: Option<CoerceCLocale>,
+        after: Option<CoerceCLocale>,
+    },
+
+    /// [PythonInterpreterConfig::coerce_c_locale_warn] differs.
+    CoerceCLocaleWarn {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::development_mode] differs.
+    DevelopmentMode {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::isolated] differs.
+    Isolated {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::legacy_windows_fs_encoding] differs.
+    LegacyWindowsFsEncoding {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::parse_argv] differs.
+    ParseArgv {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::use_environment] differs.
+    UseEnvironment {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::utf8_mode] differs.
+    Utf8Mode {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::argv] differs.
+    Argv {
+        #[cfg_attr(feature = "serialization", serde(with = "os_string_serde"))]
+        before: Option<Vec<OsString>>,
+        #[cfg_attr(feature = "serialization", serde(with = "os_string_serde"))]
+        after: Option<Vec<OsString>>,
+    },
+
+    /// [PythonInterpreterConfig::base_exec_prefix] differs.
+    BaseExecPrefix {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::base_executable] differs.
+    BaseExecutable {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::base_prefix] differs.
+    BasePrefix {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::buffered_stdio] differs.
+    BufferedStdio {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::bytes_warning] differs.
+    BytesWarning {
+        before: Option<BytesWarning>,
+        after: Option<BytesWarning>,
+    },
+
+    /// [PythonInterpreterConfig::check_hash_pycs_mode] differs.
+    CheckHashPycsMode {
+        before: Option<CheckHashPycsMode>,
+        after: Option<CheckHashPycsMode>,
+    },
+
+    /// [PythonInterpreterConfig::configure_c_stdio] differs.
+    ConfigureCStdio {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::dump_refs] differs.
+    DumpRefs {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::exec_prefix] differs.
+    ExecPrefix {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::executable] differs.
+    Executable {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::fault_handler] differs.
+    FaultHandler {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::filesystem_encoding] differs.
+    FilesystemEncoding {
+        before: Option<String>,
+        after: Option<String>,
+    },
+
+    /// [PythonInterpreterConfig::filesystem_errors] differs.
+    FilesystemErrors {
+        before: Option<String>,
+        after: Option<String>,
+    },
+
+    /// [PythonInterpreterConfig::hash_seed] differs.
+    HashSeed {
+        before: Option<c_ulong>,
+        after: Option<c_ulong>,
+    },
+
+    /// [PythonInterpreterConfig::home] differs.
+    Home {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::import_time] differs.
+    ImportTime {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::inspect] differs.
+    Inspect {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::install_signal_handlers] differs.
+    InstallSignalHandlers {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::interactive] differs.
+    Interactive {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::legacy_windows_stdio] differs.
+    LegacyWindowsStdio {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::malloc_stats] differs.
+    MallocStats {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::module_search_paths] differs.
+    ModuleSearchPaths {
+        before: Option<Vec<PathBuf>>,
+        after: Option<Vec<PathBuf>>,
+    },
+
+    /// [PythonInterpreterConfig::optimization_level] differs.
+    OptimizationLevel {
+        before: Option<BytecodeOptimizationLevel>,
+        after: Option<BytecodeOptimizationLevel>,
+    },
+
+    /// [PythonInterpreterConfig::parser_debug] differs.
+    ParserDebug {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::pathconfig_warnings] differs.
+    PathconfigWarnings {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::prefix] differs.
+    Prefix {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::program_name] differs.
+    ProgramName {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::pycache_prefix] differs.
+    PycachePrefix {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::python_path_env] differs.
+    PythonPathEnv {
+        before: Option<String>,
+        after: Option<String>,
+    },
+
+    /// [PythonInterpreterConfig::quiet] differs.
+    Quiet {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::run_command] differs.
+    RunCommand {
+        before: Option<String>,
+        after: Option<String>,
+    },
+
+    /// [PythonInterpreterConfig::run_filename] differs.
+    RunFilename {
+        before: Option<PathBuf>,
+        after: Option<PathBuf>,
+    },
+
+    /// [PythonInterpreterConfig::run_module] differs.
+    RunModule {
+        before: Option<String>,
+        after: Option<String>,
+    },
+
+    /// [PythonInterpreterConfig::show_ref_count] differs.
+    ShowRefCount {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::site_import] differs.
+    SiteImport {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::skip_first_source_line] differs.
+    SkipFirstSourceLine {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::stdio_encoding] differs.
+    StdioEncoding {
+        before: Option<String>,
+        after: Option<String>,
+    },
+
+    /// [PythonInterpreterConfig::stdio_errors] differs.
+    StdioErrors {
+        before: Option<String>,
+        after: Option<String>,
+    },
+
+    /// [PythonInterpreterConfig::tracemalloc] differs.
+    Tracemalloc {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::user_site_directory] differs.
+    UserSiteDirectory {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::verbose] differs.
+    Verbose {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::warn_options] differs.
+    WarnOptions {
+        before: Option<Vec<String>>,
+        after: Option<Vec<String>>,
+    },
+
+    /// [PythonInterpreterConfig::write_bytecode] differs.
+    WriteBytecode {
+        before: Option<bool>,
+        after: Option<bool>,
+    },
+
+    /// [PythonInterpreterConfig::x_options] differs.
+    XOptions {
+        before: Option<Vec<String>>,
+        after: Option<Vec<String>>,
+    },
+}
+
+impl ConfigDiff {
+    /// The name of the [PythonInterpreterConfig] field that differs.
+    pub fn field(&self) -> &'static str {
+        match self {
+            Self::Profile { .. } => "profile",
+            Self::Allocator { .. } => "allocator",
+            Self::ConfigureLocale { .. } => "configure_locale",
+            Self::CoerceCLocale { .. } => "coerce_c_locale",
+            Self::CoerceCLocaleWarn { .. } => "coerce_c_locale_warn",
+            Self::DevelopmentMode { .. } => "development_mode",
+            Self::Isolated { .. } => "isolated",
+            Self::LegacyWindowsFsEncoding { .. } => "legacy_windows_fs_encoding",
+            Self::ParseArgv { .. } => "parse_argv",
+            Self::UseEnvironment { .. } => "use_environment",
+            Self::Utf8Mode { .. } => "utf8_mode",
+            Self::Argv { .. } => "argv",
+            Self::BaseExecPrefix { .. } => "base_exec_prefix",
+            Self::BaseExecutable { .. } => "base_executable",
+            Self::BasePrefix { .. } => "base_prefix",
+            Self::BufferedStdio { .. } => "buffered_stdio",
+            Self::BytesWarning { .. } => "bytes_warning",
+            Self::CheckHashPycsMode { .. } => "check_hash_pycs_mode",
+            Self::ConfigureCStdio { .. } => "configure_c_stdio",
+            Self::DumpRefs { .. } => "dump_refs",
+            Self::ExecPrefix { .. } => "exec_prefix",
+            Self::Executable { .. } => "executable",
+            Self::FaultHandler { .. } => "fault_handler",
+            Self::FilesystemEncoding { .. } => "filesystem_encoding",
+            Self::FilesystemErrors { .. } => "filesystem_errors",
+            Self::HashSeed { .. } => "hash_seed",
+            Self::Home { .. } => "home",
+            Self::ImportTime { .. } => "import_time",
+            Self::Inspect { .. } => "inspect",
+            Self::InstallSignalHandlers { .. } => "install_signal_handlers",
+            Self::Interactive { .. } => "interactive",
+            Self::LegacyWindowsStdio { .. } => "legacy_windows_stdio",
+            Self::MallocStats { .. } => "malloc_stats",
+            Self::ModuleSearchPaths { .. } => "module_search_paths",
+            Self::OptimizationLevel { .. } => "optimization_level",
+            Self::ParserDebug { .. } => "parser_debug",
+            Self::PathconfigWarnings { .. } => "pathconfig_warnings",
+            Self::Prefix { .. } => "prefix",
+            Self::ProgramName { .. } => "program_name",
+            Self::PycachePrefix { .. } => "pycache_prefix",
+            Self::PythonPathEnv { .. } => "python_path_env",
+            Self::Quiet { .. } => "quiet",
+            Self::RunCommand { .. } => "run_command",
+            Self::RunFilename { .. } => "run_filename",
+            Self::RunModule { .. } => "run_module",
+            Self::ShowRefCount { .. } => "show_ref_count",
+            Self::SiteImport { .. } => "site_import",
+            Self::SkipFirstSourceLine { .. } => "skip_first_source_line",
+            Self::StdioEncoding { .. } => "stdio_encoding",
+            Self::StdioErrors { .. } => "stdio_errors",
+            Self::Tracemalloc { .. } => "tracemalloc",
+            Self::UserSiteDirectory { .. } => "user_site_directory",
+            Self::Verbose { .. } => "verbose",
+            Self::WarnOptions { .. } => "warn_options",
+            Self::WriteBytecode { .. } => "write_bytecode",
+            Self::XOptions { .. } => "x_options",
+        }
+    }
+}
+
+/// A [PythonInterpreterConfig] with a history of changes to it.
+///
+/// Each call to [Self::update()] records the differences between the
+/// current and new configuration, along with the time of the update. This
+/// allows build systems to trace why a configuration (and therefore a
+/// binary) changed. The history is included when serialized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
+pub struct ConfigSnapshot {
+    config: PythonInterpreterConfig,
+    changelog: Vec<(SystemTime, ConfigDiff)>,
+}
+
+impl ConfigSnapshot {
+    /// Construct an instance from an initial configuration with an empty changelog.
+    pub fn new(config: PythonInterpreterConfig) -> Self {
+        Self {
+            config,
+            changelog: vec![],
+        }
+    }
+
+    /// The current configuration.
+    pub fn config(&self) -> &PythonInterpreterConfig {
+        &self.config
+    }
+
+    /// Recorded changes, oldest first.
+    pub fn changelog(&self) -> &[(SystemTime, ConfigDiff)] {
+        &self.changelog
+    }
+
+    /// Replace the current configuration, recording any differences.
+    ///
+    /// All differences from a single update share a timestamp. Nothing is
+    /// recorded if `new` is identical to the current configuration.
+    pub fn update(&mut self, new: PythonInterpreterConfig) {
+        let now = SystemTime::now();
+
+        self.changelog
+            .extend(self.config.diff(&new).into_iter().map(|diff| (now, diff)));
+        self.config = new;
+    }
+}
+
+/// Prefix of environment variables consulted by [PythonInterpreterConfig::apply_env_overrides()].
+pub const ENV_OVERRIDE_PREFIX: &str = "PYOXIDIZER_CONFIG_";
+
+/// How a CPython environment variable maps to a [PythonInterpreterConfig] field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum PythonEnvVarKind {
+    /// The variable holds the value of the field.
+    Value,
+    /// Any non-empty value enables the boolean field.
+    Enable,
+    /// Any non-empty value disables the boolean field.
+    Disable,
+    /// The variable holds a comma-delimited list.
+    CommaList,
+    /// The variable holds a CPython keyword translated to the field's value.
+    ///
+    /// See [PYTHON_ENV_KEYWORDS].
+    Keyword,
+}
+
+/// CPython environment variables and the [PythonInterpreterConfig] fields they correspond to.
+///
+/// See <https://docs.python.org/3/using/cmdline.html#environment-variables>.
+pub const PYTHON_ENV_VARS: &[(&str, &str, PythonEnvVarKind)] = &[
+    (
+        "PYTHONCOERCECLOCALE",
+        "coerce_c_locale",
+        PythonEnvVarKind::Keyword,
+    ),
+    ("PYTHONDEBUG", "parser_debug", PythonEnvVarKind::Enable),
+    (
+        "PYTHONDEVMODE",
+        "development_mode",
+        PythonEnvVarKind::Enable,
+    ),
+    (
+        "PYTHONDONTWRITEBYTECODE",
+        "write_bytecode",
+        PythonEnvVarKind::Disable,
+    ),
+    (
+        "PYTHONFAULTHANDLER",
+        "fault_handler",
+        PythonEnvVarKind::Enable,
+    ),
+    ("PYTHONHASHSEED", "hash_seed", PythonEnvVarKind::Value),
+    ("PYTHONHOME", "home", PythonEnvVarKind::Value),
+    ("PYTHONINSPECT", "inspect", PythonEnvVarKind::Enable),
+    (
+        "PYTHONLEGACYWINDOWSFSENCODING",
+        "legacy_windows_fs_encoding",
+        PythonEnvVarKind::Enable,
+    ),
+    (
+        "PYTHONLEGACYWINDOWSSTDIO",
+        "legacy_windows_stdio",
+        PythonEnvVarKind::Enable,
+    ),
+    (
+        "PYTHONMALLOCSTATS",
+        "malloc_stats",
+        PythonEnvVarKind::Enable,
+    ),
+    ("PYTHONMALLOC", "allocator", PythonEnvVarKind::Keyword),
+    (
+        "PYTHONNOUSERSITE",
+        "user_site_directory",
+        PythonEnvVarKind::Disable,
+    ),
+    (
+        "PYTHONOPTIMIZE",
+        "optimization_level",
+        PythonEnvVarKind::Value,
+    ),
+    ("PYTHONPATH", "python_path_env", PythonEnvVarKind::Value),
+    (
+        "PYTHONPROFILEIMPORTTIME",
+        "import_time",
+        PythonEnvVarKind::Enable,
+    ),
+    (
+        "PYTHONPYCACHEPREFIX",
+        "pycache_prefix",
+        PythonEnvVarKind::Value,
+    ),
+    ("PYTHONTRACEMALLOC", "tracemalloc", PythonEnvVarKind::Enable),
+    (
+        "PYTHONUNBUFFERED",
+        "buffered_stdio",
+        PythonEnvVarKind::Disable,
+    ),
+    ("PYTHONUTF8", "utf8_mode", PythonEnvVarKind::Value),
+    ("PYTHONVERBOSE", "verbose", PythonEnvVarKind::Enable),
+    (
+        "PYTHONWARNINGS",
+        "warn_options",
+        PythonEnvVarKind::CommaList,
+    ),
+];
+
+/// Values of [PythonEnvVarKind::Keyword] variables and their field value equivalents.
+///
+/// Entries are `(field, variable value, field value)`. Field values use the
+/// representation of [PythonInterpreterConfig::to_map()].
+///
+/// `PYTHONCOERCECLOCALE` is special. `warn` enables `coerce_c_locale_warn`
+/// and `0` disables coercion, which the `coerce_c_locale` field can't
+/// express. Any other value requests coercion to `LC_CTYPE`.
+pub const PYTHON_ENV_KEYWORDS: &[(&str, &str, &str)] = &[
+    ("allocator", "default", "default"),
+    ("allocator", "debug", "debug"),
+    ("allocator", "malloc", "malloc"),
+    ("allocator", "malloc_debug", "malloc-debug"),
+    ("allocator", "pymalloc", "py-malloc"),
+    ("allocator", "pymalloc_debug", "py-malloc-debug"),
+    ("coerce_c_locale", "1", "LC_CTYPE"),
+];
+
+/// Python script printing the state used by [PythonInterpreterConfig::from_running_interpreter()].
+///
+/// Each line is `name=value`, with backslashes and newlines in the value
+/// escaped. List values are emitted as multiple lines with the same name.
+/// Strings are emitted in the filesystem encoding so paths round-trip.
+const QUERY_INTERPRETER_SCRIPT: &str = r#"
+import os, sys
+
+if sys.version_info < (3, 8):
+    sys.exit("Python 3.8 or newer is required")
+
+def emit(name, value):
+    value = os.fsencode(value).replace(b"\\", b"\\\\").replace(b"\n", b"\\n")
+    sys.stdout.buffer.write(name.encode("ascii") + b"=" + value + b"\n")
+
+for name in (
+    "bytes_warning", "debug", "dev_mode", "dont_write_bytecode",
+    "hash_randomization", "ignore_environment", "inspect", "interactive",
+    "isolated", "no_site", "no_user_site", "optimize", "quiet", "utf8_mode",
+    "verbose",
+):
+    emit("flags." + name, str(int(getattr(sys.flags, name))))
+
+for name in (
+    "_base_executable", "base_exec_prefix", "base_prefix", "exec_prefix",
+    "executable", "prefix", "pycache_prefix",
+):
+    value = getattr(sys, name, None)
+    if value:
+        emit(name.lstrip("_"), value)
+
+emit("filesystem_encoding", sys.getfilesystemencoding())
+emit("filesystem_errors", sys.getfilesystemencodeerrors())
+
+# -c inserts the working directory unless safe_path (3.11+) is set.
+path = sys.path
+if not getattr(sys.flags, "safe_path", False) and path and path[0] == "":
+    path = path[1:]
+for entry in path:
+    emit("path", entry)
+
+for option in sys.warnoptions:
+    emit("warnoptions", option)
+
+for key, value in sys._xoptions.items():
+    emit("xoptions", key if value is True else "%s=%s" % (key, value))
+"#;
+
+impl TryFrom<&HashMap<String, String>> for PythonInterpreterConfig {
+    type Error = String;
+
+    /// Construct an instance via [PythonInterpreterConfig::from_map()].
+    ///
+    /// Warnings about unknown keys are logged.
+    fn try_from(map: &HashMap<String, String>) -> Result<Self, Self::Error> {
+        let (config, warnings) = Self::from_map(map)?;
+
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+
+        Ok(config)
+    }
+}
+
+/// Separator between entries of `module_search_paths` in string form.
+#[cfg(windows)]
+const PATH_LIST_SEPARATOR: &str = ";";
+#[cfg(not(windows))]
+const PATH_LIST_SEPARATOR: &str = ":";
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("{} is not a valid boolean value", value)),
+    }
+}
+
+/// Serialized form of [PythonInterpreterConfig].
+///
+/// Fields are ordered by the CPython struct they belong to, matching the
+/// grouping of the CPython documentation: fields shared by `PyPreConfig` and
+/// `PyConfig`, then fields only in `PyPreConfig`, then fields only in
+/// `PyConfig`. This makes it easier to cross-reference serialized
+/// configurations against the documentation without reordering the fields
+/// of [PythonInterpreterConfig] itself.
+#[cfg(feature = "serialization")]
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SerializedPythonInterpreterConfig<'a> {
+    profile: &'a PythonInterpreterProfile,
+
+    // Fields shared by `PyPreConfig` and `PyConfig`.
+    development_mode: &'a Option<bool>,
+    isolated: &'a Option<bool>,
+    parse_argv: &'a Option<bool>,
+    use_environment: &'a Option<bool>,
+
+    // Fields only in `PyPreConfig`.
+    allocator: &'a Option<Allocator>,
+    configure_locale: &'a Option<bool>,
+    coerce_c_locale: &'a Option<CoerceCLocale>,
+    coerce_c_locale_warn: &'a Option<bool>,
+    legacy_windows_fs_encoding: &'a Option<bool>,
+    utf8_mode: &'a Option<bool>,
+
+    // Fields only in `PyConfig`.
+    #[serde(serialize_with = "os_string_serde::serialize")]
+    argv: &'a Option<Vec<OsString>>,
+    base_exec_prefix: &'a Option<PathBuf>,
+    base_executable: &'a Option<PathBuf>,
+    base_prefix: &'a Option<PathBuf>,
+    buffered_stdio: &'a Option<bool>,
+    bytes_warning: &'a Option<BytesWarning>,
+    check_hash_pycs_mode: &'a Option<CheckHashPycsMode>,
+    configure_c_stdio: &'a Option<bool>,
+    dump_refs: &'a Option<bool>,
+    exec_prefix: &'a Option<PathBuf>,
+    executable: &'a Option<PathBuf>,
+    fault_handler: &'a Option<bool>,
+    filesystem_encoding: &'a Option<String>,
+    filesystem_errors: &'a Option<String>,
+    hash_seed: &'a Option<c_ulong>,
+    home: &'a Option<PathBuf>,
+    import_time: &'a Option<bool>,
+    inspect: &'a Option<bool>,
+    install_signal_handlers: &'a Option<bool>,
+    interactive: &'a Option<bool>,
+    legacy_windows_stdio: &'a Option<bool>,
+    malloc_stats: &'a Option<bool>,
+    module_search_paths: &'a Option<Vec<PathBuf>>,
+    optimization_level: &'a Option<BytecodeOptimizationLevel>,
+    parser_debug: &'a Option<bool>,
+    pathconfig_warnings: &'a Option<bool>,
+    prefix: &'a Option<PathBuf>,
+    program_name: &'a Option<PathBuf>,
+    pycache_prefix: &'a Option<PathBuf>,
+    python_path_env: &'a Option<String>,
+    quiet: &'a Option<bool>,
+    run_command: &'a Option<String>,
+    run_filename: &'a Option<PathBuf>,
+    run_module: &'a Option<String>,
+    show_ref_count: &'a Option<bool>,
+    site_import: &'a Option<bool>,
+    skip_first_source_line: &'a Option<bool>,
+    stdio_encoding: &'a Option<String>,
+    stdio_errors: &'a Option<String>,
+    tracemalloc: &'a Option<bool>,
+    user_site_directory: &'a Option<bool>,
+    verbose: &'a Option<bool>,
+    warn_options: &'a Option<Vec<String>>,
+    write_bytecode: &'a Option<bool>,
+    x_options: &'a Option<Vec<String>>,
+}
+
+#[cfg(feature = "serialization")]
+impl Serialize for PythonInterpreterConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedPythonInterpreterConfig {
+            profile: &self.profile,
+            development_mode: &self.development_mode,
+            isolated: &self.isolated,
+            parse_argv: &self.parse_argv,
+            use_environment: &self.use_environment,
+            allocator: &self.allocator,
+            configure_locale: &self.configure_locale,
+            coerce_c_locale: &self.coerce_c_locale,
+            coerce_c_locale_warn: &self.coerce_c_locale_warn,
+            legacy_windows_fs_encoding: &self.legacy_windows_fs_encoding,
+            utf8_mode: &self.utf8_mode,
+            argv: &self.argv,
+            base_exec_prefix: &self.base_exec_prefix,
+            base_executable: &self.base_executable,
+            base_prefix: &self.base_prefix,
+            buffered_stdio: &self.buffered_stdio,
+            bytes_warning: &self.bytes_warning,
+            check_hash_pycs_mode: &self.check_hash_pycs_mode,
+            configure_c_stdio: &self.configure_c_stdio,
+            dump_refs: &self.dump_refs,
+            exec_prefix: &self.exec_prefix,
+            executable: &self.executable,
+            fault_handler: &self.fault_handler,
+            filesystem_encoding: &self.filesystem_encoding,
+            filesystem_errors: &self.filesystem_errors,
+            hash_seed: &self.hash_seed,
+            home: &self.home,
+            import_time: &self.import_time,
+            inspect: &self.inspect,
+            install_signal_handlers: &self.install_signal_handlers,
+            interactive: &self.interactive,
+            legacy_windows_stdio: &self.legacy_windows_stdio,
+            malloc_stats: &self.malloc_stats,
+            module_search_paths: &self.module_search_paths,
+            optimization_level: &self.optimization_level,
+            parser_debug: &self.parser_debug,
+            pathconfig_warnings: &self.pathconfig_warnings,
+            prefix: &self.prefix,
+            program_name: &self.program_name,
+            pycache_prefix: &self.pycache_prefix,
+            python_path_env: &self.python_path_env,
+            quiet: &self.quiet,
+            run_command: &self.run_command,
+            run_filename: &self.run_filename,
+            run_module: &self.run_module,
+            show_ref_count: &self.show_ref_count,
+            site_import: &self.site_import,
+            skip_first_source_line: &self.skip_first_source_line,
+            stdio_encoding: &self.stdio_encoding,
+            stdio_errors: &self.stdio_errors,
+            tracemalloc: &self.tracemalloc,
+            user_site_directory: &self.user_site_directory,
+            verbose: &self.verbose,
+            warn_options: &self.warn_options,
+            write_bytecode: &self.write_bytecode,
+            x_options: &self.x_options,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializers for [PythonInterpreterConfig] fields that add the field name to errors.
+#[cfg(feature = "serialization")]
+mod field_de {
+    use {super::*, serde::de::Error};
+
+    fn with_field_name<'de, D, T>(field: &'static str, deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        T::deserialize(deserializer)
+            .map_err(|e| D::Error::custom(format!("field '{}': {}", field, e)))
+    }
+
+    pub fn profile<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<PythonInterpreterProfile, D::Error> {
+        with_field_name("profile", deserializer)
+    }
+
+    pub fn allocator<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Allocator>, D::Error> {
+        with_field_name("allocator", deserializer)
+    }
+
+    pub fn coerce_c_locale<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<CoerceCLocale>, D::Error> {
+        with_field_name("coerce_c_locale", deserializer)
+    }
+
+    pub fn bytes_warning<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<BytesWarning>, D::Error> {
+        with_field_name("bytes_warning", deserializer)
+    }
+
+    pub fn check_hash_pycs_mode<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<CheckHashPycsMode>, D::Error> {
+        with_field_name("check_hash_pycs_mode", deserializer)
+    }
+
+    pub fn optimization_level<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<BytecodeOptimizationLevel>, D::Error> {
+        with_field_name("optimization_level", deserializer)
+    }
+}
+
+/// Collect paths into a list, retaining only the first occurrence of each.
+fn dedupe_paths<'a>(paths: impl Iterator<Item = &'a PathBuf>) -> Vec<PathBuf> {
+    let mut deduped: Vec<PathBuf> = vec![];
+
+    for path in paths {
+        if !deduped.contains(path) {
+            deduped.push(path.clone());
+        }
+    }
+
+    deduped
+}
+
+/// Quote a string for [PythonInterpreterConfig::to_compact_repr()].
+fn quote_compact_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Parse a quoted string at the start of a value.
+///
+/// Returns the unquoted string and the remainder of the value.
+fn parse_compact_string(s: &str) -> Result<(String, &str), String> {
+    let body = s
+        .strip_prefix('"')
+        .ok_or_else(|| format!("expected a quoted string: {}", s))?;
+
+    let mut value = String::new();
+    let mut chars = body.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &body[i + 1..])),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let rest = &body[i + 2..];
+                    let end = match (rest.strip_prefix('{'), rest.find('}')) {
+                        (Some(_), Some(end)) => end,
+                        _ => return Err(format!("invalid unicode escape in {}", s)),
+                    };
+
+                    let c = u32::from_str_radix(&rest[1..end], 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| format!("invalid unicode escape in {}", s))?;
+                    value.push(c);
+
+                    // Skip over `{...}`.
+                    for _ in 0..=end {
+                        chars.next();
+                    }
+                }
+                _ => return Err(format!("invalid escape sequence in {}", s)),
+            },
+            c => value.push(c),
+        }
+    }
+
+    Err(format!("unterminated quoted string: {}", s))
+}
+
+/// Parse a list of quoted strings, e.g. `["a", "b"]`.
+fn parse_compact_list(s: &str) -> Result<Vec<String>, String> {
+    let mut rest = s
+        .strip_prefix('[')
+        .ok_or_else(|| format!("expected a list: {}", s))?
+        .trim_start();
+    let mut values = vec![];
+
+    if let Some(remaining) = rest.strip_prefix(']') {
+        rest = remaining;
+    } else {
+        loop {
+            let (value, remaining) = parse_compact_string(rest)?;
+            values.push(value);
+
+            let remaining = remaining.trim_start();
+            if let Some(remaining) = remaining.strip_prefix(',') {
+                rest = remaining.trim_start();
+            } else if let Some(remaining) = remaining.strip_prefix(']') {
+                rest = remaining;
+                break;
+            } else {
+                return Err(format!("expected `,` or `]` in list: {}", s));
+            }
+        }
+    }
+
+    if rest.is_empty() {
+        Ok(values)
+    } else {
+        Err(format!("unexpected content after list: {}", s))
+    }
+}
+
+/// Render an `Option` as Rust source, rendering the inner value with a function.
+fn rust_option<T>(value: &Option<T>, f: impl Fn(&T) -> String) -> String {
+    match value {
+        Some(value) => format!("Some({})", f(value)),
+        None => "None".to_string(),
+    }
+}
+
+/// Render a list as a `vec![]` Rust expression.
+fn rust_vec<T>(values: &[T], f: impl Fn(&T) -> String) -> String {
+    format!(
+        "vec![{}]",
+        values.iter().map(f).collect::<Vec<_>>().join(", ")
+    )
+}
+
+/// Render a string as a Rust expression producing a `String`.
+fn rust_string(value: &str) -> String {
+    format!("\"{}\".to_string()", value.escape_default())
+}
+
+/// Render an `OsStr` as a Rust expression producing an `OsString`.
+fn rust_os_string(value: &OsStr) -> String {
+    if let Some(value) = value.to_str() {
+        return format!("std::ffi::OsString::from(\"{}\")", value.escape_default());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        format!(
+            "<std::ffi::OsString as std::os::unix::ffi::OsStringExt>::from_vec(vec!{:?})",
+            value.as_bytes()
+        )
+    }
+
+    #[cfg(not(unix))]
+    {
+        format!(
+            "std::ffi::OsString::from(\"{}\")",
+            value.to_string_lossy().escape_default()
+        )
+    }
+}
+
+/// Render a path as a Rust expression producing a `PathBuf`.
+fn rust_path_buf(value: &Path) -> String {
+    match value.to_str() {
+        Some(value) => format!("std::path::PathBuf::from(\"{}\")", value.escape_default()),
+        None => format!(
+            "std::path::PathBuf::from({})",
+            rust_os_string(value.as_os_str())
+        ),
+    }
+}
+
+/// Compute the 64-bit FNV-1a hash of data.
+///
+/// Unlike the hashers in the standard library, the result is stable across
+/// Rust versions and platforms.
+fn fnv1a64(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Render a boolean as a Starlark literal.
+fn starlark_bool(value: bool) -> String {
+    if value { "True" } else { "False" }.to_string()
+}
+
+/// Render a string as a Starlark string literal.
+fn starlark_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_ascii_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Render a path as a Starlark string literal.
+fn starlark_path(value: &Path) -> String {
+    starlark_string(&value.to_string_lossy())
+}
+
+/// Render a list as a Starlark list literal.
+fn starlark_list<T>(values: &[T], f: impl Fn(&T) -> String) -> String {
+    format!("[{}]", values.iter().map(f).collect::<Vec<_>>().join(", "))
+}
+
+/// Parse the content of a `.env` file into `(name, value)` pairs.
+///
+/// See [PythonInterpreterConfig::from_dotenv_file()] for the syntax.
+#[cfg(feature = "dotenv")]
+fn parse_dotenv(data: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = vec![];
+
+    for (i, line) in data.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=VALUE", i + 1))?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("line {}: invalid variable name: {}", i + 1, key));
+        }
+
+        let value = parse_dotenv_value(value.trim())
+            .map_err(|e| format!("line {}: {}: {}", i + 1, key, e))?;
+
+        vars.push((key.to_string(), value));
+    }
+
+    Ok(vars)
+}
+
+/// Parse the value of a `.env` file variable.
+#[cfg(feature = "dotenv")]
+fn parse_dotenv_value(value: &str) -> Result<String, String> {
+    let (parsed, rest) = if let Some(value) = value.strip_prefix('\'') {
+        let end = value
+            .find('\'')
+            .ok_or_else(|| "unterminated single quoted value".to_string())?;
+        (value[..end].to_string(), &value[end + 1..])
+    } else if let Some(value) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = value.char_indices();
+
+        loop {
+            match chars.next() {
+                Some((i, '"')) => break (parsed, &value[i + 1..]),
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, '\\')) => parsed.push('\\'),
+                    Some((_, '"')) => parsed.push('"'),
+                    Some((_, 'n')) => parsed.push('\n'),
+                    Some((_, 'r')) => parsed.push('\r'),
+                    Some((_, 't')) => parsed.push('\t'),
+                    Some((_, c)) => return Err(format!("invalid escape sequence: \\{}", c)),
+                    None => return Err("unterminated double quoted value".to_string()),
+                },
+                Some((_, c)) => parsed.push(c),
+                None => return Err("unterminated double quoted value".to_string()),
+            }
+        }
+    } else {
+        let end = value
+            .char_indices()
+            .find(|(i, c)| *c == '#' && value[..*i].ends_with(char::is_whitespace))
+            .map(|(i, _)| i)
+            .unwrap_or(value.len());
+
+        return Ok(value[..end].trim_end().to_string());
+    };
+
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(parsed)
+    } else {
+        Err(format!("unexpected content after quoted value: {}", rest))
+    }
+}
+
+/// Construct a [PythonInterpreterConfig] from the output of [QUERY_INTERPRETER_SCRIPT].
+fn config_from_interpreter_query(data: &[u8]) -> Result<PythonInterpreterConfig, String> {
+    let mut values: HashMap<&str, Vec<OsString>> = HashMap::new();
+
+    for line in data.split(|b| *b == b'\n').filter(|line| !line.is_empty()) {
+        let split = line
+            .iter()
+            .position(|b| *b == b'=')
+            .ok_or_else(|| format!("malformed line: {}", String::from_utf8_lossy(line)))?;
+        let name = std::str::from_utf8(&line[..split])
+            .map_err(|_| format!("malformed line: {}", String::from_utf8_lossy(line)))?;
+
+        let mut value = Vec::with_capacity(line.len() - split);
+        let mut bytes = line[split + 1..].iter();
+        while let Some(b) = bytes.next() {
+            match (*b, bytes.clone().next()) {
+                (b'\\', Some(b'n')) => {
+                    value.push(b'\n');
+                    bytes.next();
+                }
+                (b'\\', Some(b'\\')) => {
+                    value.push(b'\\');
+                    bytes.next();
+                }
+                (b, _) => value.push(b),
+            }
+        }
+
+        values
+            .entry(name)
+            .or_default()
+            .push(os_string_from_bytes(value));
+    }
+
+    let flag = |name: &str| -> Result<u32, String> {
+        values
+            .get(format!("flags.{}", name).as_str())
+            .and_then(|v| v.last())
+            .and_then(|v| v.to_str())
+            .ok_or_else(|| format!("sys.flags.{} not reported", name))?
+            .parse::<u32>()
+            .map_err(|e| format!("sys.flags.{}: {}", name, e))
+    };
+    let path = |name: &str| values.get(name).and_then(|v| v.last()).map(PathBuf::from);
+    let string = |name: &str| {
+        values
+            .get(name)
+            .and_then(|v| v.last())
+            .map(|v| v.to_string_lossy().to_string())
+    };
+    let strings = |name: &str| {
+        values
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|v| v.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    Ok(PythonInterpreterConfig {
+        profile: PythonInterpreterProfile::Python,
+        development_mode: Some(flag("dev_mode")? > 0),
+        isolated: Some(flag("isolated")? > 0),
+        use_environment: Some(flag("ignore_environment")? == 0),
+        utf8_mode: Some(flag("utf8_mode")? > 0),
+        base_exec_prefix: path("base_exec_prefix"),
+        base_executable: path("base_executable"),
+        base_prefix: path("base_prefix"),
+        bytes_warning: Some(BytesWarning::from(flag("bytes_warning")? as i32)),
+        exec_prefix: path("exec_prefix"),
+        executable: path("executable"),
+        filesystem_encoding: string("filesystem_encoding"),
+        filesystem_errors: string("filesystem_errors"),
+        hash_seed: if flag("hash_randomization")? == 0 {
+            Some(0)
+        } else {
+            None
+        },
+        inspect: Some(flag("inspect")? > 0),
+        interactive: Some(flag("interactive")? > 0),
+        module_search_paths: Some(
+            values
+                .get("path")
+                .into_iter()
+                .flatten()
+                .map(PathBuf::from)
+                .collect(),
+        ),
+        optimization_level: Some(BytecodeOptimizationLevel::from_flag_count(flag(
+            "optimize",
+        )?)),
+        parser_debug: Some(flag("debug")? > 0),
+        prefix: path("prefix"),
+        pycache_prefix: path("pycache_prefix"),
+        quiet: Some(flag("quiet")? > 0),
+        site_import: Some(flag("no_site")? == 0),
+        user_site_directory: Some(flag("no_user_site")? == 0),
+        verbose: Some(flag("verbose")? > 0),
+        warn_options: Some(strings("warnoptions")),
+        write_bytecode: Some(flag("dont_write_bytecode")? == 0),
+        x_options: Some(strings("xoptions")),
+        ..Default::default()
+    })
+}
+
+/// Convert bytes in the filesystem encoding to an [OsString].
+fn os_string_from_bytes(data: Vec<u8>) -> OsString {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        OsString::from_vec(data)
+    }
+
+    #[cfg(not(unix))]
+    {
+        OsString::from(String::from_utf8_lossy(&data).to_string())
+    }
+}
+
+/// Replace path components that may hold credentials with `<redacted>`.
+///
+/// See [DebugSafe].
+fn redact_secret_path_components(path: &Path) -> PathBuf {
+    path.components()
+        .map(|component| match component {
+            Component::Normal(name) => {
+                let lower = name.to_string_lossy().to_lowercase();
+
+                if lower == ".ssh" || lower.contains("token") || lower.contains("secret") {
+                    OsStr::new("<redacted>")
+                } else {
+                    name
+                }
+            }
+            component => component.as_os_str(),
+        })
+        .collect()
+}
+
+/// Split an `-X` option into its key and optional value.
+fn split_xoption(option: &str) -> (&str, Option<&str>) {
+    match option.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (option, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::hash::Hasher};
+
+    #[test]
+    fn test_validate_default() {
+        assert_eq!(PythonInterpreterConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_run_targets() {
+        let config = PythonInterpreterConfig {
+            run_command: Some("import sys".to_string()),
+            run_module: Some("foo".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "only 1 of run_command, run_module, and run_filename can be set; found run_command, run_module".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_isolated() {
+        let config = PythonInterpreterConfig {
+            isolated: Some(true),
+            use_environment: Some(true),
+            user_site_directory: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(config.validate().unwrap_err().len(), 2);
+
+        assert_eq!(PythonInterpreterConfig::for_testing().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_for_testing() {
+        let config = PythonInterpreterConfig::for_testing();
+
+        // All fields but the 3 run fields are set.
+        assert_eq!(config.to_map().len(), 53);
+        assert!(!config.has_any_run_target());
+        assert!(config.is_effectively_isolated());
+        assert_eq!(config.hash_seed, Some(12345));
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(config.validate_for_version(3, 8), Ok(()));
+        assert_eq!(config.with_run_module("foo").validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_argv() {
+        let mut config = PythonInterpreterConfig {
+            parse_argv: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.validate(),
+            Err(vec!["parse_argv is true but argv is not set".to_string()])
+        );
+
+        config.argv = Some(vec!["prog".into()]);
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_skip_first_source_line() {
+        let mut config = PythonInterpreterConfig {
+            skip_first_source_line: Some(true),
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_err());
+
+        config.run_filename = Some(PathBuf::from("script.py"));
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_builder() -> Result<(), String> {
+        let config = PythonInterpreterConfigBuilder::default()
+            .profile(PythonInterpreterProfile::Python)
+            .optimization_level(BytecodeOptimizationLevel::Two)
+            .home("/opt/python")
+            .run_module("foo")
+            .build()?;
+
+        assert_eq!(
+            config,
+            PythonInterpreterConfig {
+                profile: PythonInterpreterProfile::Python,
+                optimization_level: Some(BytecodeOptimizationLevel::Two),
+                home: Some(PathBuf::from("/opt/python")),
+                run_module: Some("foo".to_string()),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            PythonInterpreterConfigBuilder::from(config.clone()).build()?,
+            config
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_validates() {
+        assert!(PythonInterpreterConfigBuilder::default()
+            .run_command("pass")
+            .run_module("foo")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_builder_with_profile() -> Result<(), String> {
+        let config =
+            PythonInterpreterConfigBuilder::with_profile(PythonInterpreterProfile::Isolated)
+                .build()?;
+        assert_eq!(config.isolated, Some(true));
+        assert_eq!(config.use_environment, Some(false));
+        assert_eq!(config.user_site_directory, Some(false));
+
+        let config = PythonInterpreterConfigBuilder::with_profile(PythonInterpreterProfile::Python)
+            .build()?;
+        assert_eq!(config.isolated, Some(false));
+        assert_eq!(config.use_environment, Some(true));
+        assert_eq!(config.user_site_directory, Some(true));
+
+        Ok(())
+    }
+
+    fn all_fields_set() -> PythonInterpreterConfig {
+        PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            allocator: Some(Allocator::Debug),
+            configure_locale: Some(true),
+            coerce_c_locale: Some(CoerceCLocale::C),
+            coerce_c_locale_warn: Some(true),
+            development_mode: Some(true),
+            isolated: Some(false),
+            legacy_windows_fs_encoding: Some(true),
+            parse_argv: Some(true),
+            use_environment: Some(true),
+            utf8_mode: Some(true),
+            argv: Some(vec!["prog".into(), "arg".into()]),
+            base_exec_prefix: Some(PathBuf::from("/base_exec_prefix")),
+            base_executable: Some(PathBuf::from("/base_executable")),
+            base_prefix: Some(PathBuf::from("/base_prefix")),
+            buffered_stdio: Some(false),
+            bytes_warning: Some(BytesWarning::Warn),
+            check_hash_pycs_mode: Some(CheckHashPycsMode::Always),
+            configure_c_stdio: Some(true),
+            dump_refs: Some(true),
+            exec_prefix: Some(PathBuf::from("/exec_prefix")),
+            executable: Some(PathBuf::from("/executable")),
+            fault_handler: Some(true),
+            filesystem_encoding: Some("utf-8".to_string()),
+            filesystem_errors: Some("strict".to_string()),
+            hash_seed: Some(42),
+            home: Some(PathBuf::from("/home")),
+            import_time: Some(true),
+            inspect: Some(true),
+            install_signal_handlers: Some(true),
+            interactive: Some(true),
+            legacy_windows_stdio: Some(true),
+            malloc_stats: Some(true),
+            module_search_paths: Some(vec![PathBuf::from("/lib"), PathBuf::from("/lib2")]),
+            optimization_level: Some(BytecodeOptimizationLevel::One),
+            parser_debug: Some(true),
+            pathconfig_warnings: Some(true),
+            prefix: Some(PathBuf::from("/prefix")),
+            program_name: Some(PathBuf::from("/program_name")),
+            pycache_prefix: Some(PathBuf::from("/pycache_prefix")),
+            python_path_env: Some("/pythonpath".to_string()),
+            quiet: Some(true),
+            run_command: None,
+            run_filename: Some(PathBuf::from("/script.py")),
+            run_module: None,
+            show_ref_count: Some(true),
+            site_import: Some(false),
+            skip_first_source_line: Some(true),
+            stdio_encoding: Some("utf-8".to_string()),
+            stdio_errors: Some("surrogateescape".to_string()),
+            tracemalloc: Some(true),
+            user_site_directory: Some(false),
+            verbose: Some(true),
+            warn_options: Some(vec!["ignore".to_string()]),
+            write_bytecode: Some(false),
+            x_options: Some(vec!["dev".to_string(), "utf8=1".to_string()]),
+        }
+    }
+
+    #[test]
+    fn test_merge_none_overlay() {
+        let base = all_fields_set();
+
+        assert_eq!(
+            base.merge(&PythonInterpreterConfig {
+                profile: PythonInterpreterProfile::Isolated,
+                ..Default::default()
+            }),
+            base
+        );
+    }
+
+    #[test]
+    fn test_merge_full_overlay() {
+        let overlay = all_fields_set();
+
+        let merged = PythonInterpreterConfig::default().merge(&overlay);
+        assert_eq!(
+            merged,
+            PythonInterpreterConfig {
+                profile: PythonInterpreterProfile::Isolated,
+                ..overlay.clone()
+            }
+        );
+
+        let base = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            home: Some(PathBuf::from("/other")),
+            run_command: Some("pass".to_string()),
+            ..Default::default()
+        };
+
+        let merged = base.merge(&overlay);
+        assert_eq!(merged.home, overlay.home);
+        assert_eq!(merged.run_command, base.run_command);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+        let config = PythonInterpreterConfig::default();
+        let s = config.to_toml_string()?;
+        assert_eq!(s, "profile = \"isolated\"\n");
+        assert_eq!(PythonInterpreterConfig::from_toml_str(&s)?, config);
+
+        let config = all_fields_set();
+        let s = config.to_toml_string()?;
+        assert!(s.contains("argv = [\"prog\", \"arg\"]"), "{}", s);
+        assert!(s.contains("optimization_level = \"1\""));
+        assert_eq!(PythonInterpreterConfig::from_toml_str(&s)?, config);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_partial() -> Result<(), Box<dyn std::error::Error>> {
+        let config = PythonInterpreterConfig::from_toml_str(
+            "profile = \"python\"\nallocator = \"malloc\"\nargv = [\"prog\"]\nhome = \"/opt/python\"\n",
+        )?;
+
+        assert_eq!(
+            config,
+            PythonInterpreterConfig {
+                profile: PythonInterpreterProfile::Python,
+                allocator: Some(Allocator::Malloc),
+                argv: Some(vec!["prog".into()]),
+                home: Some(PathBuf::from("/opt/python")),
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_flags_default() {
+        let config = PythonInterpreterConfig::default();
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+    }
+
+    #[test]
+    fn test_pypreconfig_flags() {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+
+        config.configure_locale = Some(true);
+        assert_eq!(
+            config.to_pypreconfig_flags(),
+            PYPRECONFIG_FLAG_CONFIGURE_LOCALE
+        );
+        config.configure_locale = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.configure_locale = None;
+
+        config.coerce_c_locale_warn = Some(true);
+        assert_eq!(
+            config.to_pypreconfig_flags(),
+            PYPRECONFIG_FLAG_COERCE_C_LOCALE_WARN
+        );
+        config.coerce_c_locale_warn = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.coerce_c_locale_warn = None;
+
+        config.development_mode = Some(true);
+        assert_eq!(config.to_pypreconfig_flags(), PYPRECONFIG_FLAG_DEV_MODE);
+        config.development_mode = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.development_mode = None;
+
+        config.isolated = Some(true);
+        assert_eq!(config.to_pypreconfig_flags(), PYPRECONFIG_FLAG_ISOLATED);
+        config.isolated = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.isolated = None;
+
+        config.legacy_windows_fs_encoding = Some(true);
+        assert_eq!(
+            config.to_pypreconfig_flags(),
+            PYPRECONFIG_FLAG_LEGACY_WINDOWS_FS_ENCODING
+        );
+        config.legacy_windows_fs_encoding = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.legacy_windows_fs_encoding = None;
+
+        config.parse_argv = Some(true);
+        assert_eq!(config.to_pypreconfig_flags(), PYPRECONFIG_FLAG_PARSE_ARGV);
+        config.parse_argv = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.parse_argv = None;
+
+        config.use_environment = Some(true);
+        assert_eq!(
+            config.to_pypreconfig_flags(),
+            PYPRECONFIG_FLAG_USE_ENVIRONMENT
+        );
+        config.use_environment = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.use_environment = None;
+
+        config.utf8_mode = Some(true);
+        assert_eq!(config.to_pypreconfig_flags(), PYPRECONFIG_FLAG_UTF8_MODE);
+        config.utf8_mode = Some(false);
+        assert_eq!(config.to_pypreconfig_flags(), 0);
+        config.utf8_mode = None;
+
+        assert_eq!(all_fields_set().to_pypreconfig_flags(), 0b1111_0111);
+    }
+
+    #[test]
+    fn test_pyconfig_flags() {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(config.to_pyconfig_flags(), 0);
+
+        config.buffered_stdio = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_BUFFERED_STDIO);
+        config.buffered_stdio = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.buffered_stdio = None;
+
+        config.configure_c_stdio = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_CONFIGURE_C_STDIO);
+        config.configure_c_stdio = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.configure_c_stdio = None;
+
+        config.development_mode = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_DEV_MODE);
+        config.development_mode = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.development_mode = None;
+
+        config.dump_refs = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_DUMP_REFS);
+        config.dump_refs = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.dump_refs = None;
+
+        config.fault_handler = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_FAULTHANDLER);
+        config.fault_handler = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.fault_handler = None;
+
+        config.import_time = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_IMPORT_TIME);
+        config.import_time = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.import_time = None;
+
+        config.inspect = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_INSPECT);
+        config.inspect = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.inspect = None;
+
+        config.install_signal_handlers = Some(true);
+        assert_eq!(
+            config.to_pyconfig_flags(),
+            PYCONFIG_FLAG_INSTALL_SIGNAL_HANDLERS
+        );
+        config.install_signal_handlers = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.install_signal_handlers = None;
+
+        config.interactive = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_INTERACTIVE);
+        config.interactive = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.interactive = None;
+
+        config.isolated = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_ISOLATED);
+        config.isolated = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.isolated = None;
+
+        config.legacy_windows_stdio = Some(true);
+        assert_eq!(
+            config.to_pyconfig_flags(),
+            PYCONFIG_FLAG_LEGACY_WINDOWS_STDIO
+        );
+        config.legacy_windows_stdio = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.legacy_windows_stdio = None;
+
+        config.malloc_stats = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_MALLOC_STATS);
+        config.malloc_stats = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.malloc_stats = None;
+
+        config.parse_argv = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_PARSE_ARGV);
+        config.parse_argv = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.parse_argv = None;
+
+        config.parser_debug = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_PARSER_DEBUG);
+        config.parser_debug = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.parser_debug = None;
+
+        config.pathconfig_warnings = Some(true);
+        assert_eq!(
+            config.to_pyconfig_flags(),
+            PYCONFIG_FLAG_PATHCONFIG_WARNINGS
+        );
+        config.pathconfig_warnings = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.pathconfig_warnings = None;
+
+        config.quiet = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_QUIET);
+        config.quiet = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.quiet = None;
+
+        config.show_ref_count = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_SHOW_REF_COUNT);
+        config.show_ref_count = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.show_ref_count = None;
+
+        config.site_import = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_SITE_IMPORT);
+        config.site_import = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.site_import = None;
+
+        config.skip_first_source_line = Some(true);
+        assert_eq!(
+            config.to_pyconfig_flags(),
+            PYCONFIG_FLAG_SKIP_SOURCE_FIRST_LINE
+        );
+        config.skip_first_source_line = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.skip_first_source_line = None;
+
+        config.tracemalloc = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_TRACEMALLOC);
+        config.tracemalloc = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.tracemalloc = None;
+
+        config.use_environment = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_USE_ENVIRONMENT);
+        config.use_environment = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.use_environment = None;
+
+        config.user_site_directory = Some(true);
+        assert_eq!(
+            config.to_pyconfig_flags(),
+            PYCONFIG_FLAG_USER_SITE_DIRECTORY
+        );
+        config.user_site_directory = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.user_site_directory = None;
+
+        config.verbose = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_VERBOSE);
+        config.verbose = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.verbose = None;
+
+        config.write_bytecode = Some(true);
+        assert_eq!(config.to_pyconfig_flags(), PYCONFIG_FLAG_WRITE_BYTECODE);
+        config.write_bytecode = Some(false);
+        assert_eq!(config.to_pyconfig_flags(), 0);
+        config.write_bytecode = None;
+
+        let all = (1 << 24) - 1;
+        assert_eq!(
+            all_fields_set().to_pyconfig_flags(),
+            all & !(PYCONFIG_FLAG_BUFFERED_STDIO
+                | PYCONFIG_FLAG_ISOLATED
+                | PYCONFIG_FLAG_SITE_IMPORT
+                | PYCONFIG_FLAG_USER_SITE_DIRECTORY
+                | PYCONFIG_FLAG_WRITE_BYTECODE)
+        );
+    }
+
+    fn hash_value<T: Hash>(value: &T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(
+            hash_value(&PythonInterpreterConfig::default()),
+            hash_value(&PythonInterpreterConfig::default())
+        );
+        assert_eq!(hash_value(&all_fields_set()), hash_value(&all_fields_set()));
+        assert_ne!(
+            hash_value(&PythonInterpreterConfig::default()),
+            hash_value(&all_fields_set())
+        );
+
+        // Moving a value between fields of the same type changes the hash.
+        let a = PythonInterpreterConfig {
+            home: Some(PathBuf::from("/path")),
+            ..Default::default()
+        };
+        let b = PythonInterpreterConfig {
+            prefix: Some(PathBuf::from("/path")),
+            ..Default::default()
+        };
+        assert_ne!(hash_value(&a), hash_value(&b));
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(all_fields_set(), "all");
+        map.insert(PythonInterpreterConfig::default(), "default");
+        assert_eq!(map.get(&all_fields_set()), Some(&"all"));
+        assert_eq!(
+            map.get(&PythonInterpreterConfig::default()),
+            Some(&"default")
+        );
+    }
+
+    #[test]
+    fn test_multiprocessing_start_method_detect() {
+        let linux = OsInfo {
+            os: "linux".to_string(),
+            has_fork: true,
+        };
+        let macos = OsInfo {
+            os: "macos".to_string(),
+            has_fork: true,
+        };
+        let windows = OsInfo {
+            os: "windows".to_string(),
+            has_fork: false,
+        };
+
+        assert_eq!(
+            MultiprocessingStartMethod::detect(&linux),
+            MultiprocessingStartMethod::Fork
+        );
+        assert_eq!(
+            MultiprocessingStartMethod::detect(&macos),
+            MultiprocessingStartMethod::Fork
+        );
+        assert_eq!(
+            MultiprocessingStartMethod::detect(&windows),
+            MultiprocessingStartMethod::Spawn
+        );
+
+        let current = OsInfo::current();
+        assert_eq!(current.os, std::env::consts::OS);
+        assert_eq!(
+            MultiprocessingStartMethod::detect(&current),
+            if cfg!(windows) {
+                MultiprocessingStartMethod::Spawn
+            } else {
+                MultiprocessingStartMethod::Fork
+            }
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        for (value, expected) in [
+            (PythonInterpreterProfile::Isolated, "isolated"),
+            (PythonInterpreterProfile::Python, "python"),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+
+        for (value, expected) in [
+            (TerminfoResolution::Dynamic, "dynamic"),
+            (TerminfoResolution::None, "none"),
+            (
+                TerminfoResolution::Static("/usr/share/terminfo".into()),
+                "static:/usr/share/terminfo",
+            ),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+
+        for (value, expected) in [
+            (MemoryAllocatorBackend::Default, "default"),
+            (MemoryAllocatorBackend::Jemalloc, "jemalloc"),
+            (MemoryAllocatorBackend::Mimalloc, "mimalloc"),
+            (MemoryAllocatorBackend::Snmalloc, "snmalloc"),
+            (MemoryAllocatorBackend::Tcmalloc, "tcmalloc"),
+            (MemoryAllocatorBackend::Rust, "rust"),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+
+        for (value, expected) in [
+            (CoerceCLocale::LCCtype, "LC_CTYPE"),
+            (CoerceCLocale::C, "C"),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+
+        for (value, expected) in [
+            (BytesWarning::None, "none"),
+            (BytesWarning::Warn, "warn"),
+            (BytesWarning::Raise, "raise"),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+
+        for (value, expected) in [
+            (CheckHashPycsMode::Always, "always"),
+            (CheckHashPycsMode::Never, "never"),
+            (CheckHashPycsMode::Default, "default"),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+
+        for (value, expected) in [
+            (Allocator::NotSet, "not-set"),
+            (Allocator::Default, "default"),
+            (Allocator::Debug, "debug"),
+            (Allocator::Malloc, "malloc"),
+            (Allocator::MallocDebug, "malloc-debug"),
+            (Allocator::PyMalloc, "py-malloc"),
+            (Allocator::PyMallocDebug, "py-malloc-debug"),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+
+        for (value, expected) in [
+            (MultiprocessingStartMethod::None, "none"),
+            (MultiprocessingStartMethod::Fork, "fork"),
+            (MultiprocessingStartMethod::ForkServer, "forkserver"),
+            (MultiprocessingStartMethod::Spawn, "spawn"),
+            (MultiprocessingStartMethod::Auto, "auto"),
+        ] {
+            assert_eq!(format!("{}", value), expected);
+            assert_eq!(String::from(value), expected);
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "python".parse::<PythonInterpreterProfile>(),
+            Ok(PythonInterpreterProfile::Python)
+        );
+        assert_eq!(
+            "static:/usr/share/terminfo".parse::<TerminfoResolution>(),
+            Ok(TerminfoResolution::Static("/usr/share/terminfo".into()))
+        );
+        assert_eq!(
+            "mimalloc".parse::<MemoryAllocatorBackend>(),
+            Ok(MemoryAllocatorBackend::Mimalloc)
+        );
+        assert_eq!(
+            "LC_CTYPE".parse::<CoerceCLocale>(),
+            Ok(CoerceCLocale::LCCtype)
+        );
+        assert_eq!("raise".parse::<BytesWarning>(), Ok(BytesWarning::Raise));
+        assert_eq!(
+            "never".parse::<CheckHashPycsMode>(),
+            Ok(CheckHashPycsMode::Never)
+        );
+        assert_eq!("py-malloc".parse::<Allocator>(), Ok(Allocator::PyMalloc));
+
+        assert_eq!("foo".parse::<Allocator>(), Allocator::try_from("foo"));
+        assert!("foo".parse::<PythonInterpreterProfile>().is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let config = all_fields_set();
+        assert!(config.diff(&config).is_empty());
+        assert!(PythonInterpreterConfig::default()
+            .diff(&PythonInterpreterConfig::default())
+            .is_empty());
+
+        let mut other = config.clone();
+        other.optimization_level = Some(BytecodeOptimizationLevel::Two);
+        other.home = None;
+
+        let diffs = config.diff(&other);
+        assert_eq!(
+            diffs,
+            vec![
+                ConfigDiff::Home {
+                    before: Some(PathBuf::from("/home")),
+                    after: None,
+                },
+                ConfigDiff::OptimizationLevel {
+                    before: Some(BytecodeOptimizationLevel::One),
+                    after: Some(BytecodeOptimizationLevel::Two),
+                },
+            ]
+        );
+        assert_eq!(
+            diffs.iter().map(|d| d.field()).collect::<Vec<_>>(),
+            vec!["home", "optimization_level"]
+        );
+
+        // Every field differs from the default.
+        let diffs = PythonInterpreterConfig::default().diff(&config);
+        assert_eq!(diffs.len(), 54);
+        assert_eq!(diffs[0].field(), "profile");
+    }
+
+    #[test]
+    fn test_apply_overrides() -> Result<(), String> {
+        let config = PythonInterpreterConfig::default();
+
+        assert_eq!(config.apply_overrides(Vec::<(&str, &str)>::new())?, config);
+
+        let config = config.apply_overrides([
+            ("PYOXIDIZER_CONFIG_ALLOCATOR", "malloc"),
+            ("PYOXIDIZER_CONFIG_OPTIMIZATION_LEVEL", "2"),
+            ("PYOXIDIZER_CONFIG_PROFILE", "python"),
+            ("PYOXIDIZER_CONFIG_ISOLATED", "false"),
+            ("PYOXIDIZER_CONFIG_HASH_SEED", "42"),
+            ("PYOXIDIZER_CONFIG_HOME", "/opt/python"),
+            ("PYOXIDIZER_CONFIG_X_OPTIONS", "dev utf8"),
+            ("PYOXIDIZER_CONFIG_DOES_NOT_EXIST", "ignored"),
+            ("PYOXIDIZER_CONFIG_QUIET", ""),
+            ("PYOXIDIZER_CACHE_DIR", "/ignored"),
+            ("PYOXIDIZER_DISTUTILS_STATE_DIR", "/ignored"),
+            ("PYTHONHOME", "/ignored"),
+        ])?;
+
+        assert_eq!(
+            config,
+            PythonInterpreterConfig {
+                profile: PythonInterpreterProfile::Python,
+                allocator: Some(Allocator::Malloc),
+                isolated: Some(false),
+                hash_seed: Some(42),
+                home: Some(PathBuf::from("/opt/python")),
+                optimization_level: Some(BytecodeOptimizationLevel::Two),
+                x_options: Some(vec!["dev".to_string(), "utf8".to_string()]),
+                ..Default::default()
+            }
+        );
+
+        // Existing values are retained unless overridden.
+        let config = config.apply_overrides([("PYOXIDIZER_CONFIG_UTF8_MODE", "1")])?;
+        assert_eq!(config.allocator, Some(Allocator::Malloc));
+        assert_eq!(config.utf8_mode, Some(true));
+
+        // The memory allocator backend isn't part of this type, so backend
+        // names aren't mistaken for `allocator` values.
+        assert_eq!(
+            config.apply_overrides([("PYOXIDIZER_CONFIG_MEMORY_ALLOCATOR", "jemalloc")])?,
+            config
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_overrides_invalid() {
+        let config = PythonInterpreterConfig::default();
+
+        assert_eq!(
+            config.apply_overrides([("PYOXIDIZER_CONFIG_ALLOCATOR", "bad")]),
+            Err("PYOXIDIZER_CONFIG_ALLOCATOR: bad is not a valid allocator value; use 'not-set', 'default', 'debug', 'malloc', 'malloc-debug', 'py-malloc', or 'py-malloc-debug'".to_string())
+        );
+        assert_eq!(
+            config.apply_overrides([("PYOXIDIZER_CONFIG_ISOLATED", "maybe")]),
+            Err("PYOXIDIZER_CONFIG_ISOLATED: maybe is not a valid boolean value".to_string())
+        );
+        assert_eq!(
+            config.apply_overrides([("PYOXIDIZER_CONFIG_OPTIMIZATION_LEVEL", "3")]),
+            Err(
+                "PYOXIDIZER_CONFIG_OPTIMIZATION_LEVEL: unsupported bytecode optimization level"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_apply_env_overrides() {
+        // The process environment is shared with other tests, so only
+        // compare against the explicit form rather than mutating it.
+        let config = all_fields_set();
+
+        assert_eq!(
+            config.apply_env_overrides(),
+            config.apply_overrides(std::env::vars_os())
+        );
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let err = ParseError {
+            type_name: "profile",
+            bad_value: "bogus".to_string(),
+            valid_values: PythonInterpreterProfile::VALID_VALUES,
+        };
+        assert_eq!(
+            err.to_string(),
+            "bogus is not a valid profile value; use 'isolated' or 'python'"
+        );
+
+        assert_eq!(
+            BytesWarning::try_from("bogus").unwrap_err().to_string(),
+            "bogus is not a valid bytes warning value; use 'none', 'warn', or 'raise'"
+        );
+
+        let err = ParseError {
+            valid_values: &["1"],
+            ..err
+        };
+        assert_eq!(
+            err.to_string(),
+            "bogus is not a valid profile value; use '1'"
+        );
+
+        let err = ParseError {
+            valid_values: &[],
+            ..err
+        };
+        assert_eq!(err.to_string(), "bogus is not a valid profile value");
+    }
+
+    #[test]
+    fn test_valid_values() -> Result<(), String> {
+        for value in PythonInterpreterProfile::VALID_VALUES {
+            assert_eq!(
+                PythonInterpreterProfile::try_from(*value)?.to_string(),
+                *value
+            );
+        }
+        for value in TerminfoResolution::VALID_VALUES {
+            assert_eq!(TerminfoResolution::try_from(*value)?.to_string(), *value);
+        }
+        for value in MemoryAllocatorBackend::VALID_VALUES {
+            assert_eq!(
+                MemoryAllocatorBackend::try_from(*value)?.to_string(),
+                *value
+            );
+        }
+        for value in CoerceCLocale::VALID_VALUES {
+            assert_eq!(CoerceCLocale::try_from(*value)?.to_string(), *value);
+        }
+        for value in BytesWarning::VALID_VALUES {
+            assert_eq!(BytesWarning::try_from(*value)?.to_string(), *value);
+        }
+        for value in CheckHashPycsMode::VALID_VALUES {
+            assert_eq!(CheckHashPycsMode::try_from(*value)?.to_string(), *value);
+        }
+        for value in Allocator::VALID_VALUES {
+            assert_eq!(Allocator::try_from(*value)?.to_string(), *value);
+        }
+        for value in MultiprocessingStartMethod::VALID_VALUES {
+            assert_eq!(
+                MultiprocessingStartMethod::try_from(*value)?.to_string(),
+                *value
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_deserialize_error_message() {
+        let err = PythonInterpreterConfig::from_toml_str("allocator = \"bad-value\"\n")
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(
+            err,
+            "field 'allocator': bad-value is not a valid allocator value; use 'not-set', \
+             'default', 'debug', 'malloc', 'malloc-debug', 'py-malloc', or 'py-malloc-debug' \
+             for key `allocator` at line 1 column 1"
+        );
+
+        let err = PythonInterpreterConfig::from_toml_str("profile = \"bad\"\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "field 'profile': bad is not a valid profile value; use 'isolated' or 'python' \
+             for key `profile` at line 1 column 1"
+        );
+    }
+
+    #[test]
+    fn test_all_known() {
+        assert_eq!(
+            PythonInterpreterProfile::all_known().len(),
+            PythonInterpreterProfile::VALID_VALUES.len()
+        );
+        assert_eq!(
+            MemoryAllocatorBackend::all_known().len(),
+            MemoryAllocatorBackend::VALID_VALUES.len()
+        );
+        assert_eq!(
+            CoerceCLocale::all_known().len(),
+            CoerceCLocale::VALID_VALUES.len()
+        );
+        assert_eq!(
+            BytesWarning::all_known().len(),
+            BytesWarning::VALID_VALUES.len()
+        );
+        assert_eq!(
+            CheckHashPycsMode::all_known().len(),
+            CheckHashPycsMode::VALID_VALUES.len()
+        );
+        assert_eq!(Allocator::all_known().len(), Allocator::VALID_VALUES.len());
+        assert_eq!(
+            MultiprocessingStartMethod::all_known().len(),
+            MultiprocessingStartMethod::VALID_VALUES.len()
+        );
+
+        assert!(PythonInterpreterProfile::all_known()
+            .iter()
+            .all(|v| v.is_known()));
+        assert!(MemoryAllocatorBackend::all_known()
+            .iter()
+            .all(|v| v.is_known()));
+        assert!(CoerceCLocale::all_known().iter().all(|v| v.is_known()));
+        assert!(BytesWarning::all_known().iter().all(|v| v.is_known()));
+        assert!(CheckHashPycsMode::all_known().iter().all(|v| v.is_known()));
+        assert!(Allocator::all_known().iter().all(|v| v.is_known()));
+        assert!(MultiprocessingStartMethod::all_known()
+            .iter()
+            .all(|v| v.is_known()));
+        assert!(TerminfoResolution::Static("/usr/share/terminfo".into()).is_known());
+    }
+
+    #[test]
+    fn test_with_run() {
+        let config = all_fields_set();
+        assert_eq!(config.run_filename, Some(PathBuf::from("/script.py")));
+
+        let c = config.with_run_module("http.server");
+        assert_eq!(c.run_module, Some("http.server".to_string()));
+        assert_eq!(c.run_filename, None);
+        assert_eq!(c.run_command, None);
+        assert_eq!(c.home, config.home);
+
+        let c = c.with_run_command("print('hello')");
+        assert_eq!(c.run_command, Some("print('hello')".to_string()));
+        assert_eq!(c.run_module, None);
+        assert_eq!(c.run_filename, None);
+
+        let c = c.with_run_script("/app/main.py");
+        assert_eq!(c.run_filename, Some(PathBuf::from("/app/main.py")));
+        assert_eq!(c.run_command, None);
+        assert_eq!(c.run_module, None);
+
+        let c = PythonInterpreterConfig::default()
+            .with_argv(["prog", "--verbose"])
+            .with_optimization(BytecodeOptimizationLevel::Two);
+        assert_eq!(c.argv, Some(vec!["prog".into(), "--verbose".into()]));
+        assert_eq!(c.optimization_level, Some(BytecodeOptimizationLevel::Two));
+    }
+
+    #[test]
+    fn test_allocator_is_debug_variant() {
+        let debug = Allocator::all_known()
+            .iter()
+            .filter(|a| a.is_debug_variant())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            debug,
+            vec![
+                &Allocator::Debug,
+                &Allocator::MallocDebug,
+                &Allocator::PyMallocDebug
+            ]
+        );
+    }
+
+    #[test]
+    fn test_memory_allocator_backend_requires_feature() {
+        assert_eq!(MemoryAllocatorBackend::Default.requires_feature(), None);
+        assert_eq!(MemoryAllocatorBackend::Rust.requires_feature(), None);
+        assert_eq!(
+            MemoryAllocatorBackend::Jemalloc.requires_feature(),
+            Some("allocator-jemalloc")
+        );
+        assert_eq!(
+            MemoryAllocatorBackend::Mimalloc.requires_feature(),
+            Some("allocator-mimalloc")
+        );
+        assert_eq!(
+            MemoryAllocatorBackend::Snmalloc.requires_feature(),
+            Some("allocator-snmalloc")
+        );
+        assert_eq!(
+            MemoryAllocatorBackend::Tcmalloc.requires_feature(),
+            Some("allocator-tcmalloc")
+        );
+    }
+
+    #[test]
+    fn test_from_str_case_insensitive() {
+        for value in ["ISOLATED", "Isolated", "isolated"] {
+            assert_eq!(
+                PythonInterpreterProfile::try_from(value),
+                Ok(PythonInterpreterProfile::Isolated)
+            );
+        }
+        assert_eq!(
+            TerminfoResolution::try_from("DYNAMIC"),
+            Ok(TerminfoResolution::Dynamic)
+        );
+        assert_eq!(
+            TerminfoResolution::try_from("Static:/Usr/Share/Terminfo"),
+            Ok(TerminfoResolution::Static("/Usr/Share/Terminfo".into()))
+        );
+        assert_eq!(
+            MemoryAllocatorBackend::try_from("JeMalloc"),
+            Ok(MemoryAllocatorBackend::Jemalloc)
+        );
+        assert_eq!(
+            CoerceCLocale::try_from("lc_ctype"),
+            Ok(CoerceCLocale::LCCtype)
+        );
+        assert_eq!(CoerceCLocale::try_from("c"), Ok(CoerceCLocale::C));
+        assert_eq!(BytesWarning::try_from("RAISE"), Ok(BytesWarning::Raise));
+        assert_eq!(
+            CheckHashPycsMode::try_from("Always"),
+            Ok(CheckHashPycsMode::Always)
+        );
+        assert_eq!(
+            Allocator::try_from("Py-Malloc-Debug"),
+            Ok(Allocator::PyMallocDebug)
+        );
+        assert_eq!(
+            "ForkServer".parse::<MultiprocessingStartMethod>(),
+            Ok(MultiprocessingStartMethod::ForkServer)
+        );
+
+        // Canonical string forms are unaffected.
+        assert_eq!(Allocator::try_from("MALLOC").unwrap().to_string(), "malloc");
+        assert_eq!(CoerceCLocale::try_from("c").unwrap().to_string(), "C");
+
+        // Errors report the value as given.
+        assert_eq!(
+            BytesWarning::try_from("BOGUS"),
+            Err(ParseError {
+                type_name: "bytes warning",
+                bad_value: "BOGUS".to_string(),
+                valid_values: BytesWarning::VALID_VALUES,
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_effectively_isolated() {
+        let values = [None, Some(false), Some(true)];
+
+        for profile in PythonInterpreterProfile::all_known() {
+            // Defaults CPython's PyConfig_InitIsolatedConfig() and
+            // PyConfig_InitPythonConfig() use for these fields.
+            let (default_isolated, default_use_environment, default_user_site_directory) =
+                match profile {
+                    PythonInterpreterProfile::Isolated => (true, false, false),
+                    PythonInterpreterProfile::Python => (false, true, true),
+                };
+
+            for isolated in values {
+                for use_environment in values {
+                    for site_import in values {
+                        for user_site_directory in values {
+                            let config = PythonInterpreterConfig {
+                                profile: *profile,
+                                isolated,
+                                use_environment,
+                                site_import,
+                                user_site_directory,
+                                ..Default::default()
+                            };
+
+                            let isolated = isolated.unwrap_or(default_isolated);
+                            let reads_environment =
+                                !isolated && use_environment.unwrap_or(default_use_environment);
+                            let adds_user_site = !isolated
+                                && site_import.unwrap_or(true)
+                                && user_site_directory.unwrap_or(default_user_site_directory);
+
+                            assert_eq!(
+                                config.is_effectively_isolated(),
+                                !reads_environment && !adds_user_site,
+                                "{:?}",
+                                config
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_effective_optimization_level() {
+        for profile in PythonInterpreterProfile::all_known() {
+            for development_mode in [None, Some(false), Some(true)] {
+                for optimization_level in [
+                    None,
+                    Some(BytecodeOptimizationLevel::Zero),
+                    Some(BytecodeOptimizationLevel::One),
+                    Some(BytecodeOptimizationLevel::Two),
+                ] {
+                    let config = PythonInterpreterConfig {
+                        profile: *profile,
+                        development_mode,
+                        optimization_level,
+                        ..Default::default()
+                    };
+
+                    assert_eq!(
+                        config.effective_optimization_level(),
+                        optimization_level.unwrap_or(BytecodeOptimizationLevel::Zero),
+                        "{:?}",
+                        config
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_round_trip() -> Result<(), String> {
+        let config = all_fields_set();
+        let map = config.to_map();
+        assert_eq!(map.get("profile"), Some(&"python".to_string()));
+        assert_eq!(map.get("optimization_level"), Some(&"1".to_string()));
+        assert_eq!(map.get("x_options"), Some(&"dev utf8=1".to_string()));
+        assert!(!map.contains_key("run_command"));
+
+        let (parsed, warnings) = PythonInterpreterConfig::from_map(&map)?;
+        assert!(warnings.is_empty());
+        assert_eq!(parsed, config);
+        assert_eq!(PythonInterpreterConfig::try_from(&map)?, config);
+
+        let config = PythonInterpreterConfig::for_testing();
+        let (parsed, warnings) = PythonInterpreterConfig::from_map(&config.to_map())?;
+        assert!(warnings.is_empty());
+        assert_eq!(parsed, config);
+
+        assert_eq!(
+            PythonInterpreterConfig::default().to_map().len(),
+            1,
+            "only profile is emitted for default instance"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_map_unknown_and_invalid() -> Result<(), String> {
+        let mut map = HashMap::new();
+        map.insert("isolated".to_string(), "true".to_string());
+        map.insert("some_future_field".to_string(), "1".to_string());
+
+        let (config, warnings) = PythonInterpreterConfig::from_map(&map)?;
+        assert_eq!(config.isolated, Some(true));
+        assert_eq!(
+            warnings,
+            vec!["unknown configuration key: some_future_field".to_string()]
+        );
+
+        map.insert("allocator".to_string(), "bogus".to_string());
+        let err = PythonInterpreterConfig::from_map(&map).unwrap_err();
+        assert!(err.starts_with("allocator: "), "{}", err);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiprocessing_start_method_fork_based() {
+        assert!(!MultiprocessingStartMethod::None.is_fork_based());
+        assert!(MultiprocessingStartMethod::Fork.is_fork_based());
+        assert!(MultiprocessingStartMethod::ForkServer.is_fork_based());
+        assert!(!MultiprocessingStartMethod::Spawn.is_fork_based());
+        assert!(!MultiprocessingStartMethod::Auto.is_fork_based());
+
+        let macos = OsInfo {
+            os: "macos".to_string(),
+            has_fork: true,
+        };
+        let windows = OsInfo {
+            os: "windows".to_string(),
+            has_fork: false,
+        };
+        assert_eq!(
+            MultiprocessingStartMethod::Auto.resolve(&macos),
+            MultiprocessingStartMethod::Fork
+        );
+        assert_eq!(
+            MultiprocessingStartMethod::Auto.resolve(&windows),
+            MultiprocessingStartMethod::Spawn
+        );
+        for method in MultiprocessingStartMethod::all_known() {
+            if *method != MultiprocessingStartMethod::Auto {
+                assert_eq!(method.resolve(&macos), *method);
+            }
+        }
+
+        for method in MultiprocessingStartMethod::all_known() {
+            assert_eq!(
+                method.is_safe_on_current_platform(),
+                !(cfg!(target_os = "macos")
+                    && matches!(
+                        method,
+                        MultiprocessingStartMethod::Fork
+                            | MultiprocessingStartMethod::ForkServer
+                            | MultiprocessingStartMethod::Auto
+                    ))
+            );
+        }
+        assert!(MultiprocessingStartMethod::Spawn.is_safe_on_current_platform());
+    }
+
+    #[test]
+    fn test_estimated_startup_overhead() {
+        let profile = PythonInterpreterConfig::default().estimated_startup_overhead();
+        assert_eq!(
+            profile,
+            StartupProfile {
+                baseline: true,
+                debug_overhead: false,
+                trace_overhead: false,
+                expected_slowdown_factor: 1.0,
+            }
+        );
+
+        // Explicitly disabled features don't count.
+        let config = PythonInterpreterConfig {
+            tracemalloc: Some(false),
+            development_mode: Some(false),
+            allocator: Some(Allocator::Malloc),
+            ..Default::default()
+        };
+        let profile = config.estimated_startup_overhead();
+        assert!(profile.baseline);
+        assert_eq!(profile.expected_slowdown_factor, 1.0);
+
+        let config = PythonInterpreterConfig {
+            tracemalloc: Some(true),
+            ..Default::default()
+        };
+        let profile = config.estimated_startup_overhead();
+        assert!(!profile.baseline);
+        assert!(!profile.debug_overhead);
+        assert!(profile.trace_overhead);
+        assert_eq!(profile.expected_slowdown_factor, 2.0);
+
+        let config = PythonInterpreterConfig {
+            development_mode: Some(true),
+            allocator: Some(Allocator::PyMallocDebug),
+            ..Default::default()
+        };
+        let profile = config.estimated_startup_overhead();
+        assert!(profile.debug_overhead);
+        assert!(!profile.trace_overhead);
+        assert_eq!(profile.expected_slowdown_factor, 1.5);
+
+        let config = PythonInterpreterConfig {
+            import_time: Some(true),
+            tracemalloc: Some(true),
+            development_mode: Some(true),
+            ..Default::default()
+        };
+        let profile = config.estimated_startup_overhead();
+        assert!(profile.debug_overhead);
+        assert!(profile.trace_overhead);
+        assert_eq!(profile.expected_slowdown_factor, 3.0);
+
+        // Each setting alone lands in its tier.
+        let debug_configs = [
+            PythonInterpreterConfig {
+                development_mode: Some(true),
+                ..Default::default()
+            },
+            PythonInterpreterConfig {
+                allocator: Some(Allocator::Debug),
+                ..Default::default()
+            },
+            PythonInterpreterConfig {
+                verbose: Some(true),
+                ..Default::default()
+            },
+            PythonInterpreterConfig {
+                parser_debug: Some(true),
+                ..Default::default()
+            },
+            PythonInterpreterConfig {
+                show_ref_count: Some(true),
+                ..Default::default()
+            },
+            PythonInterpreterConfig {
+                dump_refs: Some(true),
+                ..Default::default()
+            },
+            PythonInterpreterConfig {
+                malloc_stats: Some(true),
+                ..Default::default()
+            },
+        ];
+        for config in debug_configs {
+            assert_eq!(
+                config.estimated_startup_overhead().expected_slowdown_factor,
+                1.5,
+                "{:?}",
+                config
+            );
+        }
+
+        let config = PythonInterpreterConfig {
+            import_time: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.estimated_startup_overhead().expected_slowdown_factor,
+            2.0
+        );
+    }
+
+    #[test]
+    fn test_i32_conversions() {
+        for (value, expected) in [
+            (BytesWarning::None, 0),
+            (BytesWarning::Warn, 1),
+            (BytesWarning::Raise, 2),
+        ] {
+            assert_eq!(i32::from(value), expected);
+            assert_eq!(BytesWarning::from(i32::from(value)), value);
+        }
+        assert_eq!(
+            BytesWarning::from(BytesWarning::Warn as i32),
+            BytesWarning::Warn
+        );
+
+        for (value, expected) in [
+            (Allocator::NotSet, 0),
+            (Allocator::Default, 1),
+            (Allocator::Debug, 2),
+            (Allocator::Malloc, 3),
+            (Allocator::MallocDebug, 4),
+            (Allocator::PyMalloc, 5),
+            (Allocator::PyMallocDebug, 6),
+        ] {
+            assert_eq!(i32::from(value), expected);
+        }
+    }
+
+    #[test]
+    fn test_coerce_c_locale_int() {
+        for (value, expected) in [(CoerceCLocale::LCCtype, 1), (CoerceCLocale::C, 2)] {
+            assert_eq!(value.as_int(), expected);
+            assert_eq!(i32::from(value), expected);
+            assert_eq!(CoerceCLocale::try_from(expected), Ok(value));
+        }
+
+        assert!(CoerceCLocale::try_from(0).is_err());
+        assert!(CoerceCLocale::try_from(3).is_err());
+    }
+
+    #[test]
+    fn test_terminfo_resolution_resolve_dirs() {
+        assert_eq!(TerminfoResolution::None.resolve_dirs(), None);
+        assert_eq!(
+            TerminfoResolution::Static("/foo:/bar".into()).resolve_dirs(),
+            Some("/foo:/bar".to_string())
+        );
+
+        // This is the only test touching TERMINFO_DIRS, so mutating the
+        // environment doesn't race with other tests.
+        let original = std::env::var_os("TERMINFO_DIRS");
+
+        std::env::remove_var("TERMINFO_DIRS");
+        assert_eq!(TerminfoResolution::from_env(), None);
+        let dynamic = TerminfoResolution::Dynamic.resolve_dirs();
+        if cfg!(target_os = "macos") {
+            assert_eq!(dynamic, Some("/usr/share/terminfo".to_string()));
+        } else if cfg!(target_os = "windows") {
+            assert_eq!(dynamic, None);
+        } else if cfg!(target_os = "linux") {
+            assert!(dynamic.is_some());
+        }
+
+        std::env::set_var("TERMINFO_DIRS", "/custom/terminfo");
+        assert_eq!(
+            TerminfoResolution::from_env(),
+            Some(TerminfoResolution::Static("/custom/terminfo".into()))
+        );
+        assert_eq!(TerminfoResolution::Dynamic.resolve_dirs(), None);
+
+        match original {
+            Some(value) => std::env::set_var("TERMINFO_DIRS", value),
+            None => std::env::remove_var("TERMINFO_DIRS"),
+        }
+    }
+
+    #[test]
+    fn test_for_profile() -> Result<(), String> {
+        let config = PythonInterpreterConfig::for_profile(PythonInterpreterProfile::Python);
+        assert_eq!(config.profile, PythonInterpreterProfile::Python);
+        assert_eq!(config.isolated, Some(false));
+        assert_eq!(config.use_environment, Some(true));
+        assert_eq!(config.site_import, Some(true));
+        assert_eq!(config.user_site_directory, Some(true));
+        assert!(!config.is_effectively_isolated());
+        config.validate().map_err(|e| e.join(", "))?;
+
+        let config = PythonInterpreterConfig::for_profile(PythonInterpreterProfile::Isolated);
+        assert_eq!(config.profile, PythonInterpreterProfile::Isolated);
+        assert_eq!(config.isolated, Some(true));
+        assert_eq!(config.use_environment, Some(false));
+        assert_eq!(config.site_import, Some(true));
+        assert_eq!(config.user_site_directory, Some(false));
+        assert!(config.is_effectively_isolated());
+        config.validate().map_err(|e| e.join(", "))?;
+
+        assert_eq!(
+            PythonInterpreterConfigBuilder::with_profile(PythonInterpreterProfile::Python)
+                .build()?,
+            PythonInterpreterConfig::for_profile(PythonInterpreterProfile::Python)
+        );
+
+        Ok(())
+    }
+
+    #[cfg(all(unix, feature = "serialization"))]
+    #[test]
+    fn test_argv_serialization_non_utf8() -> Result<(), serde_json::Error> {
+        use std::os::unix::ffi::OsStringExt;
+
+        let config = PythonInterpreterConfig {
+            argv: Some(vec![
+                OsString::from("prog"),
+                OsString::from_vec(vec![0x66, 0x6f, 0xff, 0x6f]),
+            ]),
+            ..Default::default()
+        };
+
+        let value = serde_json::to_value(&config)?;
+        assert_eq!(
+            value["argv"],
+            serde_json::json!(["prog", {"bytes": [0x66, 0x6f, 0xff, 0x6f]}])
+        );
+        assert_eq!(
+            serde_json::from_value::<PythonInterpreterConfig>(value)?,
+            config
+        );
+
+        let config = serde_json::from_str::<PythonInterpreterConfig>(
+            r#"{"argv": ["prog", {"utf8": "a"}, {"bytes": [98]}]}"#,
+        )?;
+        assert_eq!(
+            config.argv,
+            Some(vec!["prog".into(), "a".into(), "b".into()])
+        );
+
+        Ok(())
+    }
+
+    #[cfg(all(unix, feature = "toml"))]
+    #[test]
+    fn test_toml_argv_non_utf8() -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::ffi::OsStringExt;
+
+        let config = PythonInterpreterConfig {
+            argv: Some(vec![OsString::from_vec(vec![0xfe, 0xff])]),
+            home: Some(PathBuf::from("/home")),
+            ..Default::default()
+        };
+
+        let s = config.to_toml_string()?;
+        assert!(s.contains("bytes = [254, 255]"), "{}", s);
+        assert_eq!(PythonInterpreterConfig::from_toml_str(&s)?, config);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_security_audit() {
+        assert!(PythonInterpreterConfig::default()
+            .security_audit()
+            .is_empty());
+
+        let config = PythonInterpreterConfig {
+            use_environment: Some(true),
+            install_signal_handlers: Some(false),
+            hash_seed: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.security_audit(),
+            vec![
+                SecurityWarning::EnvironmentInIsolatedProfile,
+                SecurityWarning::SignalHandlersDisabled,
+                SecurityWarning::HashRandomizationDisabled,
+            ]
+        );
+
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            use_environment: Some(true),
+            install_signal_handlers: Some(true),
+            hash_seed: Some(42),
+            ..Default::default()
+        };
+        assert!(config.security_audit().is_empty());
+    }
+
+    #[test]
+    fn test_to_env_vars() -> Result<(), String> {
+        let config = PythonInterpreterConfig::default();
+        assert_eq!(
+            config.to_env_vars(),
+            vec![(
+                "PYOXIDIZER_CONFIG_PROFILE".to_string(),
+                "isolated".to_string()
+            )]
+        );
+
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            use_environment: Some(true),
+            allocator: Some(Allocator::Malloc),
+            coerce_c_locale: Some(CoerceCLocale::LCCtype),
+            home: Some(PathBuf::from("/opt/python")),
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            utf8_mode: Some(false),
+            verbose: Some(true),
+            inspect: Some(false),
+            write_bytecode: Some(false),
+            warn_options: Some(vec![
+                "ignore".to_string(),
+                "error::DeprecationWarning".to_string(),
+            ]),
+            ..Default::default()
+        };
+
+        let vars = config.to_env_vars();
+        assert_eq!(
+            vars,
+            [
+                ("PYOXIDIZER_CONFIG_INSPECT", "false"),
+                ("PYOXIDIZER_CONFIG_PROFILE", "python"),
+                ("PYOXIDIZER_CONFIG_USE_ENVIRONMENT", "true"),
+                ("PYTHONCOERCECLOCALE", "1"),
+                ("PYTHONDONTWRITEBYTECODE", "1"),
+                ("PYTHONHOME", "/opt/python"),
+                ("PYTHONMALLOC", "malloc"),
+                ("PYTHONOPTIMIZE", "2"),
+                ("PYTHONUTF8", "0"),
+                ("PYTHONVERBOSE", "1"),
+                ("PYTHONWARNINGS", "ignore,error::DeprecationWarning"),
+            ]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            PythonInterpreterConfig::default().apply_overrides(vars)?,
+            config
+        );
+
+        // Configurations not using the environment only emit our variables.
+        for config in [all_fields_set(), {
+            let mut config = all_fields_set();
+            config.isolated = Some(true);
+            config
+        }] {
+            let vars = config.to_env_vars();
+            if config.isolated == Some(true) {
+                assert!(vars.iter().all(|(k, _)| k.starts_with(ENV_OVERRIDE_PREFIX)));
+            }
+            assert_eq!(
+                PythonInterpreterConfig::default().apply_overrides(vars)?,
+                config
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_overrides_python_env_vars() -> Result<(), String> {
+        let vars = [
+            ("PYTHONHASHSEED", "random"),
+            ("PYTHONNOUSERSITE", "1"),
+            ("PYTHONPATH", "/a:/b"),
+            ("PYTHONTRACEMALLOC", "5"),
+        ];
+
+        // Ignored unless use_environment is explicitly enabled.
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            ..Default::default()
+        }
+        .apply_overrides(vars)?;
+        assert_eq!(config.python_path_env, None);
+
+        let config = PythonInterpreterConfig::default().apply_overrides(
+            vars.iter()
+                .copied()
+                .chain([("PYOXIDIZER_CONFIG_USE_ENVIRONMENT", "1")]),
+        )?;
+        assert_eq!(config.python_path_env, None, "isolated profile wins");
+
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            use_environment: Some(true),
+            ..Default::default()
+        }
+        .apply_overrides(vars)?;
+        assert_eq!(config.hash_seed, None);
+        assert_eq!(config.user_site_directory, Some(false));
+        assert_eq!(config.python_path_env, Some("/a:/b".to_string()));
+        assert_eq!(config.tracemalloc, Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_without_debug_fields() {
+        let config = PythonInterpreterConfig {
+            dump_refs: Some(true),
+            import_time: Some(true),
+            malloc_stats: Some(true),
+            parser_debug: Some(true),
+            show_ref_count: Some(true),
+            tracemalloc: Some(true),
+            home: Some(PathBuf::from("/home")),
+            ..Default::default()
+        };
+
+        let stripped = config.without_debug_fields();
+        assert_ne!(stripped, config);
+        assert_eq!(
+            stripped,
+            PythonInterpreterConfig {
+                home: Some(PathBuf::from("/home")),
+                ..Default::default()
+            }
+        );
+        assert_eq!(stripped.without_debug_fields(), stripped);
+
+        let config = all_fields_set().without_debug_fields();
+        assert_eq!(config.dump_refs, None);
+        assert_eq!(config.import_time, None);
+        assert_eq!(config.malloc_stats, None);
+        assert_eq!(config.parser_debug, None);
+        assert_eq!(config.show_ref_count, None);
+        assert_eq!(config.tracemalloc, None);
+        assert_eq!(config.home, all_fields_set().home);
+    }
+
+    #[test]
+    fn test_check_hash_pycs_mode_flags() {
+        for (mode, expected) in [
+            (CheckHashPycsMode::Always, "always"),
+            (CheckHashPycsMode::Never, "never"),
+            (CheckHashPycsMode::Default, "default"),
+        ] {
+            assert_eq!(mode.to_pyc_flag(), expected);
+            assert_eq!(mode.to_pyc_flag(), mode.to_string());
+        }
+    }
+
+    #[test]
+    fn test_check_hash_pycs_mode_default() {
+        assert_eq!(CheckHashPycsMode::default(), CheckHashPycsMode::Default);
+
+        assert!(CheckHashPycsMode::Always.is_strict());
+        assert!(!CheckHashPycsMode::Never.is_strict());
+        assert!(!CheckHashPycsMode::Default.is_strict());
+    }
+
+    #[test]
+    fn test_normalize_module_search_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let td = tempfile::Builder::new()
+            .prefix("python-packaging-test")
+            .tempdir()?;
+        let base = td.path();
+        std::fs::create_dir(base.join("lib"))?;
+        std::fs::create_dir(base.join("site-packages"))?;
+
+        let config = PythonInterpreterConfig::default();
+        assert_eq!(config.normalize_module_search_paths(base)?, config);
+        assert!(!config.has_module_search_path(Path::new("lib")));
+
+        let config = PythonInterpreterConfig {
+            module_search_paths: Some(vec![
+                PathBuf::from("lib"),
+                base.join("site-packages"),
+                PathBuf::from("./lib"),
+                base.join("lib"),
+            ]),
+            ..Default::default()
+        };
+        assert!(config.has_module_search_path(Path::new("lib")));
+        assert!(!config.has_module_search_path(Path::new("other")));
+
+        let normalized = config.normalize_module_search_paths(base)?;
+        assert_eq!(
+            normalized.module_search_paths,
+            Some(vec![base.join("lib"), base.join("site-packages")])
+        );
+        assert!(normalized.has_module_search_path(&base.join("lib")));
+        assert!(!normalized.has_module_search_path(Path::new("lib")));
+
+        let config = PythonInterpreterConfig {
+            module_search_paths: Some(vec![
+                PathBuf::from("site-packages/../lib"),
+                base.join("lib"),
+                PathBuf::from("lib/../../")
+                    .join(base.file_name().unwrap())
+                    .join("lib"),
+                PathBuf::from("./site-packages/./"),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config
+                .normalize_module_search_paths(base)?
+                .module_search_paths,
+            Some(vec![base.join("lib"), base.join("site-packages")])
+        );
+
+        let config = PythonInterpreterConfig {
+            module_search_paths: Some(vec![PathBuf::from("lib"), PathBuf::from("missing")]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.normalize_module_search_paths(base),
+            Err(format!(
+                "module search path does not exist: {}",
+                base.join("missing").display()
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_allocator_is_compatible_with() {
+        for allocator in Allocator::all_known() {
+            for backend in MemoryAllocatorBackend::all_known() {
+                assert_eq!(
+                    allocator.is_compatible_with(backend),
+                    *backend == MemoryAllocatorBackend::Default || !allocator.is_debug_variant(),
+                    "{} with {}",
+                    allocator,
+                    backend
+                );
+            }
+        }
+
+        assert!(Allocator::PyMalloc.is_compatible_with(&MemoryAllocatorBackend::Jemalloc));
+        assert!(!Allocator::MallocDebug.is_compatible_with(&MemoryAllocatorBackend::Jemalloc));
+        assert!(Allocator::MallocDebug.is_compatible_with(&MemoryAllocatorBackend::Default));
+    }
+
+    #[test]
+    fn test_validate_with_allocator_backend() {
+        let config = PythonInterpreterConfig {
+            allocator: Some(Allocator::Debug),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate_with_allocator_backend(&MemoryAllocatorBackend::Default),
+            Ok(())
+        );
+        assert_eq!(
+            config.validate_with_allocator_backend(&MemoryAllocatorBackend::Rust),
+            Err(vec![
+                "allocator debug is not compatible with the rust memory allocator backend; its debug hooks would be removed".to_string()
+            ])
+        );
+
+        let config = PythonInterpreterConfig {
+            parse_argv: Some(true),
+            ..config
+        };
+        assert_eq!(
+            config
+                .validate_with_allocator_backend(&MemoryAllocatorBackend::Rust)
+                .unwrap_err()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_set_field_from_str() -> Result<(), String> {
+        let expected = PythonInterpreterConfig {
+            run_filename: None,
+            run_command: Some("print('hello')".to_string()),
+            ..all_fields_set()
+        };
+
+        let mut config = PythonInterpreterConfig::default();
+        for (field, value) in expected.to_map() {
+            config.set_field_from_str(&field, &value)?;
+        }
+        assert_eq!(config, expected);
+
+        assert_eq!(
+            config.set_field_from_str("bogus", "1"),
+            Err("bogus is not a known field".to_string())
+        );
+        assert_eq!(
+            config.set_field_from_str("isolated", "maybe"),
+            Err("maybe is not a valid boolean value".to_string())
+        );
+        assert_eq!(config, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_snapshot() {
+        let start = SystemTime::now();
+
+        let mut snapshot = ConfigSnapshot::new(PythonInterpreterConfig::default());
+        assert!(snapshot.changelog().is_empty());
+
+        snapshot.update(PythonInterpreterConfig::default());
+        assert!(snapshot.changelog().is_empty());
+
+        snapshot.update(PythonInterpreterConfig {
+            optimization_level: Some(BytecodeOptimizationLevel::One),
+            ..Default::default()
+        });
+        snapshot.update(PythonInterpreterConfig {
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            home: Some(PathBuf::from("/home")),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            snapshot.config().optimization_level,
+            Some(BytecodeOptimizationLevel::Two)
+        );
+        assert_eq!(
+            snapshot
+                .changelog()
+                .iter()
+                .map(|(_, diff)| diff.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                ConfigDiff::OptimizationLevel {
+                    before: None,
+                    after: Some(BytecodeOptimizationLevel::One),
+                },
+                ConfigDiff::Home {
+                    before: None,
+                    after: Some(PathBuf::from("/home")),
+                },
+                ConfigDiff::OptimizationLevel {
+                    before: Some(BytecodeOptimizationLevel::One),
+                    after: Some(BytecodeOptimizationLevel::Two),
+                },
+            ]
+        );
+        assert!(snapshot.changelog().iter().all(|(t, _)| *t >= start));
+        assert_eq!(snapshot.changelog()[1].0, snapshot.changelog()[2].0);
+    }
+
+    #[cfg(feature = "serialization")]
+    #[test]
+    fn test_config_snapshot_serialization() -> Result<(), serde_json::Error> {
+        let mut snapshot = ConfigSnapshot::new(PythonInterpreterConfig::default());
+        snapshot.update(PythonInterpreterConfig {
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            argv: Some(vec!["prog".into()]),
+            ..Default::default()
+        });
+
+        let value = serde_json::to_value(&snapshot)?;
+        assert_eq!(value["changelog"][0][1]["field"], "argv");
+        assert_eq!(value["changelog"][1][1]["field"], "optimization_level");
+        assert_eq!(value["changelog"][1][1]["after"], "2");
+        assert_eq!(serde_json::from_value::<ConfigSnapshot>(value)?, snapshot);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_predicates() {
+        let profile = PythonInterpreterProfile::Isolated;
+        assert!(profile.is_isolated());
+        assert!(!profile.is_python());
+        assert_eq!(
+            profile.to_pyconfig_init_func(),
+            "PyConfig_InitIsolatedConfig"
+        );
+
+        let profile = PythonInterpreterProfile::Python;
+        assert!(!profile.is_isolated());
+        assert!(profile.is_python());
+        assert_eq!(profile.to_pyconfig_init_func(), "PyConfig_InitPythonConfig");
+    }
+
+    #[test]
+    fn test_python_version_constraints() {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(config.python_version_constraints().min_version, (3, 8));
+        assert!(config.validate_for_version(3, 8).is_ok());
+        assert_eq!(config.validate_for_version(3, 7).unwrap_err().len(), 1);
+
+        config.check_hash_pycs_mode = Some(CheckHashPycsMode::Always);
+        config.x_options = Some(vec![
+            "dev".to_string(),
+            "int_max_str_digits=4300".to_string(),
+            "frozen_modules=off".to_string(),
+        ]);
+
+        let constraints = config.python_version_constraints();
+        assert_eq!(constraints.min_version, (3, 11));
+        assert!(constraints.is_satisfied_by(3, 11));
+        assert!(constraints.is_satisfied_by(3, 12));
+        assert!(!constraints.is_satisfied_by(3, 10));
+
+        assert!(config.validate_for_version(3, 11).is_ok());
+        assert_eq!(
+            config.validate_for_version(3, 10),
+            Err(vec![
+                "x_options entry int_max_str_digits=4300 requires Python 3.11+; got 3.10"
+                    .to_string(),
+                "x_options entry frozen_modules=off requires Python 3.11+; got 3.10".to_string(),
+            ])
+        );
+        assert_eq!(config.validate_for_version(3, 7).unwrap_err().len(), 4);
+
+        let config = PythonInterpreterConfig {
+            x_options: Some(vec!["perf".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(config.python_version_constraints().min_version, (3, 12));
+        assert!(config.validate_for_version(3, 12).is_ok());
+        assert!(config.validate_for_version(3, 11).is_err());
+    }
+
+    #[test]
+    fn test_allocator_default() {
+        assert_eq!(Allocator::default(), Allocator::NotSet);
+        assert!(!Allocator::default().is_set());
+
+        for allocator in Allocator::all_known() {
+            assert_eq!(allocator.is_set(), *allocator != Allocator::NotSet);
+        }
+        assert!(Allocator::Default.is_set());
+        assert!(Allocator::PyMallocDebug.is_set());
+    }
+
+    #[test]
+    fn test_stdio_is_binary() {
+        for profile in PythonInterpreterProfile::all_known() {
+            for configure_c_stdio in [None, Some(false), Some(true)] {
+                for buffered_stdio in [None, Some(false), Some(true)] {
+                    let config = PythonInterpreterConfig {
+                        profile: *profile,
+                        configure_c_stdio,
+                        buffered_stdio,
+                        ..Default::default()
+                    };
+
+                    let configured =
+                        configure_c_stdio.unwrap_or(*profile == PythonInterpreterProfile::Python);
+
+                    assert_eq!(
+                        config.stdio_is_binary(),
+                        !cfg!(windows) || configured,
+                        "profile={} configure_c_stdio={:?} buffered_stdio={:?}",
+                        profile,
+                        configure_c_stdio,
+                        buffered_stdio
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_overrides_python_preconfig_env_vars() -> Result<(), String> {
+        let base = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            use_environment: Some(true),
+            ..Default::default()
+        };
+
+        let config = base.apply_overrides([
+            ("PYTHONCOERCECLOCALE", "1"),
+            ("PYTHONDEVMODE", "1"),
+            ("PYTHONMALLOC", "pymalloc_debug"),
+            ("PYTHONUTF8", "1"),
+        ])?;
+        assert_eq!(config.coerce_c_locale, Some(CoerceCLocale::LCCtype));
+        assert_eq!(config.coerce_c_locale_warn, None);
+        assert_eq!(config.development_mode, Some(true));
+        assert_eq!(config.allocator, Some(Allocator::PyMallocDebug));
+        assert_eq!(config.utf8_mode, Some(true));
+
+        let config = base.apply_overrides([("PYTHONCOERCECLOCALE", "warn")])?;
+        assert_eq!(config.coerce_c_locale, None);
+        assert_eq!(config.coerce_c_locale_warn, Some(true));
+
+        let config = base.apply_overrides([("PYTHONCOERCECLOCALE", "0")])?;
+        assert_eq!(config, base);
+
+        assert_eq!(
+            base.apply_overrides([("PYTHONMALLOC", "bogus")]),
+            Err("PYTHONMALLOC: bogus is not a recognized value".to_string())
+        );
+
+        // PYOXIDIZER_CONFIG_ variables take precedence, regardless of order.
+        let config = base.apply_overrides([
+            ("PYTHONDEVMODE", "1"),
+            ("PYOXIDIZER_CONFIG_DEVELOPMENT_MODE", "0"),
+            ("PYOXIDIZER_CONFIG_ALLOCATOR", "malloc"),
+            ("PYTHONMALLOC", "pymalloc"),
+        ])?;
+        assert_eq!(config.development_mode, Some(false));
+        assert_eq!(config.allocator, Some(Allocator::Malloc));
+
+        // Values without a CPython equivalent use our variables.
+        let config = PythonInterpreterConfig {
+            allocator: Some(Allocator::NotSet),
+            coerce_c_locale: Some(CoerceCLocale::C),
+            ..base.clone()
+        };
+        let vars = config.to_env_vars();
+        assert!(vars.contains(&(
+            "PYOXIDIZER_CONFIG_ALLOCATOR".to_string(),
+            "not-set".to_string()
+        )));
+        assert!(vars.contains(&(
+            "PYOXIDIZER_CONFIG_COERCE_C_LOCALE".to_string(),
+            "C".to_string()
+        )));
+        assert_eq!(
+            PythonInterpreterConfig::default().apply_overrides(vars)?,
+            config
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_xoptions() {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(config.xoption_get("dev"), None);
+        config.xoption_remove("dev");
+        assert_eq!(config.x_options, None);
+
+        config.xoption_set("dev", None);
+        assert_eq!(config.x_options, Some(vec!["dev".to_string()]));
+        assert_eq!(config.xoption_get("dev"), Some(None));
+
+        config.xoption_set("utf8", Some("1"));
+        assert_eq!(config.xoption_get("utf8"), Some(Some("1")));
+        assert_eq!(config.xoption_get("utf"), None);
+
+        config.xoption_set("dev", Some("x=y"));
+        assert_eq!(config.xoption_get("dev"), Some(Some("x=y")));
+        assert_eq!(
+            config.x_options,
+            Some(vec!["dev=x=y".to_string(), "utf8=1".to_string()])
+        );
+
+        // The last occurrence wins and replacing collapses duplicates.
+        config.x_options = Some(vec![
+            "a=1".to_string(),
+            "b".to_string(),
+            "a=2".to_string(),
+            "a".to_string(),
+        ]);
+        assert_eq!(config.xoption_get("a"), Some(None));
+        config.xoption_set("a", Some("3"));
+        assert_eq!(
+            config.x_options,
+            Some(vec!["a=3".to_string(), "b".to_string()])
+        );
+
+        config.xoption_remove("a");
+        assert_eq!(config.x_options, Some(vec!["b".to_string()]));
+        config.xoption_remove("b");
+        assert_eq!(config.x_options, Some(vec![]));
+        assert_eq!(config.xoption_get("b"), None);
+    }
+
+    #[test]
+    fn test_warn_filter_parse() -> Result<(), String> {
+        assert_eq!(WarnFilter::from_str("")?, WarnFilter::default());
+        assert_eq!(
+            WarnFilter::from_str("ignore")?,
+            WarnFilter {
+                action: WarnFilterAction::Ignore,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            WarnFilter::from_str("e: foo :DeprecationWarning:mod.sub:42")?,
+            WarnFilter {
+                action: WarnFilterAction::Error,
+                message: "foo".to_string(),
+                category: "DeprecationWarning".to_string(),
+                module: "mod.sub".to_string(),
+                lineno: 42,
+            }
+        );
+        assert_eq!(WarnFilter::from_str("a")?.action, WarnFilterAction::Always);
+        assert_eq!(WarnFilter::from_str("m")?.action, WarnFilterAction::Module);
+
+        assert_eq!(
+            WarnFilter::from_str("bogus"),
+            Err("bogus is not a valid warning filter action".to_string())
+        );
+        assert_eq!(
+            WarnFilter::from_str("ignore::::x"),
+            Err("x is not a valid line number".to_string())
+        );
+        assert_eq!(
+            WarnFilter::from_str("ignore:::::"),
+            Err("ignore::::: has too many fields".to_string())
+        );
+
+        for (input, normalized) in [
+            ("", "default"),
+            ("i", "ignore"),
+            ("ignore::DeprecationWarning", "ignore::DeprecationWarning"),
+            ("once:::foo:0", "once:::foo"),
+            ("error:msg::mod:7", "error:msg::mod:7"),
+        ] {
+            let filter = WarnFilter::from_str(input)?;
+            assert_eq!(filter.to_string(), normalized);
+            assert_eq!(WarnFilter::from_str(normalized)?, filter);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_warn_options_manipulation() {
+        let mut config = PythonInterpreterConfig::default();
+        assert!(!config.warn_options_contains("ignore"));
+        assert!(!config.warn_options_remove("ignore"));
+
+        config.warn_options_add("ignore::DeprecationWarning".to_string());
+        config.warn_options_add("i::DeprecationWarning".to_string());
+        config.warn_options_add("error".to_string());
+        assert_eq!(
+            config.warn_options,
+            Some(vec![
+                "ignore::DeprecationWarning".to_string(),
+                "error".to_string()
+            ])
+        );
+        assert!(config.warn_options_contains("i::DeprecationWarning::0"));
+        assert!(!config.warn_options_contains("ignore"));
+
+        // Invalid filters are compared verbatim.
+        config.warn_options_add("bogus".to_string());
+        assert!(config.warn_options_contains("bogus"));
+        assert!(!config.warn_options_contains("bogus:"));
+
+        assert!(config.warn_options_remove("ignore::DeprecationWarning:"));
+        assert!(!config.warn_options_remove("ignore::DeprecationWarning"));
+        assert_eq!(
+            config.warn_options,
+            Some(vec!["error".to_string(), "bogus".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_argv_as_strings() {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(config.argv_as_strings(), None);
+        assert_eq!(config.argv_as_strings_strict(), None);
+
+        config.argv = Some(vec![]);
+        assert_eq!(config.argv_as_strings(), Some(vec![]));
+        assert_eq!(config.argv_as_strings_strict(), Some(Ok(vec![])));
+
+        config = config.with_argv(["prog", "arg"]);
+        let expected = vec!["prog".to_string(), "arg".to_string()];
+        assert_eq!(config.argv_as_strings(), Some(expected.clone()));
+        assert_eq!(config.argv_as_strings_strict(), Some(Ok(expected)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_argv_as_strings_non_utf8() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let config = PythonInterpreterConfig::default().with_argv([
+            OsString::from("prog"),
+            OsString::from_vec(b"a\xffb".to_vec()),
+            OsString::from("arg"),
+            OsString::from_vec(b"\xfe".to_vec()),
+        ]);
+
+        assert_eq!(
+            config.argv_as_strings(),
+            Some(vec![
+                "prog".to_string(),
+                "a\u{FFFD}b".to_string(),
+                "arg".to_string(),
+                "\u{FFFD}".to_string(),
+            ])
+        );
+        assert_eq!(config.argv_as_strings_strict(), Some(Err(1)));
+    }
+
+    #[test]
+    fn test_module_search_paths_as_strings() -> Result<(), String> {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(config.module_search_paths_as_string_vec()?, None);
+        assert_eq!(config.module_search_paths_as_os_strings(), None);
+
+        config.module_search_paths = Some(vec![PathBuf::from("lib"), PathBuf::from("/usr/lib")]);
+        assert_eq!(
+            config.module_search_paths_as_string_vec()?,
+            Some(vec!["lib".to_string(), "/usr/lib".to_string()])
+        );
+        assert_eq!(
+            config.module_search_paths_as_os_strings(),
+            Some(vec![OsString::from("lib"), OsString::from("/usr/lib")])
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_module_search_paths_as_strings_non_utf8() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let config = PythonInterpreterConfig {
+            module_search_paths: Some(vec![
+                PathBuf::from("lib"),
+                PathBuf::from(OsString::from_vec(b"a\xffb".to_vec())),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.module_search_paths_as_string_vec(),
+            Err("module search path is not valid Unicode: a\u{FFFD}b".to_string())
+        );
+        assert_eq!(config.module_search_paths_as_os_strings().unwrap().len(), 2);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_module_search_paths_verbatim() -> Result<(), String> {
+        let long = format!(r"\\?\C:\{}", "a".repeat(300));
+
+        let config = PythonInterpreterConfig {
+            module_search_paths: Some(vec![
+                PathBuf::from(r"\\?\C:\Python\Lib"),
+                PathBuf::from(r"\\?\UNC\server\share\Lib"),
+                PathBuf::from(r"\\server\share\Lib"),
+                PathBuf::from(r"C:\Python"),
+                PathBuf::from(&long),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.module_search_paths_as_string_vec()?,
+            Some(vec![
+                r"C:\Python\Lib".to_string(),
+                r"\\?\UNC\server\share\Lib".to_string(),
+                r"\\server\share\Lib".to_string(),
+                r"C:\Python".to_string(),
+                long,
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serialization")]
+    #[test]
+    fn test_serialization_field_order() -> Result<(), serde_json::Error> {
+        let config = all_fields_set();
+        let json = serde_json::to_string(&config)?;
+
+        let position = |field: &str| {
+            json.find(&format!("\"{}\":", field))
+                .unwrap_or_else(|| panic!("{} not serialized", field))
+        };
+
+        let order = [
+            "profile",
+            // Shared by PyPreConfig and PyConfig.
+            "development_mode",
+            "isolated",
+            "parse_argv",
+            "use_environment",
+            // Only in PyPreConfig.
+            "allocator",
+            "configure_locale",
+            "coerce_c_locale",
+            "coerce_c_locale_warn",
+            "legacy_windows_fs_encoding",
+            "utf8_mode",
+            // Only in PyConfig.
+            "argv",
+            "base_exec_prefix",
+            "x_options",
+        ];
+        for pair in order.windows(2) {
+            assert!(
+                position(pair[0]) < position(pair[1]),
+                "{} serialized before {}",
+                pair[0],
+                pair[1]
+            );
+        }
+
+        assert_eq!(
+            serde_json::from_str::<PythonInterpreterConfig>(&json)?,
+            config
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_active_run_target() {
+        let config = PythonInterpreterConfig::default();
+        assert_eq!(config.active_run_target(), None);
+        assert!(!config.has_any_run_target());
+
+        let config = config.with_run_script("script.py");
+        assert_eq!(
+            config.active_run_target(),
+            Some(RunTarget::Filename(PathBuf::from("script.py")))
+        );
+        assert!(config.has_any_run_target());
+
+        assert_eq!(
+            config.with_run_module("foo").active_run_target(),
+            Some(RunTarget::Module("foo".to_string()))
+        );
+        assert_eq!(
+            config.with_run_command("pass").active_run_target(),
+            Some(RunTarget::Command("pass".to_string()))
+        );
+
+        // CPython's priority applies to invalid configurations.
+        let config = PythonInterpreterConfig {
+            run_command: Some("pass".to_string()),
+            run_module: Some("foo".to_string()),
+            run_filename: Some(PathBuf::from("script.py")),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        assert_eq!(
+            config.active_run_target(),
+            Some(RunTarget::Command("pass".to_string()))
+        );
+        assert_eq!(
+            PythonInterpreterConfig {
+                run_command: None,
+                ..config
+            }
+            .active_run_target(),
+            Some(RunTarget::Module("foo".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_to_python_command() {
+        let args = |command: &Command| {
+            command
+                .get_args()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+        let envs = |command: &Command| {
+            command
+                .get_envs()
+                .map(|(k, v)| {
+                    (
+                        k.to_string_lossy().to_string(),
+                        v.map(|v| v.to_string_lossy().to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let python = Path::new("/usr/bin/python3");
+
+        let command = PythonInterpreterConfig::default().to_python_command(python);
+        assert_eq!(command.get_program(), python.as_os_str());
+        assert_eq!(args(&command), vec!["-I"]);
+        assert!(envs(&command).is_empty());
+
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            use_environment: Some(false),
+            user_site_directory: Some(false),
+            write_bytecode: Some(false),
+            bytes_warning: Some(BytesWarning::Raise),
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            check_hash_pycs_mode: Some(CheckHashPycsMode::Always),
+            warn_options: Some(vec!["ignore".to_string()]),
+            development_mode: Some(true),
+            utf8_mode: Some(false),
+            pycache_prefix: Some(PathBuf::from("/tmp/pycache")),
+            x_options: Some(vec!["frozen_modules=off".to_string()]),
+            hash_seed: Some(42),
+            allocator: Some(Allocator::PyMallocDebug),
+            stdio_encoding: Some("utf-8".to_string()),
+            stdio_errors: Some("strict".to_string()),
+            configure_locale: Some(false),
+            ..Default::default()
+        }
+        .with_run_module("http.server")
+        .with_argv(["prog", "8000"]);
+
+        let command = config.to_python_command(python);
+        assert_eq!(
+            args(&command),
+            vec![
+                "-E",
+                "-s",
+                "-B",
+                "-bb",
+                "-OO",
+                "--check-hash-based-pycs",
+                "always",
+                "-W",
+                "ignore",
+                "-X",
+                "dev",
+                "-X",
+                "utf8=0",
+                "-X",
+                "pycache_prefix=/tmp/pycache",
+                "-X",
+                "frozen_modules=off",
+                "-m",
+                "http.server",
+                "8000",
+            ]
+        );
+        let mut envs = envs(&command);
+        envs.sort();
+        assert_eq!(
+            envs,
+            vec![
+                ("PYTHONHASHSEED".to_string(), Some("42".to_string())),
+                (
+                    "PYTHONIOENCODING".to_string(),
+                    Some("utf-8:strict".to_string())
+                ),
+                (
+                    "PYTHONMALLOC".to_string(),
+                    Some("pymalloc_debug".to_string())
+                ),
+            ]
+        );
+
+        let command = PythonInterpreterConfig::default()
+            .with_run_command("import sys")
+            .with_argv(["prog", "a"])
+            .to_python_command(python);
+        assert_eq!(args(&command), vec!["-I", "-c", "import sys", "a"]);
+
+        let command = PythonInterpreterConfig::default()
+            .with_run_script("script.py")
+            .to_python_command(python);
+        assert_eq!(args(&command), vec!["-I", "script.py"]);
+
+        let command = PythonInterpreterConfig::default()
+            .with_argv(["prog", "a"])
+            .to_python_command(python);
+        assert_eq!(args(&command), vec!["-I", "-", "a"]);
+    }
+
+    #[test]
+    fn test_apply_delta() {
+        let config = all_fields_set();
+
+        let delta = PythonInterpreterConfigDelta::default();
+        assert_eq!(config.apply_delta(&delta), config);
+
+        let delta = PythonInterpreterConfigDelta {
+            profile: Some(PythonInterpreterProfile::Isolated),
+            home: Some(None),
+            isolated: Some(Some(true)),
+            argv: Some(Some(vec![OsString::from("other")])),
+            ..Default::default()
+        };
+        let applied = config.apply_delta(&delta);
+        assert_eq!(applied.profile, PythonInterpreterProfile::Isolated);
+        assert_eq!(applied.home, None);
+        assert_eq!(applied.isolated, Some(true));
+        assert_eq!(applied.argv, Some(vec![OsString::from("other")]));
+        assert_eq!(
+            PythonInterpreterConfig {
+                profile: config.profile,
+                home: config.home.clone(),
+                isolated: config.isolated,
+                argv: config.argv.clone(),
+                ..applied
+            },
+            config
+        );
+
+        // A delta from a config replaces everything, including unsetting fields.
+        let delta = PythonInterpreterConfigDelta::from(PythonInterpreterConfig::default());
+        assert_eq!(delta.home, Some(None));
+        assert_eq!(
+            config.apply_delta(&delta),
+            PythonInterpreterConfig::default()
+        );
+        assert_eq!(
+            PythonInterpreterConfig::default()
+                .apply_delta(&PythonInterpreterConfigDelta::from(config.clone())),
+            config
+        );
+    }
+
+    #[test]
+    fn test_paths_iter() {
+        assert_eq!(PythonInterpreterConfig::default().paths_iter().count(), 0);
+
+        let mut config = PythonInterpreterConfig {
+            home: Some(PathBuf::from("/prefix")),
+            run_filename: Some(PathBuf::from("/prefix/script.py")),
+            module_search_paths: Some(vec![
+                PathBuf::from("/prefix/lib"),
+                PathBuf::from("/other/lib"),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.paths_iter().collect::<Vec<_>>(),
+            vec![
+                ("home", Path::new("/prefix")),
+                ("run_filename", Path::new("/prefix/script.py")),
+                ("module_search_paths", Path::new("/prefix/lib")),
+                ("module_search_paths", Path::new("/other/lib")),
+            ]
+        );
+        assert_eq!(
+            config
+                .paths_iter_mut()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            config
+                .paths_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        );
+
+        config.remap_paths(|path| match path.strip_prefix("/prefix") {
+            Ok(rest) => Path::new("/relocated").join(rest),
+            Err(_) => path.to_path_buf(),
+        });
+        assert_eq!(config.home, Some(PathBuf::from("/relocated")));
+        assert_eq!(
+            config.run_filename,
+            Some(PathBuf::from("/relocated/script.py"))
+        );
+        assert_eq!(
+            config.module_search_paths,
+            Some(vec![
+                PathBuf::from("/relocated/lib"),
+                PathBuf::from("/other/lib")
+            ])
+        );
+
+        // Every path field of a fully populated config is visited.
+        assert_eq!(all_fields_set().paths_iter().count(), 12);
+    }
+
+    #[test]
+    fn test_memory_allocator_backend_target_availability() {
+        for backend in MemoryAllocatorBackend::all_known() {
+            assert!(backend.is_available_for_target_triple("x86_64-unknown-linux-gnu"));
+            assert!(backend.is_available_for_target_triple("aarch64-apple-darwin"));
+        }
+
+        let triple = "x86_64-pc-windows-msvc";
+        assert!(MemoryAllocatorBackend::Default.is_available_for_target_triple(triple));
+        assert!(!MemoryAllocatorBackend::Jemalloc.is_available_for_target_triple(triple));
+        assert!(MemoryAllocatorBackend::Mimalloc.is_available_for_target_triple(triple));
+        assert!(MemoryAllocatorBackend::Snmalloc.is_available_for_target_triple(triple));
+        assert!(!MemoryAllocatorBackend::Tcmalloc.is_available_for_target_triple(triple));
+
+        let triple = "i686-unknown-linux-gnu";
+        assert!(MemoryAllocatorBackend::Jemalloc.is_available_for_target_triple(triple));
+        assert!(!MemoryAllocatorBackend::Snmalloc.is_available_for_target_triple(triple));
+
+        let triple = "wasm32-wasi";
+        assert!(MemoryAllocatorBackend::Rust.is_available_for_target_triple(triple));
+        assert!(!MemoryAllocatorBackend::Mimalloc.is_available_for_target_triple(triple));
+
+        // The cfg!() based check agrees with the triple based check.
+        let current = if cfg!(all(target_arch = "x86_64", target_os = "linux")) {
+            Some("x86_64-unknown-linux-gnu")
+        } else if cfg!(all(target_arch = "aarch64", target_os = "macos")) {
+            Some("aarch64-apple-darwin")
+        } else if cfg!(all(target_arch = "x86_64", target_os = "windows")) {
+            Some("x86_64-pc-windows-msvc")
+        } else {
+            None
+        };
+        if let Some(triple) = current {
+            for backend in MemoryAllocatorBackend::all_known() {
+                assert_eq!(
+                    backend.is_available_on_current_target(),
+                    backend.is_available_for_target_triple(triple),
+                    "{}",
+                    backend
+                );
+            }
+        }
+
+        assert!(MemoryAllocatorBackend::Default.compile_time_check().is_ok());
+    }
+
+    #[test]
+    fn test_with_sys_path_prepended() {
+        let c = PythonInterpreterConfig::default();
+        assert_eq!(
+            c.with_sys_path_prepended(&[]).module_search_paths,
+            Some(vec![])
+        );
+        assert_eq!(
+            c.with_sys_path_prepended(&["/a".into(), "/b".into(), "/a".into()])
+                .module_search_paths,
+            Some(vec!["/a".into(), "/b".into()])
+        );
+
+        let c = PythonInterpreterConfig {
+            module_search_paths: Some(vec!["/lib".into(), "/a".into(), "/lib2".into()]),
+            ..Default::default()
+        };
+        let c = c.with_sys_path_prepended(&["/a".into(), "/b".into()]);
+        assert_eq!(
+            c.module_search_paths,
+            Some(vec![
+                "/a".into(),
+                "/b".into(),
+                "/lib".into(),
+                "/lib2".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_with_sys_path_appended() {
+        let c = PythonInterpreterConfig::default();
+        assert_eq!(
+            c.with_sys_path_appended(&["/a".into(), "/a".into()])
+                .module_search_paths,
+            Some(vec!["/a".into()])
+        );
+
+        let c = PythonInterpreterConfig {
+            module_search_paths: Some(vec!["/lib".into(), "/a".into(), "/lib".into()]),
+            ..Default::default()
+        };
+        let c = c.with_sys_path_appended(&["/b".into(), "/a".into()]);
+        assert_eq!(
+            c.module_search_paths,
+            Some(vec!["/lib".into(), "/a".into(), "/b".into()])
+        );
+    }
+
+    #[test]
+    fn test_with_sys_path_removed() {
+        let c = PythonInterpreterConfig::default();
+        assert_eq!(
+            c.with_sys_path_removed(Path::new("/a")).module_search_paths,
+            None
+        );
+
+        let c = PythonInterpreterConfig {
+            module_search_paths: Some(vec!["/a".into(), "/lib".into(), "/a".into()]),
+            ..Default::default()
+        };
+        let c = c.with_sys_path_removed(Path::new("/a"));
+        assert_eq!(c.module_search_paths, Some(vec!["/lib".into()]));
+        let c = c.with_sys_path_removed(Path::new("/lib"));
+        assert_eq!(c.module_search_paths, Some(vec![]));
+    }
+
+    #[test]
+    fn test_effective_utf8_mode() {
+        let no_env = HashMap::new();
+        let env_on = HashMap::from([("PYTHONUTF8".to_string(), "1".to_string())]);
+        let env_off = HashMap::from([("PYTHONUTF8".to_string(), "0".to_string())]);
+        let env_empty = HashMap::from([("PYTHONUTF8".to_string(), "".to_string())]);
+        let locale_c = !cfg!(windows);
+
+        let python = PythonInterpreterConfig::for_profile(PythonInterpreterProfile::Python);
+        let isolated = PythonInterpreterConfig::for_profile(PythonInterpreterProfile::Isolated);
+
+        // Defaults to off, except under the C and POSIX locales.
+        assert!(!python.effective_utf8_mode(&no_env, None));
+        assert!(!python.effective_utf8_mode(&no_env, Some("en_US.UTF-8")));
+        assert!(!python.effective_utf8_mode(&no_env, Some("C.UTF-8")));
+        assert_eq!(python.effective_utf8_mode(&no_env, Some("C")), locale_c);
+        assert_eq!(python.effective_utf8_mode(&no_env, Some("POSIX")), locale_c);
+
+        // The environment variable takes precedence over the locale.
+        assert!(python.effective_utf8_mode(&env_on, Some("en_US.UTF-8")));
+        assert!(!python.effective_utf8_mode(&env_off, Some("C")));
+        assert_eq!(python.effective_utf8_mode(&env_empty, Some("C")), locale_c);
+
+        // Unless the environment isn't used.
+        let c = PythonInterpreterConfig {
+            use_environment: Some(false),
+            ..python.clone()
+        };
+        assert!(!c.effective_utf8_mode(&env_on, None));
+        let c = PythonInterpreterConfig {
+            isolated: Some(true),
+            ..python.clone()
+        };
+        assert!(!c.effective_utf8_mode(&env_on, None));
+        assert_eq!(c.effective_utf8_mode(&env_on, Some("C")), locale_c);
+
+        // An explicit value always wins.
+        let c = PythonInterpreterConfig {
+            utf8_mode: Some(false),
+            ..python.clone()
+        };
+        assert!(!c.effective_utf8_mode(&env_on, Some("C")));
+        let c = PythonInterpreterConfig {
+            utf8_mode: Some(true),
+            ..python
+        };
+        assert!(c.effective_utf8_mode(&env_off, None));
+
+        // The isolated profile disables it unless explicitly enabled.
+        assert!(!isolated.effective_utf8_mode(&env_on, Some("C")));
+        let c = PythonInterpreterConfig {
+            utf8_mode: Some(true),
+            ..isolated
+        };
+        assert!(c.effective_utf8_mode(&no_env, None));
+    }
+
+    #[test]
+    fn test_compact_repr_snapshot() {
+        let c = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Isolated,
+            argv: Some(vec!["prog".into(), "with space".into()]),
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            run_command: Some("import sys\nprint(\"hi\")".to_string()),
+            stdio_encoding: Some("".to_string()),
+            site_import: Some(false),
+            x_options: Some(vec![]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            c.to_compact_repr(),
+            concat!(
+                "argv = [\"prog\", \"with space\"]\n",
+                "optimization_level = 2\n",
+                "profile = isolated\n",
+                "run_command = \"import sys\\nprint(\\\"hi\\\")\"\n",
+                "site_import = false\n",
+                "stdio_encoding = \"\"\n",
+                "x_options = []\n",
+            )
+        );
+        assert_eq!(
+            PythonInterpreterConfig::from_compact_repr(&c.to_compact_repr()),
+            Ok(c)
+        );
+    }
+
+    #[test]
+    fn test_compact_repr_round_trip() {
+        for c in [all_fields_set(), PythonInterpreterConfig::for_testing()] {
+            assert_eq!(
+                PythonInterpreterConfig::from_compact_repr(&c.to_compact_repr()),
+                Ok(c)
+            );
+        }
+
+        let c = PythonInterpreterConfig {
+            run_filename: Some(PathBuf::from(" leading space")),
+            run_module: Some("\"quoted\"".to_string()),
+            warn_options: Some(vec!["a, b]".to_string(), "\\\u{7}".to_string()]),
+            ..Default::default()
+        };
+        let repr = c.to_compact_repr();
+        assert!(repr.contains("warn_options = [\"a, b]\", \"\\\\\\u{7}\"]\n"));
+        assert_eq!(PythonInterpreterConfig::from_compact_repr(&repr), Ok(c));
+
+        assert_eq!(
+            PythonInterpreterConfig::from_compact_repr(""),
+            Ok(PythonInterpreterConfig::default())
+        );
+    }
+
+    #[test]
+    fn test_from_compact_repr_errors() {
+        assert_eq!(
+            PythonInterpreterConfig::from_compact_repr("isolated"),
+            Err("line 1: expected `name = value`".to_string())
+        );
+        assert_eq!(
+            PythonInterpreterConfig::from_compact_repr("\nfoo = bar"),
+            Err("line 2: foo: foo is not a known field".to_string())
+        );
+        assert_eq!(
+            PythonInterpreterConfig::from_compact_repr("argv = [\"a\" \"b\"]"),
+            Err("line 1: argv: expected `,` or `]` in list: [\"a\" \"b\"]".to_string())
+        );
+        assert_eq!(
+            PythonInterpreterConfig::from_compact_repr("run_module = \"a\"b"),
+            Err("line 1: run_module: unexpected content after quoted value".to_string())
+        );
+        assert_eq!(
+            PythonInterpreterConfig::from_compact_repr("run_module = \"a\\q\""),
+            Err("line 1: run_module: invalid escape sequence in \"a\\q\"".to_string())
+        );
+        assert!(PythonInterpreterConfig::from_compact_repr("run_module = \"a").is_err());
+    }
+
+    #[test]
+    fn test_fields_touching_stdlib() {
+        let c = PythonInterpreterConfig::default();
+        assert!(c.fields_touching_stdlib().is_empty());
+
+        let c = PythonInterpreterConfig {
+            home: Some(PathBuf::from("/home")),
+            site_import: Some(false),
+            executable: Some(PathBuf::from("/executable")),
+            verbose: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(c.fields_touching_stdlib(), vec!["home", "site_import"]);
+
+        let c = PythonInterpreterConfig {
+            module_search_paths: Some(vec![]),
+            ..Default::default()
+        };
+        assert_eq!(c.fields_touching_stdlib(), vec!["module_search_paths"]);
+
+        assert_eq!(
+            all_fields_set().fields_touching_stdlib(),
+            vec![
+                "base_exec_prefix",
+                "base_prefix",
+                "exec_prefix",
+                "home",
+                "module_search_paths",
+                "prefix",
+                "site_import"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embed_in_generated_rust_code_escaping() {
+        let c = PythonInterpreterConfig {
+            argv: Some(vec!["a\"b".into()]),
+            home: Some(PathBuf::from("C:\\Python")),
+            hash_seed: Some(42),
+            ..Default::default()
+        };
+        let code = c.embed_in_generated_rust_code();
+
+        assert!(code.contains("    argv: Some(vec![std::ffi::OsString::from(\"a\\\"b\")]),\n"));
+        assert!(code.contains("    home: Some(std::path::PathBuf::from(\"C:\\\\Python\")),\n"));
+        assert!(code.contains("    hash_seed: Some(42),\n"));
+        assert!(code.ends_with("    x_options: None,\n}\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_embed_in_generated_rust_code_non_unicode() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let c = PythonInterpreterConfig {
+            argv: Some(vec![OsString::from_vec(vec![0x66, 0xff])]),
+            ..Default::default()
+        };
+
+        assert!(c.embed_in_generated_rust_code().contains(
+            "    argv: Some(vec![<std::ffi::OsString as std::os::unix::ffi::OsStringExt>::from_vec(vec![102, 255])]),\n"
+        ));
+    }
+
+    #[test]
+    fn test_field_coverage() {
+        let c = PythonInterpreterConfig::default();
+        assert_eq!(c.count_set_fields(), 0);
+        assert_eq!(c.coverage_fraction(), 0.0);
+        assert_eq!(c.unset_fields().len(), 55);
+        assert_eq!(c.unset_fields()[0], "allocator");
+        assert_eq!(c.unset_fields()[54], "x_options");
+
+        let c = PythonInterpreterConfig::for_testing();
+        assert_eq!(c.count_set_fields(), 52);
+        assert_eq!(
+            c.unset_fields(),
+            vec!["run_command", "run_filename", "run_module"]
+        );
+
+        let c = c.with_run_module("foo");
+        assert_eq!(c.count_set_fields(), 53);
+        assert_eq!(c.unset_fields(), vec!["run_command", "run_filename"]);
+
+        let c = PythonInterpreterConfig {
+            run_command: Some("pass".to_string()),
+            run_filename: Some(PathBuf::from("foo.py")),
+            ..c
+        };
+        assert!(c.unset_fields().is_empty());
+        assert_eq!(c.count_set_fields(), 55);
+        assert_eq!(c.coverage_fraction(), 1.0);
+
+        let c = PythonInterpreterConfig {
+            verbose: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(c.count_set_fields(), 1);
+        assert_eq!(c.coverage_fraction(), 1.0 / 55.0);
+        assert!(!c.unset_fields().contains(&"verbose"));
+    }
+
+    #[test]
+    fn test_resolve_linux_terminfo_dirs() -> Result<(), Box<dyn std::error::Error>> {
+        let td = tempfile::Builder::new()
+            .prefix("python-packaging-test")
+            .tempdir()?;
+        let root = td.path();
+        let rooted = |dirs: &[&str]| {
+            dirs.iter()
+                .map(|dir| root.join(dir).display().to_string())
+                .collect::<Vec<_>>()
+                .join(":")
+        };
+
+        // Without os-release, only existing well-known directories are used.
+        assert_eq!(resolve_linux_terminfo_dirs(root), "");
+
+        std::fs::create_dir_all(root.join("etc/terminfo"))?;
+        std::fs::create_dir_all(root.join("usr/share/terminfo/x"))?;
+        std::fs::write(root.join("usr/share/terminfo/x/xterm"), b"fake")?;
+        std::fs::create_dir_all(root.join("usr/lib"))?;
+        assert_eq!(
+            resolve_linux_terminfo_dirs(root),
+            rooted(&["etc/terminfo", "usr/share/terminfo"])
+        );
+
+        std::fs::write(
+            root.join("etc/os-release"),
+            "NAME=\"Ubuntu\"\nID=ubuntu\nID_LIKE=debian\n",
+        )?;
+        assert_eq!(
+            resolve_linux_terminfo_dirs(root),
+            rooted(&["etc/terminfo", "lib/terminfo", "usr/share/terminfo"])
+        );
+
+        std::fs::write(root.join("etc/os-release"), "ID=fedora\n")?;
+        assert_eq!(
+            resolve_linux_terminfo_dirs(root),
+            rooted(&["etc/terminfo", "usr/share/terminfo"])
+        );
+
+        std::fs::write(root.join("etc/os-release"), "ID=arch\n")?;
+        assert_eq!(
+            resolve_linux_terminfo_dirs(root),
+            rooted(&["etc/terminfo", "usr/share/terminfo"])
+        );
+
+        assert_eq!(
+            resolve_linux_terminfo_dirs(Path::new("/")),
+            match resolve_linux_distro(Path::new("/")) {
+                LinuxDistroVariant::Debian => TERMINFO_DIRS_DEBIAN.to_string(),
+                LinuxDistroVariant::RedHat => TERMINFO_DIRS_REDHAT.to_string(),
+                LinuxDistroVariant::Unknown => TERMINFO_DIRS_COMMON
+                    .iter()
+                    .copied()
+                    .filter(|dir| Path::new(dir).exists())
+                    .collect::<Vec<_>>()
+                    .join(":"),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_development_defaults() {
+        let base = PythonInterpreterConfig {
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            verbose: Some(false),
+            ..Default::default()
+        };
+        let config = base.with_development_defaults();
+
+        assert_eq!(config.development_mode, Some(true));
+        assert_eq!(config.fault_handler, Some(true));
+        assert_eq!(config.tracemalloc, Some(true));
+        assert_eq!(config.import_time, Some(true));
+        assert_eq!(config.verbose, Some(true));
+        assert_eq!(config.malloc_stats, Some(true));
+        assert_eq!(config.bytes_warning, Some(BytesWarning::Warn));
+        assert_eq!(config.optimization_level, base.optimization_level);
+        assert_eq!(config.count_set_fields(), 8);
+
+        // A release gate catches it.
+        assert_ne!(config.without_debug_fields(), config);
+    }
+
+    #[test]
+    fn test_enum_str_eq() {
+        assert!(MemoryAllocatorBackend::Jemalloc == "jemalloc");
+        assert!("jemalloc" == MemoryAllocatorBackend::Jemalloc);
+        assert!(MemoryAllocatorBackend::Jemalloc != "mimalloc");
+        assert!("mimalloc" != MemoryAllocatorBackend::Jemalloc);
+        assert!(MemoryAllocatorBackend::Jemalloc == "Jemalloc");
+        assert!("JEMALLOC" == MemoryAllocatorBackend::Jemalloc);
+
+        assert!(PythonInterpreterProfile::Isolated == "isolated");
+        assert!("python" == PythonInterpreterProfile::Python);
+        assert!(TerminfoResolution::Static("/foo".into()) == "static:/foo");
+        assert!("static:/bar" != TerminfoResolution::Static("/foo".into()));
+        assert!(TerminfoResolution::Static("/foo".into()) == "STATIC:/foo");
+        assert!(TerminfoResolution::Static("/foo".into()) != "static:/FOO");
+        assert!(TerminfoResolution::Static("".into()) == "static:");
+        assert!(TerminfoResolution::Static("".into()) != "static");
+        assert!(TerminfoResolution::Dynamic == "Dynamic");
+        assert!(CoerceCLocale::LCCtype == "LC_CTYPE");
+        assert!(CoerceCLocale::LCCtype == "lc_ctype");
+        assert!("raise" == BytesWarning::Raise);
+        assert!(CheckHashPycsMode::Never == "never");
+        assert!("py-malloc-debug" == Allocator::PyMallocDebug);
+        assert!(MultiprocessingStartMethod::ForkServer == "forkserver");
+        assert!("ignore" == WarnFilterAction::Ignore);
+        assert!("Ignore" != WarnFilterAction::Ignore);
+
+        for backend in MemoryAllocatorBackend::all_known() {
+            let value = backend.to_string();
+            assert!(*backend == value.as_str());
+            assert!(value.as_str() == *backend);
+        }
+
+        for backend in MemoryAllocatorBackend::all_known() {
+            let value = backend.as_str().to_ascii_uppercase();
+            assert_eq!(
+                *backend == value.as_str(),
+                MemoryAllocatorBackend::try_from(value.as_str()).as_ref() == Ok(backend)
+            );
+        }
+    }
+
+    #[test]
+    fn test_multiprocessing_start_method_to_python_str() {
+        assert_eq!(MultiprocessingStartMethod::None.to_python_str(), None);
+        assert_eq!(
+            MultiprocessingStartMethod::Fork.to_python_str(),
+            Some("fork")
+        );
+        assert_eq!(
+            MultiprocessingStartMethod::ForkServer.to_python_str(),
+            Some("forkserver")
+        );
+        assert_eq!(
+            MultiprocessingStartMethod::Spawn.to_python_str(),
+            Some("spawn")
+        );
+        assert_eq!(MultiprocessingStartMethod::Auto.to_python_str(), None);
+
+        for os in [
+            OsInfo {
+                os: "linux".to_string(),
+                has_fork: true,
+            },
+            OsInfo {
+                os: "windows".to_string(),
+                has_fork: false,
+            },
+        ] {
+            assert!(MultiprocessingStartMethod::detect(&os)
+                .to_python_str()
+                .is_some());
+        }
+    }
+
+    #[test]
+    fn test_strip_write_paths() {
+        let base = PythonInterpreterConfig {
+            pycache_prefix: Some("/cache".into()),
+            write_bytecode: Some(true),
+            ..PythonInterpreterConfig::for_testing()
+        };
+        let config = base.strip_write_paths();
+
+        assert_eq!(config.pycache_prefix, None);
+        assert_eq!(config.write_bytecode, Some(false));
+        assert_eq!(config.count_set_fields(), base.count_set_fields() - 1);
+
+        // Idempotent.
+        assert_eq!(config.strip_write_paths(), config);
+    }
+
+    #[test]
+    fn test_to_pyoxidizer_starlark_snippet() {
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            allocator: Some(Allocator::PyMallocDebug),
+            isolated: Some(false),
+            argv: Some(vec!["ignored".into()]),
+            hash_seed: Some(42),
+            module_search_paths: Some(vec!["/lib".into(), "C:\\lib".into()]),
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            run_command: Some("print(\"hi\")\n".to_string()),
+            x_options: Some(vec![]),
+            ..Default::default()
+        };
+
+        let snippet = config.to_pyoxidizer_starlark_snippet();
+        let lines = snippet.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            &lines[0..2],
+            &[
+                "python_config = dist.make_python_interpreter_config()",
+                "python_config.config_profile = \"python\"",
+            ]
+        );
+        for line in [
+            "python_config.allocator = \"py-malloc-debug\"",
+            "python_config.isolated = False",
+            "python_config.hash_seed = 42",
+            "python_config.module_search_paths = [\"/lib\", \"C:\\\\lib\"]",
+            "python_config.optimization_level = 2",
+            "python_config.run_command = \"print(\\\"hi\\\")\\n\"",
+            "python_config.x_options = []",
+            // Unset fields are reset.
+            "python_config.configure_locale = None",
+            "python_config.home = None",
+        ] {
+            assert!(lines.contains(&line), "{}", line);
+        }
+        assert!(!lines
+            .iter()
+            .any(|line| line.starts_with("python_config.argv ")));
+
+        // Every field but argv is assigned.
+        assert_eq!(lines.len(), 2 + 54);
+        assert!(snippet.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_x_options_map() {
+        let map = |entries: &[(&str, Option<&str>)]| {
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.map(|v| v.to_string())))
+                .collect::<HashMap<_, _>>()
+        };
+
+        // Absent.
+        let config = PythonInterpreterConfig::default();
+        assert!(config.x_options_as_map().is_empty());
+        assert_eq!(
+            PythonInterpreterConfig::x_options_from_map(HashMap::new()),
+            config
+        );
+
+        // Bare flag.
+        let config = PythonInterpreterConfig {
+            x_options: Some(vec!["dev".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(config.x_options_as_map(), map(&[("dev", None)]));
+        assert_eq!(
+            PythonInterpreterConfig::x_options_from_map(config.x_options_as_map()),
+            config
+        );
+
+        // Valued, with an `=` in the value.
+        let config = PythonInterpreterConfig {
+            x_options: Some(vec!["pycache_prefix=a=b".to_string(), "utf8=1".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.x_options_as_map(),
+            map(&[("pycache_prefix", Some("a=b")), ("utf8", Some("1"))])
+        );
+        assert_eq!(
+            PythonInterpreterConfig::x_options_from_map(config.x_options_as_map()),
+            config
+        );
+
+        // Last occurrence wins.
+        let config = PythonInterpreterConfig {
+            x_options: Some(vec![
+                "utf8=1".to_string(),
+                "dev".to_string(),
+                "utf8".to_string(),
+                "dev=0".to_string(),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.x_options_as_map(),
+            map(&[("dev", Some("0")), ("utf8", None)])
+        );
+        assert_eq!(
+            PythonInterpreterConfig::x_options_from_map(config.x_options_as_map()).x_options,
+            Some(vec!["dev=0".to_string(), "utf8".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_allocator_chain_is_coherent() {
+        let chain = |cpython_layer, rust_layer| AllocatorChain {
+            cpython_layer,
+            rust_layer,
+        };
+
+        for allocator in Allocator::all_known() {
+            assert!(chain(*allocator, MemoryAllocatorBackend::Default).is_coherent());
+        }
+
+        assert!(chain(Allocator::NotSet, MemoryAllocatorBackend::Jemalloc).is_coherent());
+        assert!(chain(Allocator::Default, MemoryAllocatorBackend::Mimalloc).is_coherent());
+        assert!(chain(Allocator::PyMalloc, MemoryAllocatorBackend::Jemalloc).is_coherent());
+        assert!(chain(Allocator::PyMalloc, MemoryAllocatorBackend::Rust).is_coherent());
+
+        assert!(chain(Allocator::Malloc, MemoryAllocatorBackend::Jemalloc).is_coherent());
+        assert!(!chain(Allocator::MallocDebug, MemoryAllocatorBackend::Snmalloc).is_coherent());
+        assert!(!chain(Allocator::Debug, MemoryAllocatorBackend::Mimalloc).is_coherent());
+        assert!(!chain(Allocator::PyMallocDebug, MemoryAllocatorBackend::Rust).is_coherent());
+    }
+
+    #[cfg(feature = "dotenv")]
+    #[test]
+    fn test_parse_dotenv() {
+        let vars = parse_dotenv(
+            "# comment\n\
+             \n\
+             export PYOXIDIZER_CONFIG_HOME=/opt/python # trailing\n\
+             PYOXIDIZER_CONFIG_X_OPTIONS = dev#1 utf8\n\
+             SINGLE='a \\n # b'\n\
+             DOUBLE=\"a\\tb\\\"c\" # comment\n\
+             EMPTY=\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "PYOXIDIZER_CONFIG_HOME".to_string(),
+                    "/opt/python".to_string()
+                ),
+                (
+                    "PYOXIDIZER_CONFIG_X_OPTIONS".to_string(),
+                    "dev#1 utf8".to_string()
+                ),
+                ("SINGLE".to_string(), "a \\n # b".to_string()),
+                ("DOUBLE".to_string(), "a\tb\"c".to_string()),
+                ("EMPTY".to_string(), "".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            parse_dotenv("A=1\nB\n"),
+            Err("line 2: expected KEY=VALUE".to_string())
+        );
+        assert_eq!(
+            parse_dotenv("A=\"unterminated\n"),
+            Err("line 1: A: unterminated double quoted value".to_string())
+        );
+        assert_eq!(
+            parse_dotenv("A='x' y\n"),
+            Err("line 1: A: unexpected content after quoted value: y".to_string())
+        );
+        assert_eq!(
+            parse_dotenv("A=\"\\x\"\n"),
+            Err("line 1: A: invalid escape sequence: \\x".to_string())
+        );
+    }
+
+    #[cfg(feature = "dotenv")]
+    #[test]
+    fn test_from_dotenv_file() -> Result<(), Box<dyn std::error::Error>> {
+        let td = tempfile::Builder::new()
+            .prefix("python-packaging-test")
+            .tempdir()?;
+        let path = td.path().join(".env");
+
+        std::fs::write(
+            &path,
+            "DATABASE_URL=postgres://localhost\n\
+             PYOXIDIZER_CONFIG_OPTIMIZATION_LEVEL=2\n\
+             PYOXIDIZER_CONFIG_USE_ENVIRONMENT=1\n\
+             PYOXIDIZER_CONFIG_ISOLATED=0\n\
+             PYTHONHOME=\"/opt/python\"\n",
+        )?;
+
+        assert_eq!(
+            PythonInterpreterConfig::from_dotenv_file(&path)?,
+            PythonInterpreterConfig {
+                home: Some(PathBuf::from("/opt/python")),
+                isolated: Some(false),
+                optimization_level: Some(BytecodeOptimizationLevel::Two),
+                use_environment: Some(true),
+                ..Default::default()
+            }
+        );
+
+        std::fs::write(&path, "PYOXIDIZER_CONFIG_ALLOCATOR=bad\n")?;
+        assert_eq!(
+            PythonInterpreterConfig::from_dotenv_file(&path),
+            Err("PYOXIDIZER_CONFIG_ALLOCATOR: bad is not a valid allocator value; use 'not-set', 'default', 'debug', 'malloc', 'malloc-debug', 'py-malloc', or 'py-malloc-debug'".to_string())
+        );
+
+        assert!(PythonInterpreterConfig::from_dotenv_file(&td.path().join("missing")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sysconfig_vars() {
+        assert!(PythonInterpreterConfig::default()
+            .sysconfig_vars()
+            .is_empty());
+
+        let vars = PythonInterpreterConfig::for_testing().sysconfig_vars();
+        let mut names = vars.keys().map(|k| k.as_str()).collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            vec![
+                "base",
+                "exec_prefix",
+                "installed_base",
+                "installed_platbase",
+                "platbase",
+                "prefix",
+                "projectbase",
+            ]
+        );
+
+        let config = PythonInterpreterConfig {
+            prefix: Some("/venv".into()),
+            exec_prefix: Some("/venv-exec".into()),
+            base_prefix: Some("/usr".into()),
+            base_exec_prefix: Some("/usr-exec".into()),
+            executable: Some("/venv/bin/python3".into()),
+            ..Default::default()
+        };
+        let vars = config.sysconfig_vars();
+
+        assert_eq!(vars["prefix"], "/venv");
+        assert_eq!(vars["base"], "/venv");
+        assert_eq!(vars["exec_prefix"], "/venv-exec");
+        assert_eq!(vars["platbase"], "/venv-exec");
+        assert_eq!(vars["installed_base"], "/usr");
+        assert_eq!(vars["installed_platbase"], "/usr-exec");
+        assert_eq!(vars["projectbase"], "/venv/bin");
+        assert!(!vars.contains_key("Py_DEBUG"));
+
+        // A bare executable name has no directory.
+        let config = PythonInterpreterConfig {
+            executable: Some("python3".into()),
+            ..Default::default()
+        };
+        assert!(config.sysconfig_vars().is_empty());
+    }
+
+    #[test]
+    fn test_normalize_for_platform() {
+        let config = PythonInterpreterConfig {
+            coerce_c_locale: Some(CoerceCLocale::LCCtype),
+            coerce_c_locale_warn: Some(true),
+            legacy_windows_fs_encoding: Some(false),
+            legacy_windows_stdio: Some(true),
+            utf8_mode: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.normalize_for_platform("x86_64-unknown-linux-gnu"),
+            Ok(PythonInterpreterConfig {
+                legacy_windows_fs_encoding: None,
+                legacy_windows_stdio: None,
+                ..config.clone()
+            })
+        );
+        assert_eq!(
+            config.normalize_for_platform("x86_64-pc-windows-msvc"),
+            Ok(PythonInterpreterConfig {
+                coerce_c_locale: None,
+                coerce_c_locale_warn: None,
+                ..config.clone()
+            })
+        );
+
+        let config = PythonInterpreterConfig {
+            legacy_windows_fs_encoding: Some(true),
+            ..config
+        };
+        assert_eq!(
+            config.normalize_for_platform("x86_64-pc-windows-msvc"),
+            Err(NormalizationError::Conflict {
+                platform: "x86_64-pc-windows-msvc".to_string(),
+                field: "legacy_windows_fs_encoding",
+                other: "utf8_mode",
+            })
+        );
+        assert!(config
+            .normalize_for_platform("aarch64-apple-darwin")
+            .is_ok());
+
+        for platform in ["", "windows", "-"] {
+            assert_eq!(
+                PythonInterpreterConfig::default().normalize_for_platform(platform),
+                Err(NormalizationError::UnknownPlatform(platform.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_seed_is_randomized() {
+        for (hash_seed, randomized) in [(None, true), (Some(0), false), (Some(1), true)] {
+            let config = PythonInterpreterConfig {
+                hash_seed,
+                ..Default::default()
+            };
+
+            assert_eq!(config.hash_seed_is_randomized(), randomized);
+            assert_eq!(
+                config.warn_if_hash_not_randomized(),
+                if randomized {
+                    None
+                } else {
+                    Some(SecurityWarning::HashRandomizationDisabled)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_clone_scrubbed() {
+        let config = PythonInterpreterConfig {
+            executable: Some("/home/alice/venv/bin/python".into()),
+            home: Some("/home/alice".into()),
+            prefix: Some("/home/alice2/python".into()),
+            program_name: Some("python".into()),
+            module_search_paths: Some(vec!["/usr/lib/python3.10".into(), "/home/alice/lib".into()]),
+            argv: Some(vec!["/home/alice/script.py".into()]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.clone_scrubbed(Path::new("/home/alice")),
+            PythonInterpreterConfig {
+                executable: Some(Path::new("<home>").join("venv/bin/python")),
+                home: Some("<home>".into()),
+                module_search_paths: Some(vec![
+                    "/usr/lib/python3.10".into(),
+                    Path::new("<home>").join("lib"),
+                ]),
+                ..config.clone()
+            }
+        );
+
+        assert_eq!(config.clone_scrubbed(Path::new("/")), config);
+    }
+
+    #[test]
+    fn test_config_from_interpreter_query() -> Result<(), String> {
+        let flags = [
+            ("bytes_warning", "1"),
+            ("debug", "0"),
+            ("dev_mode", "1"),
+            ("dont_write_bytecode", "1"),
+            ("hash_randomization", "0"),
+            ("ignore_environment", "0"),
+            ("inspect", "0"),
+            ("interactive", "0"),
+            ("isolated", "0"),
+            ("no_site", "0"),
+            ("no_user_site", "1"),
+            ("optimize", "2"),
+            ("quiet", "0"),
+            ("utf8_mode", "1"),
+            ("verbose", "0"),
+        ];
+
+        let mut output = flags
+            .iter()
+            .map(|(k, v)| format!("flags.{}={}\n", k, v))
+            .collect::<String>();
+        output.push_str(concat!(
+            "executable=/usr/bin/python3\n",
+            "prefix=/usr\n",
+            "filesystem_encoding=utf-8\n",
+            "path=/usr/lib/python310.zip\n",
+            "path=/tmp/with\\nnewline\n",
+            "path=C:\\\\Python\n",
+            "warnoptions=ignore::DeprecationWarning\n",
+            "xoptions=dev\n",
+            "xoptions=frozen_modules=off\n",
+        ));
+
+        let config = config_from_interpreter_query(output.as_bytes())?;
+
+        assert_eq!(config.profile, PythonInterpreterProfile::Python);
+        assert_eq!(config.bytes_warning, Some(BytesWarning::Warn));
+        assert_eq!(config.development_mode, Some(true));
+        assert_eq!(config.write_bytecode, Some(false));
+        assert_eq!(config.hash_seed, Some(0));
+        assert_eq!(config.user_site_directory, Some(false));
+        assert_eq!(config.site_import, Some(true));
+        assert_eq!(
+            config.optimization_level,
+            Some(BytecodeOptimizationLevel::Two)
+        );
+        assert_eq!(config.utf8_mode, Some(true));
+        assert_eq!(config.executable, Some("/usr/bin/python3".into()));
+        assert_eq!(config.prefix, Some("/usr".into()));
+        assert_eq!(config.base_prefix, None);
+        assert_eq!(config.filesystem_encoding, Some("utf-8".into()));
+        assert_eq!(
+            config.module_search_paths,
+            Some(vec![
+                "/usr/lib/python310.zip".into(),
+                "/tmp/with\nnewline".into(),
+                "C:\\Python".into(),
+            ])
+        );
+        assert_eq!(
+            config.warn_options,
+            Some(vec!["ignore::DeprecationWarning".into()])
+        );
+        assert_eq!(
+            config.x_options,
+            Some(vec!["dev".into(), "frozen_modules=off".into()])
+        );
+
+        assert!(config_from_interpreter_query(b"prefix=/usr\n")
+            .unwrap_err()
+            .contains("sys.flags"));
+        assert!(config_from_interpreter_query(b"garbage\n").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_target_as_argv() {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(config.run_target_as_argv(), None);
+
+        config.argv = Some(vec!["prog".into(), "foo".into(), "--bar".into()]);
+        assert_eq!(config.run_target_as_argv(), None);
+
+        config.run_filename = Some("script.py".into());
+        assert_eq!(
+            config.run_target_as_argv(),
+            Some(vec!["script.py".into(), "foo".into(), "--bar".into()])
+        );
+
+        config.run_module = Some("json.tool".into());
+        assert_eq!(
+            config.run_target_as_argv(),
+            Some(vec!["-m".into(), "foo".into(), "--bar".into()])
+        );
+
+        config.run_command = Some("print('hello')".into());
+        assert_eq!(
+            config.run_target_as_argv(),
+            Some(vec!["-c".into(), "foo".into(), "--bar".into()])
+        );
+
+        // Only a program name.
+        config.argv = Some(vec!["prog".into()]);
+        assert_eq!(config.run_target_as_argv(), Some(vec!["-c".into()]));
+
+        config.argv = None;
+        assert_eq!(config.run_target_as_argv(), Some(vec!["-c".into()]));
+
+        config.run_command = None;
+        assert_eq!(config.run_target_as_argv(), Some(vec!["-m".into()]));
+
+        config.run_module = None;
+        assert_eq!(config.run_target_as_argv(), Some(vec!["script.py".into()]));
+    }
+
+    #[test]
+    fn test_warn_options_by_action() -> Result<(), String> {
+        let mut config = PythonInterpreterConfig::default();
+        assert!(config.warn_options_by_action().is_empty());
+
+        config.warn_options = Some(vec![
+            "ignore::DeprecationWarning:mymodule".to_string(),
+            "error".to_string(),
+            "i:::other".to_string(),
+            "bogus::UserWarning".to_string(),
+            ":msg".to_string(),
+        ]);
+
+        assert_eq!(
+            WarnFilter::from_str("ignore::DeprecationWarning:mymodule")?,
+            WarnFilter {
+                action: WarnFilterAction::Ignore,
+                category: "DeprecationWarning".to_string(),
+                module: "mymodule".to_string(),
+                ..Default::default()
+            }
+        );
+
+        let by_action = config.warn_options_by_action();
+        assert_eq!(
+            by_action,
+            [
+                (
+                    "ignore".to_string(),
+                    vec![
+                        "ignore::DeprecationWarning:mymodule".to_string(),
+                        "i:::other".to_string()
+                    ]
+                ),
+                ("error".to_string(), vec!["error".to_string()]),
+                ("default".to_string(), vec![":msg".to_string()]),
+            ]
+            .into_iter()
+            .collect::<HashMap<_, _>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_sys_flags_repr() {
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Python,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.to_sys_flags_repr(3, 9),
+            "sys.flags(debug=0, inspect=0, interactive=0, optimize=0, dont_write_bytecode=0, \
+             no_user_site=0, no_site=0, ignore_environment=0, verbose=0, bytes_warning=0, \
+             quiet=0, hash_randomization=1, isolated=0, dev_mode=False, utf8_mode=0)"
+        );
+
+        let config = PythonInterpreterConfig {
+            profile: PythonInterpreterProfile::Isolated,
+            development_mode: Some(true),
+            optimization_level: Some(BytecodeOptimizationLevel::Two),
+            bytes_warning: Some(BytesWarning::Raise),
+            write_bytecode: Some(false),
+            hash_seed: Some(0),
+            utf8_mode: Some(true),
+            x_options: Some(vec!["int_max_str_digits=5000".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.to_sys_flags_repr(3, 11),
+            "sys.flags(debug=0, inspect=0, interactive=0, optimize=2, dont_write_bytecode=1, \
+             no_user_site=1, no_site=0, ignore_environment=1, verbose=0, bytes_warning=2, \
+             quiet=0, hash_randomization=0, isolated=1, dev_mode=True, utf8_mode=1, \
+             warn_default_encoding=0, safe_path=True, int_max_str_digits=5000)"
+        );
+        assert!(config
+            .to_sys_flags_repr(3, 10)
+            .ends_with("utf8_mode=1, warn_default_encoding=0)"));
+    }
+
+    #[test]
+    fn test_with_frozen_modules_disabled() {
+        let config = PythonInterpreterConfig::default();
+        assert!(!config.has_frozen_modules_disabled());
+
+        let disabled = config.with_frozen_modules_disabled();
+        assert!(disabled.has_frozen_modules_disabled());
+        assert_eq!(
+            disabled.x_options,
+            Some(vec!["frozen_modules=off".to_string()])
+        );
+        assert_eq!(disabled.python_version_constraints().min_version, (3, 11));
+
+        let config = PythonInterpreterConfig {
+            x_options: Some(vec!["dev".to_string(), "frozen_modules=on".to_string()]),
+            ..Default::default()
+        };
+        assert!(!config.has_frozen_modules_disabled());
+        assert_eq!(
+            config.with_frozen_modules_disabled().x_options,
+            Some(vec!["dev".to_string(), "frozen_modules=off".to_string()])
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_to_python_sysconfig_dict() {
+        let config = PythonInterpreterConfig {
+            prefix: Some("/venv".into()),
+            base_prefix: Some("/usr".into()),
+            ..Default::default()
+        };
+
+        let vars = config.to_python_sysconfig_dict();
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars["prefix"], serde_json::json!("/venv"));
+        assert_eq!(vars["base"], serde_json::json!("/venv"));
+        assert_eq!(vars["installed_base"], serde_json::json!("/usr"));
+        assert!(!vars.contains_key("EXT_SUFFIX"));
+
+        assert!(PythonInterpreterConfig::default()
+            .to_python_sysconfig_dict()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_reproducibility_report() -> Result<(), String> {
+        let mut config = PythonInterpreterConfig::default();
+        assert!(!config.is_reproducible_build());
+        assert_eq!(
+            config
+                .reproducibility_report()
+                .blockers
+                .iter()
+                .map(|(field, _)| *field)
+                .collect::<Vec<_>>(),
+            vec!["hash_seed", "write_bytecode", "import_time"]
+        );
+
+        config.hash_seed = Some(42);
+        config.import_time = Some(true);
+        let report = config.reproducibility_report();
+        assert_eq!(
+            report.to_string(),
+            "write_bytecode: bytecode files embed source timestamps; \
+             import_time: import timings vary between runs"
+        );
+
+        config.make_reproducible(7)?;
+        assert!(config.is_reproducible_build());
+        assert_eq!(config.hash_seed, Some(7));
+        assert_eq!(
+            config.reproducibility_report().to_string(),
+            "configuration is reproducible"
+        );
+
+        config.hash_seed = Some(0);
+        assert_eq!(
+            config.reproducibility_report().to_string(),
+            "hash_seed: 0 disables hash randomization"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_reproducible() -> Result<(), String> {
+        let bools = [None, Some(false), Some(true)];
+
+        for hash_seed in [None, Some(0), Some(1), Some(42), Some(c_ulong::MAX)] {
+            for write_bytecode in bools {
+                for import_time in bools {
+                    let config = PythonInterpreterConfig {
+                        hash_seed,
+                        write_bytecode,
+                        import_time,
+                        ..Default::default()
+                    };
+
+                    let mut expected = vec![];
+                    if !matches!(hash_seed, Some(seed) if seed != 0) {
+                        expected.push("hash_seed");
+                    }
+                    if write_bytecode != Some(false) {
+                        expected.push("write_bytecode");
+                    }
+                    if import_time != Some(false) {
+                        expected.push("import_time");
+                    }
+                    assert_eq!(
+                        config
+                            .reproducibility_report()
+                            .blockers
+                            .iter()
+                            .map(|(field, _)| *field)
+                            .collect::<Vec<_>>(),
+                        expected,
+                        "{:?}",
+                        config
+                    );
+                    assert_eq!(config.is_reproducible_build(), expected.is_empty());
+
+                    for seed in [1, 7, 42, c_ulong::MAX] {
+                        let mut config = config.clone();
+                        config.make_reproducible(seed)?;
+                        assert!(config.is_reproducible_build(), "{:?}", config);
+                        assert_eq!(config.hash_seed, Some(seed));
+                    }
+
+                    let mut zero = config.clone();
+                    assert_eq!(
+                        zero.make_reproducible(0),
+                        Err("reproducible builds require a non-zero hash seed".to_string())
+                    );
+                    assert_eq!(zero, config);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_safe() {
+        let config = PythonInterpreterConfig {
+            executable: Some("/home/alice/venv/bin/python".into()),
+            prefix: Some("/opt/python".into()),
+            module_search_paths: Some(vec![
+                "/home/alice/.ssh/lib".into(),
+                "/srv/API_Token_dir/site-packages".into(),
+                "/srv/my-secrets".into(),
+                "/usr/lib/python3.10".into(),
+            ]),
+            ..Default::default()
+        };
+
+        let expected = PythonInterpreterConfig {
+            executable: Some(Path::new("<home>").join("venv/bin/python")),
+            module_search_paths: Some(vec![
+                Path::new("<home>").join("<redacted>").join("lib"),
+                PathBuf::from("/srv/<redacted>/site-packages"),
+                PathBuf::from("/srv/<redacted>"),
+                PathBuf::from("/usr/lib/python3.10"),
+            ]),
+            ..config.clone()
+        };
+
+        let output = format!("{:?}", config.debug_safe(Path::new("/home/alice")));
+        assert_eq!(output, format!("{:?}", expected));
+        assert!(!output.contains("alice"));
+        assert!(!output.contains("Token"));
+        assert!(!output.contains(".ssh"));
+
+        // Only redaction applies with a home directory of `/`.
+        assert_eq!(
+            format!("{:?}", config.debug_safe(Path::new("/"))),
+            format!(
+                "{:?}",
+                PythonInterpreterConfig {
+                    executable: config.executable.clone(),
+                    module_search_paths: Some(vec![
+                        "/home/alice/<redacted>/lib".into(),
+                        "/srv/<redacted>/site-packages".into(),
+                        "/srv/<redacted>".into(),
+                        "/usr/lib/python3.10".into(),
+                    ]),
+                    ..config.clone()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_stdout_will_buffer() {
+        let mut config = PythonInterpreterConfig::default();
+        assert_eq!(
+            config.stdout_will_buffer(Some(true)),
+            StdoutBuffering::LineBuffered
+        );
+        assert_eq!(
+            config.stdout_will_buffer(Some(false)),
+            StdoutBuffering::FullyBuffered
+        );
+        assert_eq!(
+            config.stdout_will_buffer(None),
+            StdoutBuffering::FullyBuffered
+        );
+
+        config.interactive = Some(true);
+        assert_eq!(
+            config.stdout_will_buffer(Some(false)),
+            StdoutBuffering::FullyBuffered
+        );
+
+        config.buffered_stdio = Some(true);
+        assert_eq!(
+            config.stdout_will_buffer(Some(true)),
+            StdoutBuffering::LineBuffered
+        );
+        assert_eq!(
+            config.stdout_will_buffer(None),
+            StdoutBuffering::FullyBuffered
+        );
+
+        config.buffered_stdio = Some(false);
+        for is_tty in [Some(true), Some(false), None] {
+            assert_eq!(
+                config.stdout_will_buffer(is_tty),
+                StdoutBuffering::Unbuffered
+            );
+        }
+
+        config.interactive = None;
+        assert_eq!(
+            config.stdout_will_buffer(Some(true)),
+            StdoutBuffering::Unbuffered
+        );
+    }
+}
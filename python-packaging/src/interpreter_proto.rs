@@ -0,0 +1,319 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*! Protocol Buffers representation of interpreter configurations.
+
+The schema is defined in `proto/interpreter_config.proto`, which also
+documents how it is versioned. The Rust types in [proto] are generated
+from it at build time by `prost-build`, which requires `protoc` to be
+available.
+
+Enum fields are represented by their canonical string values. `OsString`
+and path fields are represented as [proto::OsStringValue], which holds the
+UTF-8 bytes of the string, or on Unix the raw bytes of strings that aren't
+valid UTF-8. Decoding raw bytes on other platforms is an error.
+*/
+
+use {
+    crate::{
+        interpreter::{
+            Allocator, BytesWarning, CheckHashPycsMode, CoerceCLocale, PythonInterpreterConfig,
+            PythonInterpreterProfile,
+        },
+        resource::BytecodeOptimizationLevel,
+    },
+    std::{
+        ffi::{OsStr, OsString},
+        os::raw::c_ulong,
+        path::PathBuf,
+    },
+};
+
+/// Types generated from `proto/interpreter_config.proto`.
+pub mod proto {
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/pyoxidizer.interpreter_config.v1.rs"
+    ));
+}
+
+fn os_string_to_proto(value: &OsStr) -> proto::OsStringValue {
+    if let Some(value) = value.to_str() {
+        return proto::OsStringValue {
+            data: value.as_bytes().to_vec(),
+            utf8: true,
+        };
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        proto::OsStringValue {
+            data: value.as_bytes().to_vec(),
+            utf8: false,
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        proto::OsStringValue {
+            data: value.to_string_lossy().as_bytes().to_vec(),
+            utf8: true,
+        }
+    }
+}
+
+fn os_string_from_proto(value: proto::OsStringValue) -> Result<OsString, String> {
+    if value.utf8 {
+        return String::from_utf8(value.data)
+            .map(OsString::from)
+            .map_err(|_| "value marked as UTF-8 is not valid UTF-8".to_string());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Ok(OsString::from_vec(value.data))
+    }
+
+    #[cfg(not(unix))]
+    {
+        Err("non-UTF-8 values can only be decoded on Unix".to_string())
+    }
+}
+
+fn os_string_list_to_proto<'a>(values: impl Iterator<Item = &'a OsStr>) -> proto::OsStringList {
+    proto::OsStringList {
+        values: values.map(os_string_to_proto).collect(),
+    }
+}
+
+fn os_string_list_from_proto(value: proto::OsStringList) -> Result<Vec<OsString>, String> {
+    value.values.into_iter().map(os_string_from_proto).collect()
+}
+
+impl From<PythonInterpreterConfig> for proto::PythonInterpreterConfig {
+    // c_ulong is u32 on some platforms.
+    #[allow(clippy::unnecessary_cast)]
+    fn from(value: PythonInterpreterConfig) -> Self {
+        Self {
+            profile: value.profile.to_string(),
+            allocator: value.allocator.map(|v| v.to_string()),
+            configure_locale: value.configure_locale,
+            coerce_c_locale: value.coerce_c_locale.map(|v| v.to_string()),
+            coerce_c_locale_warn: value.coerce_c_locale_warn,
+            development_mode: value.development_mode,
+            isolated: value.isolated,
+            legacy_windows_fs_encoding: value.legacy_windows_fs_encoding,
+            parse_argv: value.parse_argv,
+            use_environment: value.use_environment,
+            utf8_mode: value.utf8_mode,
+            argv: value
+                .argv
+                .map(|v| os_string_list_to_proto(v.iter().map(|v| v.as_os_str()))),
+            base_exec_prefix: value
+                .base_exec_prefix
+                .map(|v| os_string_to_proto(v.as_os_str())),
+            base_executable: value
+                .base_executable
+                .map(|v| os_string_to_proto(v.as_os_str())),
+            base_prefix: value.base_prefix.map(|v| os_string_to_proto(v.as_os_str())),
+            buffered_stdio: value.buffered_stdio,
+            bytes_warning: value.bytes_warning.map(|v| v.to_string()),
+            check_hash_pycs_mode: value.check_hash_pycs_mode.map(|v| v.to_string()),
+            configure_c_stdio: value.configure_c_stdio,
+            dump_refs: value.dump_refs,
+            exec_prefix: value.exec_prefix.map(|v| os_string_to_proto(v.as_os_str())),
+            executable: value.executable.map(|v| os_string_to_proto(v.as_os_str())),
+            fault_handler: value.fault_handler,
+            filesystem_encoding: value.filesystem_encoding,
+            filesystem_errors: value.filesystem_errors,
+            hash_seed: value.hash_seed.map(|v| v as u64),
+            home: value.home.map(|v| os_string_to_proto(v.as_os_str())),
+            import_time: value.import_time,
+            inspect: value.inspect,
+            install_signal_handlers: value.install_signal_handlers,
+            interactive: value.interactive,
+            legacy_windows_stdio: value.legacy_windows_stdio,
+            malloc_stats: value.malloc_stats,
+            module_search_paths: value
+                .module_search_paths
+                .map(|v| os_string_list_to_proto(v.iter().map(|v| v.as_os_str()))),
+            optimization_level: value.optimization_level.map(i32::from),
+            parser_debug: value.parser_debug,
+            pathconfig_warnings: value.pathconfig_warnings,
+            prefix: value.prefix.map(|v| os_string_to_proto(v.as_os_str())),
+            program_name: value
+                .program_name
+                .map(|v| os_string_to_proto(v.as_os_str())),
+            pycache_prefix: value
+                .pycache_prefix
+                .map(|v| os_string_to_proto(v.as_os_str())),
+            python_path_env: value.python_path_env,
+            quiet: value.quiet,
+            run_command: value.run_command,
+            run_filename: value
+                .run_filename
+                .map(|v| os_string_to_proto(v.as_os_str())),
+            run_module: value.run_module,
+            show_ref_count: value.show_ref_count,
+            site_import: value.site_import,
+            skip_first_source_line: value.skip_first_source_line,
+            stdio_encoding: value.stdio_encoding,
+            stdio_errors: value.stdio_errors,
+            tracemalloc: value.tracemalloc,
+            user_site_directory: value.user_site_directory,
+            verbose: value.verbose,
+            warn_options: value
+                .warn_options
+                .map(|values| proto::StringList { values }),
+            write_bytecode: value.write_bytecode,
+            x_options: value.x_options.map(|values| proto::StringList { values }),
+        }
+    }
+}
+
+impl TryFrom<proto::PythonInterpreterConfig> for PythonInterpreterConfig {
+    type Error = String;
+
+    fn try_from(value: proto::PythonInterpreterConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
+            profile: PythonInterpreterProfile::try_from(value.profile.as_str())
+                .map_err(|e| format!("profile: {}", e))?,
+            allocator: value
+                .allocator
+                .map(Allocator::try_from)
+                .transpose()
+                .map_err(|e| format!("allocator: {}", e))?,
+            configure_locale: value.configure_locale,
+            coerce_c_locale: value
+                .coerce_c_locale
+                .map(CoerceCLocale::try_from)
+                .transpose()
+                .map_err(|e| format!("coerce_c_locale: {}", e))?,
+            coerce_c_locale_warn: value.coerce_c_locale_warn,
+            development_mode: value.development_mode,
+            isolated: value.isolated,
+            legacy_windows_fs_encoding: value.legacy_windows_fs_encoding,
+            parse_argv: value.parse_argv,
+            use_environment: value.use_environment,
+            utf8_mode: value.utf8_mode,
+            argv: value
+                .argv
+                .map(os_string_list_from_proto)
+                .transpose()
+                .map_err(|e| format!("argv: {}", e))?,
+            base_exec_prefix: value
+                .base_exec_prefix
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("base_exec_prefix: {}", e))?,
+            base_executable: value
+                .base_executable
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("base_executable: {}", e))?,
+            base_prefix: value
+                .base_prefix
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("base_prefix: {}", e))?,
+            buffered_stdio: value.buffered_stdio,
+            bytes_warning: value
+                .bytes_warning
+                .map(BytesWarning::try_from)
+                .transpose()
+                .map_err(|e| format!("bytes_warning: {}", e))?,
+            check_hash_pycs_mode: value
+                .check_hash_pycs_mode
+                .map(CheckHashPycsMode::try_from)
+                .transpose()
+                .map_err(|e| format!("check_hash_pycs_mode: {}", e))?,
+            configure_c_stdio: value.configure_c_stdio,
+            dump_refs: value.dump_refs,
+            exec_prefix: value
+                .exec_prefix
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("exec_prefix: {}", e))?,
+            executable: value
+                .executable
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("executable: {}", e))?,
+            fault_handler: value.fault_handler,
+            filesystem_encoding: value.filesystem_encoding,
+            filesystem_errors: value.filesystem_errors,
+            hash_seed: value
+                .hash_seed
+                .map(c_ulong::try_from)
+                .transpose()
+                .map_err(|e| format!("hash_seed: {}", e))?,
+            home: value
+                .home
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("home: {}", e))?,
+            import_time: value.import_time,
+            inspect: value.inspect,
+            install_signal_handlers: value.install_signal_handlers,
+            interactive: value.interactive,
+            legacy_windows_stdio: value.legacy_windows_stdio,
+            malloc_stats: value.malloc_stats,
+            module_search_paths: value
+                .module_search_paths
+                .map(|v| {
+                    os_string_list_from_proto(v).map(|v| v.into_iter().map(PathBuf::from).collect())
+                })
+                .transpose()
+                .map_err(|e| format!("module_search_paths: {}", e))?,
+            optimization_level: value
+                .optimization_level
+                .map(BytecodeOptimizationLevel::try_from)
+                .transpose()
+                .map_err(|e| format!("optimization_level: {}", e))?,
+            parser_debug: value.parser_debug,
+            pathconfig_warnings: value.pathconfig_warnings,
+            prefix: value
+                .prefix
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("prefix: {}", e))?,
+            program_name: value
+                .program_name
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("program_name: {}", e))?,
+            pycache_prefix: value
+                .pycache_prefix
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("pycache_prefix: {}", e))?,
+            python_path_env: value.python_path_env,
+            quiet: value.quiet,
+            run_command: value.run_command,
+            run_filename: value
+                .run_filename
+                .map(|v| os_string_from_proto(v).map(PathBuf::from))
+                .transpose()
+                .map_err(|e| format!("run_filename: {}", e))?,
+            run_module: value.run_module,
+            show_ref_count: value.show_ref_count,
+            site_import: value.site_import,
+            skip_first_source_line: value.skip_first_source_line,
+            stdio_encoding: value.stdio_encoding,
+            stdio_errors: value.stdio_errors,
+            tracemalloc: value.tracemalloc,
+            user_site_directory: value.user_site_directory,
+            verbose: value.verbose,
+            warn_options: value.warn_options.map(|v| v.values),
+            write_bytecode: value.write_bytecode,
+            x_options: value.x_options.map(|v| v.values),
+        })
+    }
+}
@@ -15,6 +15,8 @@ and packaging facilities.
 pub mod bytecode;
 pub mod filesystem_scanning;
 pub mod interpreter;
+#[cfg(feature = "prost")]
+pub mod interpreter_proto;
 pub mod libpython;
 pub mod licensing;
 pub mod location;
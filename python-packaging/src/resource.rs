@@ -31,8 +31,10 @@ use serde::{Deserialize, Serialize};
 /// An optimization level for Python bytecode.
 ///
 /// Serialization type: `int`
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum BytecodeOptimizationLevel {
     /// Optimization level 0.
     ///
@@ -62,6 +64,17 @@ impl BytecodeOptimizationLevel {
             BytecodeOptimizationLevel::Two => ".opt-2",
         }
     }
+
+    /// Obtain the level corresponding to a count of `-O` command line flags.
+    ///
+    /// Counts above 2 are clamped to [Self::Two], mirroring CPython.
+    pub fn from_flag_count(n: u32) -> Self {
+        match n {
+            0 => BytecodeOptimizationLevel::Zero,
+            1 => BytecodeOptimizationLevel::One,
+            _ => BytecodeOptimizationLevel::Two,
+        }
+    }
 }
 
 impl TryFrom<i32> for BytecodeOptimizationLevel {
@@ -87,6 +100,35 @@ impl From<BytecodeOptimizationLevel> for i32 {
     }
 }
 
+/// Values above 2 are clamped to [BytecodeOptimizationLevel::Two].
+impl From<u8> for BytecodeOptimizationLevel {
+    fn from(i: u8) -> Self {
+        Self::from_flag_count(i as u32)
+    }
+}
+
+impl From<BytecodeOptimizationLevel> for u8 {
+    fn from(level: BytecodeOptimizationLevel) -> Self {
+        match level {
+            BytecodeOptimizationLevel::Zero => 0,
+            BytecodeOptimizationLevel::One => 1,
+            BytecodeOptimizationLevel::Two => 2,
+        }
+    }
+}
+
+impl PartialOrd for BytecodeOptimizationLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BytecodeOptimizationLevel {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        i32::from(*self).cmp(&i32::from(*other))
+    }
+}
+
 /// A Python module defined via source code.
 #[derive(Clone, Debug, PartialEq)]
 pub struct PythonModuleSource {
@@ -1050,4 +1092,55 @@ mod tests {
                 .join("resource.txt")
         );
     }
+
+    #[test]
+    fn test_bytecode_optimization_level_ord() {
+        use BytecodeOptimizationLevel::*;
+
+        assert!(Zero < One);
+        assert!(Zero < Two);
+        assert!(One < Two);
+        assert!(One > Zero);
+        assert!(Two > Zero);
+        assert!(Two > One);
+
+        for level in [Zero, One, Two] {
+            assert_eq!(level.cmp(&level), std::cmp::Ordering::Equal);
+        }
+
+        assert!(Two >= One);
+        assert!(One >= One);
+
+        let mut levels = vec![Two, Zero, One, Zero];
+        levels.sort();
+        assert_eq!(levels, vec![Zero, Zero, One, Two]);
+    }
+
+    #[test]
+    fn test_bytecode_optimization_level_from_flag_count() {
+        use BytecodeOptimizationLevel::*;
+
+        assert_eq!(BytecodeOptimizationLevel::from_flag_count(0), Zero);
+        assert_eq!(BytecodeOptimizationLevel::from_flag_count(1), One);
+        assert_eq!(BytecodeOptimizationLevel::from_flag_count(2), Two);
+        assert_eq!(BytecodeOptimizationLevel::from_flag_count(3), Two);
+        assert_eq!(BytecodeOptimizationLevel::from_flag_count(u32::MAX), Two);
+    }
+
+    #[test]
+    fn test_bytecode_optimization_level_u8() {
+        use BytecodeOptimizationLevel::*;
+
+        assert_eq!(BytecodeOptimizationLevel::from(0u8), Zero);
+        assert_eq!(BytecodeOptimizationLevel::from(1u8), One);
+        assert_eq!(BytecodeOptimizationLevel::from(2u8), Two);
+        assert_eq!(BytecodeOptimizationLevel::from(3u8), Two);
+        assert_eq!(BytecodeOptimizationLevel::from(u8::MAX), Two);
+
+        for level in [Zero, One, Two] {
+            let value: u8 = level.into();
+            assert_eq!(value as i32, i32::from(level));
+            assert_eq!(BytecodeOptimizationLevel::from(value), level);
+        }
+    }
 }
@@ -0,0 +1,59 @@
+// generated by PythonInterpreterConfig::embed_in_generated_rust_code(); checksum fnv1a64:bfa5a8d969f6a5e4
+python_packaging::interpreter::PythonInterpreterConfig {
+    profile: python_packaging::interpreter::PythonInterpreterProfile::Isolated,
+    allocator: Some(python_packaging::interpreter::Allocator::PyMalloc),
+    configure_locale: Some(false),
+    coerce_c_locale: Some(python_packaging::interpreter::CoerceCLocale::LCCtype),
+    coerce_c_locale_warn: Some(false),
+    development_mode: Some(false),
+    isolated: Some(true),
+    legacy_windows_fs_encoding: Some(false),
+    parse_argv: Some(false),
+    use_environment: Some(false),
+    utf8_mode: Some(true),
+    argv: Some(vec![std::ffi::OsString::from("python")]),
+    base_exec_prefix: Some(std::path::PathBuf::from("/python")),
+    base_executable: Some(std::path::PathBuf::from("/python/bin/python")),
+    base_prefix: Some(std::path::PathBuf::from("/python")),
+    buffered_stdio: Some(true),
+    bytes_warning: Some(python_packaging::interpreter::BytesWarning::Warn),
+    check_hash_pycs_mode: Some(python_packaging::interpreter::CheckHashPycsMode::Never),
+    configure_c_stdio: Some(false),
+    dump_refs: Some(false),
+    exec_prefix: Some(std::path::PathBuf::from("/python")),
+    executable: Some(std::path::PathBuf::from("/python/bin/python")),
+    fault_handler: Some(false),
+    filesystem_encoding: Some("utf-8".to_string()),
+    filesystem_errors: Some("surrogateescape".to_string()),
+    hash_seed: Some(12345),
+    home: Some(std::path::PathBuf::from("/python")),
+    import_time: Some(false),
+    inspect: Some(false),
+    install_signal_handlers: Some(false),
+    interactive: Some(false),
+    legacy_windows_stdio: Some(false),
+    malloc_stats: Some(false),
+    module_search_paths: Some(vec![std::path::PathBuf::from("/python/lib")]),
+    optimization_level: Some(python_packaging::resource::BytecodeOptimizationLevel::Zero),
+    parser_debug: Some(false),
+    pathconfig_warnings: Some(false),
+    prefix: Some(std::path::PathBuf::from("/python")),
+    program_name: Some(std::path::PathBuf::from("/python/bin/python")),
+    pycache_prefix: Some(std::path::PathBuf::from("/python/pycache")),
+    python_path_env: Some("".to_string()),
+    quiet: Some(true),
+    run_command: None,
+    run_filename: None,
+    run_module: None,
+    show_ref_count: Some(false),
+    site_import: Some(false),
+    skip_first_source_line: Some(false),
+    stdio_encoding: Some("utf-8".to_string()),
+    stdio_errors: Some("strict".to_string()),
+    tracemalloc: Some(false),
+    user_site_directory: Some(false),
+    verbose: Some(false),
+    warn_options: Some(vec![]),
+    write_bytecode: Some(false),
+    x_options: Some(vec![]),
+}
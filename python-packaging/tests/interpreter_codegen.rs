@@ -0,0 +1,51 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use python_packaging::interpreter::PythonInterpreterConfig;
+
+/// `data/interpreter_config_for_testing.rs` is the output of
+/// `embed_in_generated_rust_code()` for `PythonInterpreterConfig::for_testing()`.
+const GENERATED: &str = include_str!("data/interpreter_config_for_testing.rs");
+
+#[test]
+fn generated_code_is_current() {
+    assert_eq!(
+        PythonInterpreterConfig::for_testing().embed_in_generated_rust_code(),
+        GENERATED
+    );
+}
+
+#[test]
+fn generated_code_compiles() {
+    let config: PythonInterpreterConfig = include!("data/interpreter_config_for_testing.rs");
+
+    assert_eq!(config, PythonInterpreterConfig::for_testing());
+}
+
+#[test]
+fn generated_code_checksum() {
+    let code = PythonInterpreterConfig::default().embed_in_generated_rust_code();
+    let (comment, literal) = code.split_once('\n').unwrap();
+
+    assert!(comment.starts_with("// generated by "));
+    assert!(comment.contains(" checksum fnv1a64:"));
+    assert!(literal.starts_with("python_packaging::interpreter::PythonInterpreterConfig {\n"));
+    assert!(literal.contains("    run_command: None,\n"));
+
+    let other = PythonInterpreterConfig {
+        run_command: Some("print(\"hello\")\n".to_string()),
+        ..Default::default()
+    }
+    .embed_in_generated_rust_code();
+    let (other_comment, other_literal) = other.split_once('\n').unwrap();
+
+    assert_ne!(comment, other_comment);
+    assert!(
+        other_literal.contains("    run_command: Some(\"print(\\\"hello\\\")\\n\".to_string()),\n")
+    );
+}
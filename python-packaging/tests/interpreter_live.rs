@@ -0,0 +1,46 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use {
+    python_packaging::interpreter::{PythonInterpreterConfig, PythonInterpreterProfile},
+    std::path::{Path, PathBuf},
+};
+
+/// Find a `python3` executable on `PATH`.
+fn find_python() -> Option<PathBuf> {
+    let name = if cfg!(windows) {
+        "python.exe"
+    } else {
+        "python3"
+    };
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|p| p.join(name))
+        .find(|p| p.is_file())
+}
+
+// Requires a Python interpreter on PATH. Run with `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn from_running_interpreter() -> Result<(), String> {
+    let python = find_python().ok_or_else(|| "no Python executable found on PATH".to_string())?;
+
+    let config = PythonInterpreterConfig::from_running_interpreter(&python)?;
+
+    assert_eq!(config.profile, PythonInterpreterProfile::Python);
+    assert!(config.executable.is_some());
+    assert!(config.prefix.is_some());
+    assert!(config.module_search_paths.is_some());
+    assert!(config.filesystem_encoding.is_some());
+
+    assert!(
+        PythonInterpreterConfig::from_running_interpreter(Path::new("/does/not/exist")).is_err()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use {
+    python_packaging::interpreter::PythonInterpreterConfig,
+    std::path::{Path, PathBuf},
+};
+
+#[test]
+fn no_paths() {
+    let config = PythonInterpreterConfig::default();
+    assert_eq!(config.verify_paths_exist(Path::new("/nonexistent")), Ok(()));
+    config.warn_on_missing_paths(Path::new("/nonexistent"));
+}
+
+#[test]
+fn relative_and_absolute_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let td = tempfile::Builder::new()
+        .prefix("python-packaging-test")
+        .tempdir()?;
+    let base = td.path();
+
+    std::fs::create_dir_all(base.join("lib"))?;
+    std::fs::create_dir_all(base.join("prefix"))?;
+    std::fs::write(base.join("main.py"), b"print('hello')")?;
+
+    let config = PythonInterpreterConfig {
+        home: Some(base.join("prefix")),
+        run_filename: Some(PathBuf::from("main.py")),
+        module_search_paths: Some(vec![PathBuf::from("lib"), base.join("lib")]),
+        ..Default::default()
+    };
+    assert_eq!(config.verify_paths_exist(base), Ok(()));
+    config.warn_on_missing_paths(base);
+
+    // Relative paths are resolved against the base directory.
+    assert_eq!(
+        config.verify_paths_exist(&base.join("lib")),
+        Err(vec![
+            base.join("lib").join("main.py"),
+            base.join("lib").join("lib")
+        ])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn missing_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let td = tempfile::Builder::new()
+        .prefix("python-packaging-test")
+        .tempdir()?;
+    let base = td.path();
+
+    std::fs::create_dir_all(base.join("lib"))?;
+
+    let config = PythonInterpreterConfig {
+        executable: Some(PathBuf::from("bin/python")),
+        home: Some(base.join("prefx")),
+        module_search_paths: Some(vec![PathBuf::from("lib"), PathBuf::from("lib2")]),
+        program_name: Some(PathBuf::from("python")),
+        pycache_prefix: Some(PathBuf::from("pycache")),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        config.verify_paths_exist(base),
+        Err(vec![
+            base.join("bin/python"),
+            base.join("prefx"),
+            base.join("lib2"),
+        ])
+    );
+    config.warn_on_missing_paths(base);
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "prost")]
+
+use {
+    prost::Message,
+    python_packaging::{
+        interpreter::{Allocator, PythonInterpreterConfig},
+        interpreter_proto::proto,
+    },
+};
+
+fn round_trip(config: &PythonInterpreterConfig) -> PythonInterpreterConfig {
+    let data = proto::PythonInterpreterConfig::from(config.clone()).encode_to_vec();
+    let message = proto::PythonInterpreterConfig::decode(data.as_slice()).unwrap();
+
+    PythonInterpreterConfig::try_from(message).unwrap()
+}
+
+#[test]
+fn default() {
+    let config = PythonInterpreterConfig::default();
+    assert_eq!(round_trip(&config), config);
+}
+
+#[test]
+fn for_testing() {
+    let config = PythonInterpreterConfig {
+        allocator: Some(Allocator::PyMallocDebug),
+        run_module: Some("main".to_string()),
+        warn_options: Some(vec![]),
+        ..PythonInterpreterConfig::for_testing()
+    };
+    assert_eq!(round_trip(&config), config);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8() {
+    use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+    let config = PythonInterpreterConfig {
+        argv: Some(vec![OsString::from_vec(vec![0x66, 0x6f, 0xff])]),
+        home: Some(OsString::from_vec(vec![0x2f, 0xfe]).into()),
+        ..Default::default()
+    };
+    assert_eq!(round_trip(&config), config);
+}
+
+#[test]
+fn invalid_values() {
+    let message = proto::PythonInterpreterConfig {
+        profile: "isolated".to_string(),
+        allocator: Some("bogus".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        PythonInterpreterConfig::try_from(message),
+        Err("allocator: bogus is not a valid allocator value; use 'not-set', 'default', 'debug', 'malloc', 'malloc-debug', 'py-malloc', or 'py-malloc-debug'".to_string())
+    );
+
+    let message = proto::PythonInterpreterConfig {
+        profile: "isolated".to_string(),
+        home: Some(proto::OsStringValue {
+            data: vec![0xff],
+            utf8: true,
+        }),
+        ..Default::default()
+    };
+    assert!(PythonInterpreterConfig::try_from(message).is_err());
+}
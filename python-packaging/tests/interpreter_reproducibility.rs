@@ -0,0 +1,129 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Property tests for reproducible build settings.
+//!
+//! Inputs are generated from the `Arbitrary` impls, fed with a fixed
+//! pseudo-random byte stream so failures are reproducible.
+
+#![cfg(feature = "arbitrary")]
+
+use {
+    arbitrary::{Arbitrary, Unstructured},
+    python_packaging::interpreter::PythonInterpreterConfig,
+    std::os::raw::c_ulong,
+};
+
+const CASES: u64 = 1000;
+
+/// Run `f` with inputs generated from `CASES` distinct byte streams.
+fn check<T: for<'a> Arbitrary<'a>>(f: impl Fn(T)) {
+    for case in 0..CASES {
+        // xorshift64*, seeded per case.
+        let mut state = case.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        let data = (0..4096)
+            .map(|_| {
+                state ^= state >> 12;
+                state ^= state << 25;
+                state ^= state >> 27;
+                (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 56) as u8
+            })
+            .collect::<Vec<_>>();
+
+        let mut u = Unstructured::new(&data);
+        f(T::arbitrary(&mut u).expect("generating input"));
+    }
+}
+
+/// A generated config with an independently generated `hash_seed`.
+///
+/// The `PythonInterpreterConfig` impl never produces a seed of `0`, which
+/// these tests need to cover.
+fn with_hash_seed(
+    mut config: PythonInterpreterConfig,
+    hash_seed: Option<c_ulong>,
+) -> PythonInterpreterConfig {
+    config.hash_seed = hash_seed;
+    config
+}
+
+fn blockers(config: &PythonInterpreterConfig) -> Vec<&'static str> {
+    config
+        .reproducibility_report()
+        .blockers
+        .into_iter()
+        .map(|(field, _)| field)
+        .collect()
+}
+
+#[test]
+fn make_reproducible_is_reproducible() {
+    check(
+        |(config, hash_seed, seed): (PythonInterpreterConfig, Option<c_ulong>, c_ulong)| {
+            let original = with_hash_seed(config, hash_seed);
+            let mut config = original.clone();
+
+            // Covered by make_reproducible_rejects_zero_seed.
+            if seed == 0 {
+                return;
+            }
+
+            config.make_reproducible(seed).unwrap();
+            assert!(config.is_reproducible_build(), "{:?}", config);
+            assert!(blockers(&config).is_empty());
+            assert_eq!(config.hash_seed, Some(seed));
+
+            // Nothing besides the reported fields changes.
+            assert_eq!(
+                PythonInterpreterConfig {
+                    hash_seed: original.hash_seed,
+                    write_bytecode: original.write_bytecode,
+                    import_time: original.import_time,
+                    ..config
+                },
+                original
+            );
+        },
+    );
+}
+
+#[test]
+fn make_reproducible_rejects_zero_seed() {
+    check(
+        |(config, hash_seed): (PythonInterpreterConfig, Option<c_ulong>)| {
+            let original = with_hash_seed(config, hash_seed);
+            let mut config = original.clone();
+            assert!(config.make_reproducible(0).is_err());
+            assert_eq!(config, original);
+        },
+    );
+}
+
+#[test]
+fn blockers_match_fields() {
+    check(
+        |(config, hash_seed): (PythonInterpreterConfig, Option<c_ulong>)| {
+            let config = with_hash_seed(config, hash_seed);
+            let blockers = blockers(&config);
+
+            assert_eq!(
+                blockers.contains(&"hash_seed"),
+                matches!(config.hash_seed, None | Some(0))
+            );
+            assert_eq!(
+                blockers.contains(&"write_bytecode"),
+                config.write_bytecode != Some(false)
+            );
+            assert_eq!(
+                blockers.contains(&"import_time"),
+                config.import_time != Some(false)
+            );
+            assert_eq!(config.is_reproducible_build(), blockers.is_empty());
+        },
+    );
+}
@@ -0,0 +1,58 @@
+// Copyright 2022 Gregory Szorc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "schemars")]
+
+use {
+    anyhow::{anyhow, Result},
+    python_packaging::interpreter::PythonInterpreterConfig,
+    serde_json::json,
+};
+
+#[test]
+fn json_schema_validates_config() -> Result<()> {
+    let schema = serde_json::to_value(PythonInterpreterConfig::json_schema())?;
+    let schema = jsonschema::JSONSchema::compile(&schema)
+        .map_err(|e| anyhow!("error compiling schema: {}", e))?;
+
+    let config = json!({
+        "profile": "python",
+        "allocator": "malloc",
+        "coerce_c_locale": "LC_CTYPE",
+        "isolated": false,
+        "home": "/opt/python",
+        "hash_seed": 42,
+        "module_search_paths": ["/opt/python/lib"],
+        "optimization_level": "1",
+        "run_module": "http.server",
+        "x_options": ["dev", "utf8"],
+    });
+    assert!(schema.is_valid(&config));
+
+    // The schema should agree with what the config actually serializes to.
+    let config: PythonInterpreterConfig = serde_json::from_value(config)?;
+    assert!(schema.is_valid(&serde_json::to_value(&config)?));
+
+    assert!(!schema.is_valid(&json!({"allocator": "bad-value"})));
+    assert!(!schema.is_valid(&json!({"isolated": "yes"})));
+    assert!(!schema.is_valid(&json!({"optimization_level": "3"})));
+
+    Ok(())
+}
+
+#[test]
+fn json_schema_has_descriptions() -> Result<()> {
+    let schema = serde_json::to_value(PythonInterpreterConfig::json_schema())?;
+
+    let description = schema["properties"]["home"]["description"]
+        .as_str()
+        .ok_or_else(|| anyhow!("home field has no description"))?;
+    assert!(description.contains("PyConfig.home"), "{}", description);
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+/*! Code signing binaries embedding a Python interpreter. */
+
+use {
+    apple_codesign::{
+        AppleCodesignError, CodeSignatureFlags, SettingsScope, SigningSettings, UnifiedSigner,
+    },
+    python_packaging::interpreter::PythonInterpreterConfig,
+    std::path::{Path, PathBuf},
+    thiserror::Error,
+};
+
+/// Entitlement allowing a hardened runtime process to create writable and executable memory.
+///
+/// Needed by runtimes that generate machine code at run time, such as JITs
+/// and libffi closures (used by Python's `ctypes`).
+pub const ENTITLEMENT_ALLOW_UNSIGNED_EXECUTABLE_MEMORY: &str =
+    "com.apple.security.cs.allow-unsigned-executable-memory";
+
+#[derive(Debug, Error)]
+pub enum SigningError {
+    #[error("path to sign does not exist: {0}")]
+    MissingPath(PathBuf),
+
+    #[error("interpreter config doesn't define executable or base_executable")]
+    NoBinaries,
+
+    #[error("failed to sign {path}: {source}")]
+    Codesign {
+        path: PathBuf,
+        source: Box<AppleCodesignError>,
+    },
+
+    #[error("invalid signing settings: {0}")]
+    Settings(Box<AppleCodesignError>),
+}
+
+/// Render an entitlements property list enabling the given boolean entitlements.
+pub fn entitlements_plist(entitlements: &[&str]) -> String {
+    let mut plist = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+        "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+        "<plist version=\"1.0\">\n",
+        "<dict>\n",
+    ));
+
+    for entitlement in entitlements {
+        plist.push_str(&format!("    <key>{}</key>\n    <true/>\n", entitlement));
+    }
+
+    plist.push_str("</dict>\n</plist>\n");
+
+    plist
+}
+
+/// Obtain [SigningSettings] for binaries embedding a Python interpreter.
+///
+/// The settings enable the hardened runtime and the entitlements an embedded
+/// Python runtime needs under it. No signing key is set, which results in
+/// ad-hoc signing. That is sufficient to run on Apple Silicon machines, where
+/// the kernel refuses to execute unsigned arm64 code. Call
+/// [SigningSettings::set_signing_key()] on the result to sign with a
+/// certificate instead.
+pub fn embedded_python_signing_settings<'key>() -> Result<SigningSettings<'key>, SigningError> {
+    let mut settings = SigningSettings::default();
+
+    settings.set_code_signature_flags(SettingsScope::Main, CodeSignatureFlags::RUNTIME);
+    settings
+        .set_entitlements_xml(
+            SettingsScope::Main,
+            entitlements_plist(&[ENTITLEMENT_ALLOW_UNSIGNED_EXECUTABLE_MEMORY]),
+        )
+        .map_err(|e| SigningError::Settings(Box::new(e)))?;
+
+    Ok(settings)
+}
+
+/// Sign Mach-O binaries in place.
+///
+/// Existing signatures are replaced. Signing is performed by the
+/// `apple-codesign` crate, so this works on any platform and doesn't require
+/// Apple's `codesign` tool.
+pub fn codesign_binaries<P: AsRef<Path>>(
+    paths: &[P],
+    settings: &SigningSettings,
+) -> Result<(), SigningError> {
+    let signer = UnifiedSigner::new(settings.clone());
+
+    for path in paths {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Err(SigningError::MissingPath(path.to_path_buf()));
+        }
+
+        signer
+            .sign_macho(path, path)
+            .map_err(|source| SigningError::Codesign {
+                path: path.to_path_buf(),
+                source: Box::new(source),
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Obtain the binaries of an interpreter config that need signing.
+///
+/// These are [PythonInterpreterConfig::executable] and
+/// [PythonInterpreterConfig::base_executable], if defined. A path is only
+/// returned once if both fields refer to it.
+pub fn interpreter_config_binaries(config: &PythonInterpreterConfig) -> Vec<PathBuf> {
+    let mut paths = vec![];
+
+    for path in [&config.executable, &config.base_executable]
+        .into_iter()
+        .flatten()
+    {
+        if !paths.contains(path) {
+            paths.push(path.clone());
+        }
+    }
+
+    paths
+}
+
+/// Sign the binaries of an interpreter config so they run on Apple Silicon.
+///
+/// The binaries from [interpreter_config_binaries()] are ad-hoc signed in
+/// place with [embedded_python_signing_settings()]. Use
+/// [codesign_binaries()] to sign with other settings.
+pub fn sign_for_apple_silicon(config: &PythonInterpreterConfig) -> Result<(), SigningError> {
+    let paths = interpreter_config_binaries(config);
+
+    if paths.is_empty() {
+        return Err(SigningError::NoBinaries);
+    }
+
+    codesign_binaries(&paths, &embedded_python_signing_settings()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entitlements_plist_contents() {
+        let plist = entitlements_plist(&[ENTITLEMENT_ALLOW_UNSIGNED_EXECUTABLE_MEMORY]);
+
+        assert!(plist.starts_with("<?xml"));
+        assert!(plist.contains(
+            "<key>com.apple.security.cs.allow-unsigned-executable-memory</key>\n    <true/>\n"
+        ));
+        assert!(plist.ends_with("</dict>\n</plist>\n"));
+    }
+
+    #[test]
+    fn embedded_python_settings() {
+        let settings = embedded_python_signing_settings().unwrap();
+
+        assert_eq!(
+            settings.code_signature_flags(SettingsScope::Main),
+            Some(CodeSignatureFlags::RUNTIME)
+        );
+        assert!(settings
+            .entitlements_xml(SettingsScope::Main)
+            .unwrap()
+            .unwrap()
+            .contains(ENTITLEMENT_ALLOW_UNSIGNED_EXECUTABLE_MEMORY));
+        assert!(settings.signing_key().is_none());
+    }
+
+    #[test]
+    fn config_binaries() {
+        assert!(interpreter_config_binaries(&PythonInterpreterConfig::default()).is_empty());
+        assert!(matches!(
+            sign_for_apple_silicon(&PythonInterpreterConfig::default()),
+            Err(SigningError::NoBinaries)
+        ));
+
+        let config = PythonInterpreterConfig {
+            executable: Some("/app/bin/app".into()),
+            base_executable: Some("/app/bin/app".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            interpreter_config_binaries(&config),
+            vec![PathBuf::from("/app/bin/app")]
+        );
+
+        let config = PythonInterpreterConfig {
+            executable: Some("/app/bin/app".into()),
+            base_executable: Some("/app/bin/python".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            interpreter_config_binaries(&config),
+            vec![
+                PathBuf::from("/app/bin/app"),
+                PathBuf::from("/app/bin/python")
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("missing");
+
+        let config = PythonInterpreterConfig {
+            executable: Some(path.clone()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            sign_for_apple_silicon(&config),
+            Err(SigningError::MissingPath(p)) if p == path
+        ));
+    }
+
+    #[test]
+    fn not_macho() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("script");
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+
+        assert!(matches!(
+            codesign_binaries(&[&path], &embedded_python_signing_settings().unwrap()),
+            Err(SigningError::Codesign { path: p, .. }) if p == path
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Signing real Mach-O binaries.
+//!
+//! These tests copy binaries shipped with macOS, so they only run there.
+//! Results are checked with Apple's `codesign` tool as well as
+//! `apple-codesign`'s verifier.
+
+#![cfg(target_os = "macos")]
+
+use {
+    python_packaging::interpreter::PythonInterpreterConfig,
+    std::{path::Path, process::Command},
+    tugger_apple::{sign_for_apple_silicon, ENTITLEMENT_ALLOW_UNSIGNED_EXECUTABLE_MEMORY},
+};
+
+fn assert_signed(path: &Path) {
+    assert!(
+        apple_codesign::verify_path(path).is_empty(),
+        "{}",
+        path.display()
+    );
+
+    assert!(Command::new("codesign")
+        .arg("--verify")
+        .arg("--strict")
+        .arg(path)
+        .status()
+        .unwrap()
+        .success());
+
+    let output = Command::new("codesign")
+        .arg("--display")
+        .arg("--entitlements")
+        .arg("-")
+        .arg(path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains(ENTITLEMENT_ALLOW_UNSIGNED_EXECUTABLE_MEMORY));
+
+    let output = Command::new("codesign")
+        .arg("--display")
+        .arg("--verbose")
+        .arg(path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("(runtime)"));
+}
+
+#[test]
+fn sign_interpreter_config_binaries() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let executable = temp_dir.path().join("app");
+    let base_executable = temp_dir.path().join("python");
+    std::fs::copy("/usr/bin/true", &executable).unwrap();
+    std::fs::copy("/usr/bin/true", &base_executable).unwrap();
+
+    let config = PythonInterpreterConfig {
+        executable: Some(executable.clone()),
+        base_executable: Some(base_executable.clone()),
+        ..Default::default()
+    };
+
+    sign_for_apple_silicon(&config).unwrap();
+
+    assert_signed(&executable);
+    assert_signed(&base_executable);
+
+    // Signed binaries still run.
+    assert!(Command::new(&executable).status().unwrap().success());
+}